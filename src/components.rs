@@ -1,3 +1,4 @@
+pub(crate) mod admin;
 pub(crate) mod common;
 pub(crate) mod dashboard;
 pub(crate) mod features;
@@ -0,0 +1,5 @@
+pub(crate) mod moderation;
+pub(crate) mod stats;
+pub(crate) mod usage;
+pub(crate) mod user;
+pub(crate) mod users;
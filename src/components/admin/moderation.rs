@@ -0,0 +1,144 @@
+use crate::components::toast::manager::{ToastManager, ToastType};
+use crate::server::moderation::controller::{list_moderation_cases, resolve_moderation_case};
+use crate::server::moderation::model::{
+    ContentKind, ContentSource, ModerationAction, ModerationCase, ReviewStatus,
+};
+use crate::server::moderation::request::{ListCasesRequest, ResolveCaseRequest};
+use crate::theme::Theme;
+use chrono::Duration;
+use dioxus::prelude::*;
+
+#[component]
+pub fn ModerationPanel() -> Element {
+    let theme = use_context::<Signal<Theme>>();
+    let dark_mode = theme() == Theme::Dark;
+    let mut toasts_manager = use_context::<Signal<ToastManager>>();
+    let mut status = use_signal(|| ReviewStatus::Pending);
+    let mut cases = use_signal(Vec::<ModerationCase>::new);
+
+    let _ = use_resource(move || async move {
+        if let Ok(response) = list_moderation_cases(ListCasesRequest { status: status() }).await {
+            cases.set(response.data);
+        }
+    });
+
+    let resolve = move |case_id: String, approve: bool| {
+        spawn(async move {
+            match resolve_moderation_case(ResolveCaseRequest { case_id, approve }).await {
+                Ok(response) => {
+                    let resolved = response.data;
+                    cases.set(
+                        cases()
+                            .into_iter()
+                            .filter(|case| case.id != resolved.id)
+                            .collect(),
+                    );
+                }
+                Err(err) => {
+                    toasts_manager.set(
+                        toasts_manager()
+                            .add_toast(
+                                "Error".into(),
+                                err.to_string(),
+                                ToastType::Error,
+                                Some(Duration::seconds(5)),
+                            )
+                            .clone(),
+                    );
+                }
+            }
+        });
+    };
+
+    rsx! {
+        div { class: "flex flex-col md:flex-row md:items-center md:justify-between mb-4 gap-4",
+            h2 { class: "text-xl font-semibold", "Moderation Queue" }
+            div { class: "flex space-x-2",
+                for (label, value) in [
+                    ("Pending", ReviewStatus::Pending),
+                    ("Approved", ReviewStatus::Approved),
+                    ("Rejected", ReviewStatus::Rejected),
+                ] {
+                    button {
+                        class: format!(
+                            "px-3 py-1 rounded {}",
+                            if status() == value { "bg-blue-500 text-white" } else { "bg-gray-500 text-white" }
+                        ),
+                        onclick: move |_| status.set(value),
+                        "{label}"
+                    }
+                }
+            }
+        }
+        if cases().is_empty() {
+            p { class: "text-sm text-gray-500", "Nothing here." }
+        } else {
+            table { class: "w-full text-left text-sm",
+                thead {
+                    tr {
+                        th { class: "p-2", "Content" }
+                        th { class: "p-2", "Text" }
+                        th { class: "p-2", "Reasons" }
+                        th { class: "p-2", "Date" }
+                        th { class: "p-2" }
+                    }
+                }
+                tbody {
+                    for case in cases() {
+                        tr { class: "border-t border-gray-600 align-top",
+                            td { class: "p-2 whitespace-nowrap",
+                                p {
+                                    match case.kind {
+                                        ContentKind::Og => "OG",
+                                        ContentKind::Message => "Chat message",
+                                    }
+                                    " {case.field}"
+                                }
+                                p { class: "text-xs text-gray-500",
+                                    match case.source {
+                                        ContentSource::User => "Written by user",
+                                        ContentSource::Ai => "Written by AI",
+                                    }
+                                }
+                                if case.action == ModerationAction::Block {
+                                    span { class: "px-2 py-0.5 rounded-full text-xs font-bold bg-red-500 text-white", "Blocked" }
+                                }
+                            }
+                            td { class: format!("p-2 max-w-md break-words {}", if dark_mode { "text-gray-200" } else { "text-gray-800" }),
+                                "{case.text}"
+                            }
+                            td { class: "p-2",
+                                ul { class: "list-disc ml-4",
+                                    for reason in case.reasons.clone() {
+                                        li { "{reason}" }
+                                    }
+                                }
+                            }
+                            td { class: "p-2 whitespace-nowrap", "{case.created_at.format(\"%B %d, %Y\")}" }
+                            td { class: "p-2 whitespace-nowrap",
+                                if case.status == ReviewStatus::Pending {
+                                    button {
+                                        class: "px-3 py-1 rounded bg-green-600 text-white mr-2",
+                                        onclick: {
+                                            let case_id = case.id.to_string();
+                                            move |_| resolve(case_id.clone(), true)
+                                        },
+                                        "Approve"
+                                    }
+                                    button {
+                                        class: "px-3 py-1 rounded bg-red-600 text-white",
+                                        onclick: {
+                                            let case_id = case.id.to_string();
+                                            move |_| resolve(case_id.clone(), false)
+                                        },
+                                        "Reject"
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,86 @@
+use crate::components::spinner::Spinner;
+use crate::components::spinner::SpinnerSize;
+use crate::server::admin::controller::stats_history;
+use crate::server::admin::request::StatsHistoryRequest;
+use crate::server::admin::response::StatsHistoryResponse;
+use crate::theme::Theme;
+use dioxus::prelude::*;
+
+const HISTORY_DAYS: u32 = 30;
+
+#[component]
+pub fn StatsPanel() -> Element {
+    let theme = use_context::<Signal<Theme>>();
+    let dark_mode = theme() == Theme::Dark;
+    let mut stats = use_signal(|| None::<StatsHistoryResponse>);
+    let mut error = use_signal(|| None::<String>);
+
+    let _ = use_resource(move || async move {
+        match stats_history(StatsHistoryRequest { days: HISTORY_DAYS }).await {
+            Ok(response) => stats.set(Some(response.data)),
+            Err(err) => error.set(Some(err.to_string())),
+        }
+    });
+
+    let card = format!(
+        "p-4 rounded-lg shadow {}",
+        if dark_mode {
+            "bg-gray-700"
+        } else {
+            "bg-gray-100"
+        }
+    );
+
+    rsx! {
+        h2 { class: "text-xl font-semibold mb-4", "Overview" }
+        if let Some(stats) = stats() {
+            div { class: "grid grid-cols-1 sm:grid-cols-3 gap-4 mb-6",
+                div { class: "{card}",
+                    p { class: "text-sm text-gray-500", "Users" }
+                    p { class: "text-2xl font-bold", "{stats.current.users}" }
+                }
+                div { class: "{card}",
+                    p { class: "text-sm text-gray-500", "OGs" }
+                    p { class: "text-2xl font-bold", "{stats.current.ogs}" }
+                }
+                div { class: "{card}",
+                    p { class: "text-sm text-gray-500", "Staff" }
+                    p { class: "text-2xl font-bold", "{stats.current.staff_users}" }
+                }
+            }
+            h3 { class: "text-lg font-semibold mb-2", "Last {HISTORY_DAYS} days" }
+            table { class: "w-full text-left text-sm",
+                thead {
+                    tr {
+                        th { class: "p-2", "Day" }
+                        th { class: "p-2", "Users" }
+                        th { class: "p-2", "OGs" }
+                        th { class: "p-2", "Staff" }
+                    }
+                }
+                tbody {
+                    for snapshot in stats.history.iter().rev() {
+                        tr { class: "border-t border-gray-600",
+                            td { class: "p-2", "{snapshot.day}" }
+                            td { class: "p-2", "{snapshot.users}" }
+                            td { class: "p-2", "{snapshot.ogs}" }
+                            td { class: "p-2", "{snapshot.staff_users}" }
+                        }
+                    }
+                }
+            }
+        } else if let Some(error) = error() {
+            p { class: "text-red-500", "{error}" }
+        } else {
+            p {
+                class: "flex items-center space-x-2 px-4 py-2 rounded",
+                Spinner {
+                    aria_label: "Loading spinner".to_string(),
+                    size: SpinnerSize::Md,
+                    dark_mode: true,
+                }
+                span { "Loading stats..." }
+            }
+        }
+    }
+}
@@ -0,0 +1,95 @@
+use crate::server::usage::controller::ai_usage_report;
+use crate::server::usage::request::UsageReportRequest;
+use crate::server::usage::response::{UsageBreakdown, UsageReportResponse};
+use crate::theme::Theme;
+use dioxus::prelude::*;
+
+const REPORT_DAYS: u32 = 30;
+
+#[component]
+fn BreakdownTable(title: String, rows: Vec<UsageBreakdown>) -> Element {
+    rsx! {
+        h3 { class: "text-lg font-semibold mt-6 mb-2", "{title}" }
+        if rows.is_empty() {
+            p { class: "text-sm text-gray-500", "No AI calls yet." }
+        } else {
+            table { class: "w-full text-left text-sm",
+                thead {
+                    tr {
+                        th { class: "p-2", "" }
+                        th { class: "p-2", "Requests" }
+                        th { class: "p-2", "Tokens" }
+                        th { class: "p-2", "Avg. latency" }
+                        th { class: "p-2", "Success" }
+                    }
+                }
+                tbody {
+                    for row in rows {
+                        tr { class: "border-t border-gray-600",
+                            td { class: "p-2", "{row.key}" }
+                            td { class: "p-2", "{row.requests}" }
+                            td { class: "p-2", "{row.tokens}" }
+                            td { class: "p-2", "{row.avg_latency_ms:.0} ms" }
+                            td { class: "p-2", "{row.success_rate * 100.0:.0}%" }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+pub fn UsagePanel() -> Element {
+    let theme = use_context::<Signal<Theme>>();
+    let dark_mode = theme() == Theme::Dark;
+    let mut report = use_signal(|| None::<UsageReportResponse>);
+    let mut error = use_signal(|| None::<String>);
+
+    let _ = use_resource(move || async move {
+        match ai_usage_report(UsageReportRequest { days: REPORT_DAYS }).await {
+            Ok(response) => report.set(Some(response.data)),
+            Err(err) => error.set(Some(err.to_string())),
+        }
+    });
+
+    let card = format!(
+        "p-4 rounded-lg shadow {}",
+        if dark_mode {
+            "bg-gray-700"
+        } else {
+            "bg-gray-100"
+        }
+    );
+
+    rsx! {
+        h2 { class: "text-xl font-semibold mb-4", "AI Usage (last {REPORT_DAYS} days)" }
+        if let Some(report) = report() {
+            div { class: "grid grid-cols-1 sm:grid-cols-4 gap-4",
+                div { class: "{card}",
+                    p { class: "text-sm text-gray-500", "Successful calls" }
+                    p { class: "text-2xl font-bold", "{report.stats.total_requests}" }
+                }
+                div { class: "{card}",
+                    p { class: "text-sm text-gray-500", "Estimated tokens" }
+                    p { class: "text-2xl font-bold", "{report.tokens}" }
+                }
+                div { class: "{card}",
+                    p { class: "text-sm text-gray-500", "Avg. response time" }
+                    p { class: "text-2xl font-bold", "{report.stats.avg_gen_time:.1}s" }
+                }
+                div { class: "{card}",
+                    p { class: "text-sm text-gray-500", "Success rate" }
+                    p { class: "text-2xl font-bold", "{report.stats.success_rate * 100.0:.0}%" }
+                }
+            }
+            BreakdownTable { title: "By feature", rows: report.by_feature.clone() }
+            BreakdownTable { title: "By model", rows: report.by_model.clone() }
+            BreakdownTable { title: "Top users", rows: report.by_user.clone() }
+        } else if let Some(error) = error() {
+            p { class: "text-red-500", "{error}" }
+        } else {
+            p { class: "text-sm text-gray-500", "Loading usage..." }
+        }
+    }
+}
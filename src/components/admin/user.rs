@@ -0,0 +1,222 @@
+use crate::components::spinner::Spinner;
+use crate::components::spinner::SpinnerSize;
+use crate::components::toast::manager::ToastManager;
+use crate::components::toast::manager::ToastType;
+use crate::server::admin::controller::{
+    change_user_role, get_user_detail, get_user_messages, reset_user_verification,
+    set_user_suspended,
+};
+use crate::server::admin::request::{
+    ChangeRoleRequest, ResetVerificationRequest, SetSuspendedRequest, UserDetailRequest,
+    UserMessagesRequest,
+};
+use crate::server::admin::response::UserDetailResponse;
+use crate::server::auth::model::{Role, User};
+use crate::server::common::response::SuccessResponse;
+use crate::server::conversation::model::Message;
+use crate::theme::Theme;
+use chrono::Duration;
+use dioxus::prelude::*;
+
+#[component]
+pub fn UserPanel(user_id: String) -> Element {
+    let theme = use_context::<Signal<Theme>>();
+    let dark_mode = theme() == Theme::Dark;
+    let mut toasts_manager = use_context::<Signal<ToastManager>>();
+    let mut detail = use_signal(|| None::<UserDetailResponse>);
+    let mut messages = use_signal(Vec::<Message>::new);
+    let mut selected_conversation = use_signal(|| None::<String>);
+    let mut busy = use_signal(|| false);
+
+    let _ = use_resource(move || {
+        let user_id = user_id.clone();
+        async move {
+            if let Ok(response) = get_user_detail(UserDetailRequest { user_id }).await {
+                detail.set(Some(response.data));
+            }
+        }
+    });
+
+    let _ = use_resource(move || async move {
+        if let Some(conversation_id) = selected_conversation() {
+            if let Ok(response) = get_user_messages(UserMessagesRequest { conversation_id }).await {
+                messages.set(response.data);
+            }
+        }
+    });
+
+    let mut apply = move |result: Result<SuccessResponse<User>, ServerFnError>| {
+        match result {
+            Ok(response) => {
+                if let Some(mut current) = detail() {
+                    current.user = response.data;
+                    detail.set(Some(current));
+                }
+                toasts_manager.set(
+                    toasts_manager()
+                        .add_toast(
+                            "Success".into(),
+                            "User updated.".into(),
+                            ToastType::Success,
+                            Some(Duration::seconds(5)),
+                        )
+                        .clone(),
+                );
+            }
+            Err(err) => {
+                let error_message = err
+                    .to_string()
+                    .split("error running server function:")
+                    .nth(1)
+                    .unwrap_or("")
+                    .trim()
+                    .to_string();
+                toasts_manager.set(
+                    toasts_manager()
+                        .add_toast(
+                            "Error".into(),
+                            error_message,
+                            ToastType::Error,
+                            Some(Duration::seconds(5)),
+                        )
+                        .clone(),
+                );
+            }
+        }
+        busy.set(false);
+    };
+
+    let Some(data) = detail() else {
+        return rsx! {
+            p {
+                class: "flex items-center space-x-2 px-4 py-2 rounded",
+                Spinner {
+                    aria_label: "Loading spinner".to_string(),
+                    size: SpinnerSize::Md,
+                    dark_mode: true,
+                }
+                span { "Loading user..." }
+            }
+        };
+    };
+    let user = data.user.clone();
+    let id = user.id.to_string();
+    let (suspend_id, role_id, verify_id) = (id.clone(), id.clone(), id);
+
+    rsx! {
+        div { class: "space-y-6",
+            div { class: "space-y-2",
+                h2 { class: "text-2xl font-semibold", "{user.name}" }
+                p { "{user.email}" }
+                p { "Role: {user.role} | Verified: {user.verified} | Suspended: {user.suspended}" }
+                p { class: "text-sm text-gray-500", "Joined {user.created_at.format(\"%B %d, %Y\")}" }
+            }
+
+            div { class: "flex flex-wrap items-center gap-4",
+                button {
+                    class: format!(
+                        "px-4 py-2 rounded text-white {}",
+                        if user.suspended { "bg-green-600" } else { "bg-red-600" }
+                    ),
+                    disabled: busy(),
+                    onclick: move |_| {
+                        let user_id = suspend_id.clone();
+                        busy.set(true);
+                        spawn(async move {
+                            apply(set_user_suspended(SetSuspendedRequest {
+                                user_id,
+                                suspended: !user.suspended,
+                            })
+                            .await);
+                        });
+                    },
+                    if user.suspended { "Unsuspend" } else { "Suspend" }
+                }
+                select {
+                    class: format!("p-2 border rounded-md {}", if dark_mode { "bg-gray-900 border-gray-700" } else { "border-gray-300" }),
+                    disabled: busy(),
+                    value: "{user.role}",
+                    onchange: move |e| {
+                        let role = match e.value().as_str() {
+                            "admin" => Role::Admin,
+                            "support" => Role::Support,
+                            _ => Role::User,
+                        };
+                        let user_id = role_id.clone();
+                        busy.set(true);
+                        spawn(async move {
+                            apply(change_user_role(ChangeRoleRequest {
+                                user_id,
+                                role,
+                            })
+                            .await);
+                        });
+                    },
+                    for role in [Role::User, Role::Support, Role::Admin] {
+                        option { value: "{role}", selected: role == user.role, "{role}" }
+                    }
+                }
+                button {
+                    class: "px-4 py-2 rounded bg-gray-500 text-white",
+                    disabled: busy() || !user.verified,
+                    onclick: move |_| {
+                        let user_id = verify_id.clone();
+                        busy.set(true);
+                        spawn(async move {
+                            apply(reset_user_verification(ResetVerificationRequest {
+                                user_id,
+                            })
+                            .await);
+                        });
+                    },
+                    "Reset Verification"
+                }
+            }
+
+            div {
+                h3 { class: "text-xl font-semibold mb-2", "OGs ({data.ogs.len()})" }
+                div { class: "grid grid-cols-1 sm:grid-cols-2 md:grid-cols-4 gap-4",
+                    for og in data.ogs.iter() {
+                        a {
+                            href: "{og.image_url}",
+                            target: "_blank",
+                            class: format!("p-2 rounded-lg shadow {}", if dark_mode { "bg-gray-700" } else { "bg-gray-100" }),
+                            img { src: "{og.image_url}", alt: "OG cover", class: "w-full h-32 object-cover rounded-md mb-2" }
+                            p { class: "font-semibold", "{og.title}" }
+                            p { class: "text-sm text-gray-500", "{og.created_at.format(\"%B %d, %Y\")}" }
+                        }
+                    }
+                }
+            }
+
+            div { class: "grid grid-cols-1 md:grid-cols-3 gap-4",
+                div {
+                    h3 { class: "text-xl font-semibold mb-2", "Conversations ({data.conversations.len()})" }
+                    ul {
+                        for conversation in data.conversations.clone().into_iter() {
+                            li {
+                                class: format!("p-2 cursor-pointer rounded-lg {}",
+                                    if selected_conversation() == Some(conversation.id.to_string()) {
+                                        "bg-blue-200 text-black dark:bg-blue-600 font-bold"
+                                    } else {
+                                        "hover:bg-blue-100 hover:text-black dark:hover:bg-blue-800"
+                                    }
+                                ),
+                                onclick: move |_| selected_conversation.set(Some(conversation.id.to_string())),
+                                "{conversation.title}"
+                            }
+                        }
+                    }
+                }
+                div { class: "md:col-span-2 space-y-2",
+                    for message in messages().iter() {
+                        div { class: format!("p-2 rounded-lg {}", if dark_mode { "bg-gray-700" } else { "bg-gray-100" }),
+                            p { class: "text-xs text-gray-500", "{message.sender} · {message.timestamp.format(\"%b %d, %Y %R\")}" }
+                            p { class: "whitespace-pre-wrap", "{message.content}" }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,118 @@
+use crate::components::spinner::Spinner;
+use crate::components::spinner::SpinnerSize;
+use crate::router::Route;
+use crate::server::admin::controller::list_users;
+use crate::server::admin::request::ListUsersRequest;
+use crate::server::admin::response::UsersPageResponse;
+use crate::theme::Theme;
+use dioxus::prelude::*;
+
+const PER_PAGE: u64 = 20;
+
+#[component]
+pub fn UsersPanel() -> Element {
+    let theme = use_context::<Signal<Theme>>();
+    let dark_mode = theme() == Theme::Dark;
+    let mut search_query = use_signal(String::new);
+    let mut page = use_signal(|| 0_u64);
+    let mut users = use_signal(|| None::<UsersPageResponse>);
+    let mut loading = use_signal(|| true);
+
+    let _ = use_resource(move || async move {
+        loading.set(true);
+        if let Ok(response) = list_users(ListUsersRequest {
+            query: search_query(),
+            page: page(),
+            per_page: PER_PAGE,
+        })
+        .await
+        {
+            users.set(Some(response.data));
+        }
+        loading.set(false);
+    });
+
+    let last_page = users()
+        .map(|users| users.total.saturating_sub(1) / PER_PAGE)
+        .unwrap_or(0);
+
+    rsx! {
+        div { class: "flex flex-col md:flex-row md:items-center md:justify-between mb-4 gap-4",
+            h2 { class: "text-xl font-semibold", "Users" }
+            input {
+                class: format!(
+                    "block w-full md:w-1/3 p-2 border rounded-md shadow-sm {}",
+                    if dark_mode { "bg-gray-900" } else { "" },
+                ),
+                placeholder: "Search by name or email...",
+                value: "{search_query()}",
+                oninput: move |e| {
+                    search_query.set(e.value());
+                    page.set(0);
+                },
+            }
+        }
+        if loading() && users().is_none() {
+            p {
+                class: "flex items-center space-x-2 px-4 py-2 rounded",
+                Spinner {
+                    aria_label: "Loading spinner".to_string(),
+                    size: SpinnerSize::Md,
+                    dark_mode: true,
+                }
+                span { "Loading users..." }
+            }
+        } else if let Some(result) = users() {
+            table { class: "w-full text-left text-sm",
+                thead {
+                    tr {
+                        th { class: "p-2", "Name" }
+                        th { class: "p-2", "Email" }
+                        th { class: "p-2", "Role" }
+                        th { class: "p-2", "Verified" }
+                        th { class: "p-2", "Status" }
+                        th { class: "p-2", "Joined" }
+                    }
+                }
+                tbody {
+                    for user in result.users {
+                        tr { class: "border-t border-gray-600",
+                            td { class: "p-2",
+                                Link {
+                                    to: Route::AdminUser { id: user.id.to_string() },
+                                    class: "text-blue-500 hover:underline",
+                                    "{user.name}"
+                                }
+                            }
+                            td { class: "p-2", "{user.email}" }
+                            td { class: "p-2", "{user.role}" }
+                            td { class: "p-2", if user.verified { "Yes" } else { "No" } }
+                            td { class: "p-2", if user.suspended { "Suspended" } else { "Active" } }
+                            td { class: "p-2", "{user.created_at.format(\"%B %d, %Y\")}" }
+                        }
+                    }
+                }
+            }
+            div { class: "flex items-center justify-between mt-4",
+                span { class: "text-sm text-gray-500", "{result.total} users" }
+                div { class: "flex items-center space-x-2",
+                    button {
+                        class: "px-3 py-1 rounded bg-gray-500 text-white disabled:opacity-50",
+                        disabled: page() == 0,
+                        onclick: move |_| page.set(page().saturating_sub(1)),
+                        "Previous"
+                    }
+                    span { "Page {page() + 1} of {last_page + 1}" }
+                    button {
+                        class: "px-3 py-1 rounded bg-gray-500 text-white disabled:opacity-50",
+                        disabled: page() >= last_page,
+                        onclick: move |_| page.set(page() + 1),
+                        "Next"
+                    }
+                }
+            }
+        } else {
+            p { "Failed to load users." }
+        }
+    }
+}
@@ -3,4 +3,6 @@ pub(crate) mod fields;
 pub(crate) mod navbar;
 pub(crate) mod ogs;
 pub(crate) mod profile;
+pub(crate) mod prompts;
 pub(crate) mod sidebar;
+pub(crate) mod voices;
@@ -23,8 +23,17 @@ pub struct CachedConversationsData {
 pub const CONVERSATIONS_CACHE_KEY: &str = "conversations_cache";
 pub const CONVERSATIONS_CACHE_TIMEOUT: i64 = 2 * 60 * 60;
 
+pub fn cache_conversations(og_id: &str, conversations: Vec<Conversation>) {
+    let cached_data = CachedConversationsData {
+        og_id: og_id.to_string(),
+        conversations,
+        timestamp: Utc::now().timestamp(),
+    };
+    let _ = LocalStorage::set(CONVERSATIONS_CACHE_KEY, &cached_data);
+}
+
 #[component]
-pub fn ChatPanelPage(user_token: Signal<String>, og_id: String) -> Element {
+pub fn ChatPanelPage(og_id: String) -> Element {
     let selected_conversation = use_signal(ObjectId::new);
     let og_id_clone = og_id.clone();
     let mut conversations = use_signal(Vec::<Conversation>::new);
@@ -49,20 +58,14 @@ pub fn ChatPanelPage(user_token: Signal<String>, og_id: String) -> Element {
             }
 
             if let Ok(response) = get_conversations(GetConversationsRequest {
-                token: user_token(),
                 og_id: og_id.clone(),
+                archived: false,
             })
             .await
             {
                 loading.set(false);
                 conversations.set(response.data.clone());
-
-                let cached_data = CachedConversationsData {
-                    og_id: og_id,
-                    conversations: response.data.clone(),
-                    timestamp: now,
-                };
-                let _ = LocalStorage::set(CONVERSATIONS_CACHE_KEY, &cached_data);
+                cache_conversations(&og_id, response.data);
             } else {
                 loading.set(true);
             }
@@ -84,7 +87,6 @@ pub fn ChatPanelPage(user_token: Signal<String>, og_id: String) -> Element {
                     ConversationsSidebar {
                         conversations,
                         selected_conversation,
-                        token: user_token(),
                         og_id: og_id_clone.clone()
                     }
                 }
@@ -94,7 +96,8 @@ pub fn ChatPanelPage(user_token: Signal<String>, og_id: String) -> Element {
                 class: "flex-1 flex flex-col h-full dark:bg-gray-800",
                 ChatPanel {
                     conversation_id: selected_conversation,
-                    user_token: user_token,
+                    conversations,
+                    og_id: og_id_clone.clone(),
                 }
             }
             button {
@@ -111,7 +114,6 @@ pub fn ChatPanelPage(user_token: Signal<String>, og_id: String) -> Element {
                     ConversationsSidebar {
                         conversations: conversations.clone(),
                         selected_conversation: selected_conversation.clone(),
-                        token: user_token(),
                         og_id: og_id_clone.clone(),
                     }
                 }
@@ -1,21 +1,35 @@
+use crate::components::dashboard::chat::cache_conversations;
 use crate::components::dashboard::ogs::list::CachedOGsData;
 use crate::components::dashboard::ogs::list::CACHE_KEY;
 use crate::components::dashboard::ogs::list::CACHE_TIMEOUT;
+use crate::components::toast::manager::{ToastManager, ToastType};
+use crate::markdown::render_markdown;
+use crate::server::ai::controller::get_ai_models;
+use crate::server::conversation::controller::auto_title_conversation;
 use crate::server::conversation::controller::get_messages;
-use crate::server::conversation::controller::save_message_to_db;
+use crate::server::conversation::controller::review_edit;
+use crate::server::conversation::model::Conversation;
+use crate::server::conversation::model::EditStatus;
 use crate::server::conversation::model::Message;
 use crate::server::conversation::request::GetMessagesRequest;
+use crate::server::conversation::request::ReviewEditRequest;
+use crate::server::conversation::request::SendQueryRequest;
+use crate::server::conversation::request::TitleConversationRequest;
+use crate::server::conversation::tree::{active_path, children};
+use crate::server::moderation::model::ReviewStatus;
 use crate::server::og::controller::get_ogs_for_user;
 use crate::server::og::model::OG;
-use crate::server::og::request::GetOGsForUserRequest;
+use crate::server::voice::controller::list_voices;
+use crate::server::voice::model::BrandVoice;
 use gloo_storage::Storage;
 
 use crate::theme::Theme;
 use bson::oid::ObjectId;
-use chrono::Utc;
+use chrono::{Duration, Utc};
 use dioxus::prelude::*;
 use gloo_storage::LocalStorage;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 #[derive(Serialize, Deserialize)]
 pub struct CachedMessagesData {
@@ -27,6 +41,106 @@ pub struct CachedMessagesData {
 pub const MESSAGES_CACHE_KEY: &str = "messages_cache";
 pub const MESSAGES_CACHE_TIMEOUT: i64 = 2 * 60 * 60;
 
+/// Posts the query to the streaming endpoint and relays each server-sent
+/// event back to Rust. [`STOP_SCRIPT`] aborts the request.
+const STREAM_SCRIPT: &str = r#"
+    const request = await dioxus.recv();
+    const controller = new AbortController();
+    window.nanoOgChatStream = controller;
+
+    try {
+        const response = await fetch("/api/stream_query", {
+            method: "POST",
+            headers: { "Content-Type": "application/json" },
+            credentials: "same-origin",
+            body: JSON.stringify(request),
+            signal: controller.signal,
+        });
+        if (!response.ok) {
+            dioxus.send({ kind: "error", error: await response.text() });
+            return;
+        }
+
+        const reader = response.body.getReader();
+        const decoder = new TextDecoder();
+        let buffer = "";
+        while (true) {
+            const { done, value } = await reader.read();
+            if (done) break;
+            buffer += decoder.decode(value, { stream: true });
+
+            let end;
+            while ((end = buffer.indexOf("\n\n")) >= 0) {
+                const frame = buffer.slice(0, end);
+                buffer = buffer.slice(end + 2);
+
+                let kind = "message";
+                let data = "";
+                for (const line of frame.split("\n")) {
+                    if (line.startsWith("event:")) kind = line.slice(6).trim();
+                    else if (line.startsWith("data:")) data += line.slice(5).trim();
+                }
+                if (!data) continue;
+
+                const payload = JSON.parse(data);
+                if (kind === "query") dioxus.send({ kind, message: payload });
+                else if (kind === "token") dioxus.send({ kind, token: payload });
+                else if (kind === "done") dioxus.send({ kind, message: payload });
+                else if (kind === "error") dioxus.send({ kind, error: payload });
+            }
+        }
+        dioxus.send({ kind: "end" });
+    } catch (err) {
+        if (controller.signal.aborted) dioxus.send({ kind: "end" });
+        else dioxus.send({ kind: "error", error: String(err) });
+    }
+"#;
+
+const STOP_SCRIPT: &str = "window.nanoOgChatStream?.abort();";
+
+#[derive(Deserialize)]
+struct StreamEvent {
+    kind: String,
+    #[serde(default)]
+    token: String,
+    message: Option<Message>,
+    #[serde(default)]
+    error: String,
+}
+
+/// Reply text without a tool call that is still streaming in.
+fn visible_text(content: &str) -> &str {
+    content
+        .split("<tool_call>")
+        .next()
+        .unwrap_or_default()
+        .trim_end()
+}
+
+fn field_label(field: &str) -> &str {
+    match field {
+        "title" => "Title",
+        "description" => "Description",
+        "site_name" => "Site name",
+        "image_url" => "Image URL",
+        "image_alt" => "Image alt text",
+        "author" => "Author",
+        "locale" => "Locale",
+        "twitter_card" => "Twitter card",
+        "twitter_site" => "Twitter site",
+        other => other,
+    }
+}
+
+fn cache_messages(conversation: ObjectId, messages: Vec<Message>) {
+    let cached_data = CachedMessagesData {
+        conversation: conversation.to_string(),
+        messages,
+        timestamp: Utc::now().timestamp(),
+    };
+    let _ = LocalStorage::set(MESSAGES_CACHE_KEY, &cached_data);
+}
+
 fn truncate(text: String, max_length: usize) -> String {
     if text.len() > max_length {
         format!("{}...", &text[0..max_length])
@@ -36,15 +150,38 @@ fn truncate(text: String, max_length: usize) -> String {
 }
 
 #[component]
-pub fn ChatPanel(conversation_id: Signal<ObjectId>, user_token: Signal<String>) -> Element {
+pub fn ChatPanel(
+    conversation_id: Signal<ObjectId>,
+    conversations: Signal<Vec<Conversation>>,
+    og_id: String,
+) -> Element {
     let mut messages = use_signal(Vec::<Message>::new);
     let mut input_query = use_signal(|| "".to_string());
     let mut selected_og = use_signal(|| None::<OG>);
     let mut ogs = use_signal(Vec::<OG>::new);
     let mut thinking = use_signal(|| false);
+    let mut streaming = use_signal(|| false);
+    let mut choices = use_signal(HashMap::<Option<ObjectId>, ObjectId>::new);
+    let mut editing = use_signal(|| None::<ObjectId>);
+    let mut edit_draft = use_signal(String::new);
     let mut loading = use_signal(|| false);
+    let mut detail = use_signal(|| "brief".to_string());
+    let mut model = use_signal(String::new);
+    let mut models = use_signal(Vec::<String>::new);
+    let mut voice = use_signal(String::new);
+    let mut voices = use_signal(Vec::<BrandVoice>::new);
+    let mut toasts_manager = use_context::<Signal<ToastManager>>();
     let theme = use_context::<Signal<Theme>>();
 
+    let _ = use_resource(move || async move {
+        if let Ok(response) = get_ai_models().await {
+            models.set(response.data);
+        }
+        if let Ok(response) = list_voices().await {
+            voices.set(response.data);
+        }
+    });
+
     let _ = use_resource(move || async move {
         let now = Utc::now().timestamp();
 
@@ -59,11 +196,7 @@ pub fn ChatPanel(conversation_id: Signal<ObjectId>, user_token: Signal<String>)
             }
         }
 
-        match get_ogs_for_user(GetOGsForUserRequest {
-            token: user_token(),
-        })
-        .await
-        {
+        match get_ogs_for_user().await {
             Ok(response) => {
                 let cached_data = CachedOGsData {
                     data: response.data.clone(),
@@ -85,6 +218,7 @@ pub fn ChatPanel(conversation_id: Signal<ObjectId>, user_token: Signal<String>)
 
     use_effect(move || {
         let conversation_id = conversation_id();
+        choices.set(HashMap::new());
         spawn(async move {
             let now = Utc::now().timestamp();
 
@@ -99,7 +233,6 @@ pub fn ChatPanel(conversation_id: Signal<ObjectId>, user_token: Signal<String>)
             }
 
             if let Ok(response) = get_messages(GetMessagesRequest {
-                token: user_token(),
                 conversation_id: conversation_id,
             })
             .await
@@ -119,32 +252,235 @@ pub fn ChatPanel(conversation_id: Signal<ObjectId>, user_token: Signal<String>)
         });
     });
 
-    let mut handle_send_query = {
-        move || {
-            if !input_query().is_empty() && selected_og().is_some() && selected_og().is_some() {
-                thinking.set(true);
-                let query_text = input_query();
-
-                let user_message = Message {
-                    id: ObjectId::new(),
-                    conversation: conversation_id(),
-                    sender: "user".to_string(),
-                    content: query_text.clone(),
-                    timestamp: Utc::now(),
-                };
+    // Moves the conversation to the top of the sidebar and names it after
+    // its first exchange.
+    let og_id = use_signal(|| og_id);
+    let touch_conversation = move |id: ObjectId| {
+        let og_id = og_id();
+        async move {
+            let mut current_conversations = conversations();
+            let Some(conversation) = current_conversations.iter_mut().find(|c| c.id == id) else {
+                return;
+            };
+            conversation.updated_at = Utc::now();
+            let needs_title = !conversation.custom_title
+                && messages().iter().filter(|m| m.sender != "user").count() == 1;
+            conversations.set(current_conversations.clone());
+            cache_conversations(&og_id, current_conversations);
+
+            if needs_title {
+                if let Ok(response) = auto_title_conversation(TitleConversationRequest {
+                    conversation_id: id,
+                })
+                .await
+                {
+                    let mut current_conversations = conversations();
+                    if let Some(conversation) =
+                        current_conversations.iter_mut().find(|c| c.id == id)
+                    {
+                        *conversation = response.data;
+                    }
+                    conversations.set(current_conversations.clone());
+                    cache_conversations(&og_id, current_conversations);
+                }
+            }
+        }
+    };
+
+    // Sends `query` as a reply to `parent`, or with `regenerate` answers the
+    // user message `parent` again, streaming the assistant's reply in.
+    let mut send_turn = move |query: String, parent: Option<ObjectId>, regenerate: bool| {
+        if selected_og().is_none() || streaming() {
+            return;
+        }
+        thinking.set(true);
+
+        let mut pending_query = None;
+        let mut reply_parent = parent;
+        if !regenerate {
+            let user_message = Message {
+                id: ObjectId::new(),
+                conversation: conversation_id(),
+                parent,
+                root: parent.is_none(),
+                sender: "user".to_string(),
+                content: query.clone(),
+                timestamp: Utc::now(),
+                edit: None,
+                moderation: None,
+            };
+            pending_query = Some(user_message.id);
+            reply_parent = Some(user_message.id);
+            choices.write().insert(parent, user_message.id);
+
+            let mut current_messages = messages();
+            current_messages.push(user_message);
+            messages.set(current_messages);
+        }
 
-                let mut current_messages = messages();
-                current_messages.push(user_message.clone());
-                messages.set(current_messages);
+        let request = SendQueryRequest {
+            conversation_id: conversation_id(),
+            query,
+            og: selected_og().map(|og| og.id.to_hex()).unwrap_or_default(),
+            detail: detail(),
+            model: model(),
+            parent,
+            regenerate,
+            voice_id: ObjectId::parse_str(voice()).ok(),
+        };
+        let mut eval = document::eval(STREAM_SCRIPT);
+        let _ = eval.send(request);
+        streaming.set(true);
 
-                spawn(async move {
-                    save_message_to_db(user_message).await.unwrap();
-                });
+        spawn(async move {
+            let reply_id = ObjectId::new();
+            let mut finished = false;
+            while let Ok(event) = eval.recv::<StreamEvent>().await {
+                match event.kind.as_str() {
+                    "query" => {
+                        if let Some(message) = event.message {
+                            let mut current_messages = messages();
+                            current_messages.retain(|m| Some(m.id) != pending_query);
+                            choices.write().insert(message.parent, message.id);
+                            reply_parent = Some(message.id);
+                            current_messages.push(message);
+                            messages.set(current_messages);
+                        }
+                    }
+                    "token" => {
+                        thinking.set(false);
+                        let mut current_messages = messages();
+                        match current_messages.iter_mut().find(|m| m.id == reply_id) {
+                            Some(reply) => reply.content.push_str(&event.token),
+                            None => {
+                                choices.write().insert(reply_parent, reply_id);
+                                current_messages.push(Message {
+                                    id: reply_id,
+                                    conversation: conversation_id(),
+                                    parent: reply_parent,
+                                    root: false,
+                                    sender: "assistant".to_string(),
+                                    content: event.token,
+                                    timestamp: Utc::now(),
+                                    edit: None,
+                                    moderation: None,
+                                });
+                            }
+                        }
+                        messages.set(current_messages);
+                    }
+                    "done" => {
+                        if let Some(message) = event.message {
+                            let mut current_messages = messages();
+                            current_messages.retain(|m| m.id != reply_id);
+                            choices.write().insert(message.parent, message.id);
+                            current_messages.push(message);
+                            messages.set(current_messages);
+                            finished = true;
+                        }
+                        break;
+                    }
+                    "error" => {
+                        toasts_manager.set(
+                            toasts_manager()
+                                .add_toast(
+                                    "Error".into(),
+                                    event.error,
+                                    ToastType::Error,
+                                    Some(Duration::seconds(5)),
+                                )
+                                .clone(),
+                        );
+                        break;
+                    }
+                    _ => break,
+                }
+            }
 
-                input_query.set("".to_string());
+            // A stopped or failed reply may still have been partly saved.
+            if !finished {
+                if let Ok(response) = get_messages(GetMessagesRequest {
+                    conversation_id: conversation_id(),
+                })
+                .await
+                {
+                    messages.set(response.data);
+                }
             }
+
+            cache_messages(conversation_id(), messages());
+            thinking.set(false);
+            streaming.set(false);
+            touch_conversation(conversation_id()).await;
+        });
+    };
+
+    let mut handle_send_query = move || {
+        if !input_query().is_empty() {
+            let parent = active_path(&messages(), &choices())
+                .last()
+                .map(|message| message.id);
+            send_turn(input_query(), parent, false);
+            input_query.set("".to_string());
         }
     };
+    let handle_stop = move |_| {
+        document::eval(STOP_SCRIPT);
+    };
+    let handle_review = move |message_id: ObjectId, accept: bool| {
+        spawn(async move {
+            match review_edit(ReviewEditRequest { message_id, accept }).await {
+                Ok(response) => {
+                    let og = response.data;
+                    let status = if accept {
+                        EditStatus::Accepted
+                    } else {
+                        EditStatus::Rejected
+                    };
+                    let mut current_messages = messages();
+                    if let Some(edit) = current_messages
+                        .iter_mut()
+                        .find(|m| m.id == message_id)
+                        .and_then(|m| m.edit.as_mut())
+                    {
+                        edit.status = status;
+                    }
+                    messages.set(current_messages.clone());
+                    cache_messages(conversation_id(), current_messages);
+
+                    if accept {
+                        let mut current_ogs = ogs();
+                        if let Some(existing) = current_ogs.iter_mut().find(|o| o.id == og.id) {
+                            *existing = og.clone();
+                        }
+                        ogs.set(current_ogs.clone());
+                        let _ = LocalStorage::set(
+                            CACHE_KEY,
+                            &CachedOGsData {
+                                data: current_ogs,
+                                timestamp: Utc::now().timestamp(),
+                            },
+                        );
+                        if selected_og().is_some_and(|selected| selected.id == og.id) {
+                            selected_og.set(Some(og));
+                        }
+                    }
+                }
+                Err(err) => {
+                    toasts_manager.set(
+                        toasts_manager()
+                            .add_toast(
+                                "Error".into(),
+                                err.to_string(),
+                                ToastType::Error,
+                                Some(Duration::seconds(5)),
+                            )
+                            .clone(),
+                    );
+                }
+            }
+        });
+    };
     let mut handle_og_change = move |og_id: String| {
         for og in ogs().into_iter() {
             if og.id.to_string() == og_id {
@@ -155,6 +491,18 @@ pub fn ChatPanel(conversation_id: Signal<ObjectId>, user_token: Signal<String>)
         }
     };
 
+    let all_messages = messages();
+    let thread: Vec<(Message, Vec<ObjectId>)> = active_path(&all_messages, &choices())
+        .into_iter()
+        .map(|message| {
+            let siblings = children(&all_messages, message.parent)
+                .iter()
+                .map(|sibling| sibling.id)
+                .collect();
+            (message.clone(), siblings)
+        })
+        .collect();
+
     rsx! {
         div {
             class: format!(
@@ -176,6 +524,57 @@ pub fn ChatPanel(conversation_id: Signal<ObjectId>, user_token: Signal<String>)
                         option { value: "{og.id}", "{truncate(og.title.clone(), 20)}" }
                     }
                 }
+
+                select {
+                    class: format!(
+                        "p-2 rounded-lg mb-2 md:mb-0 {}",
+                        if theme() == Theme::Dark { "bg-gray-700 text-white" } else { "bg-gray-100 text-black" }
+                    ),
+                    value: "{detail}",
+                    onchange: move |evt| detail.set(evt.value()),
+                    option { value: "brief", "Brief" },
+                    option { value: "detailed", "Detailed" },
+                }
+
+                select {
+                    class: format!(
+                        "p-2 rounded-lg mb-2 md:mb-0 {}",
+                        if theme() == Theme::Dark { "bg-gray-700 text-white" } else { "bg-gray-100 text-black" }
+                    ),
+                    value: "{model}",
+                    onchange: move |evt| model.set(evt.value()),
+                    option { value: "", "Default model" },
+                    for name in models().iter() {
+                        option { value: "{name}", "{name}" }
+                    }
+                }
+
+                if !voices().is_empty() {
+                    select {
+                        class: format!(
+                            "p-2 rounded-lg mb-2 md:mb-0 {}",
+                            if theme() == Theme::Dark { "bg-gray-700 text-white" } else { "bg-gray-100 text-black" }
+                        ),
+                        value: "{voice}",
+                        onchange: move |evt| voice.set(evt.value()),
+                        option { value: "", "No brand voice" },
+                        for profile in voices().iter() {
+                            option { value: "{profile.id}", "{profile.name}" }
+                        }
+                    }
+                }
+            }
+
+            if let Some(og) = selected_og() {
+                div {
+                    class: format!(
+                        "mx-4 mt-4 p-3 rounded-lg border text-sm {}",
+                        if theme() == Theme::Dark { "border-gray-700 bg-gray-800" } else { "border-gray-200 bg-gray-50" }
+                    ),
+                    p { class: "font-semibold truncate", "{og.title}" }
+                    p { class: "text-gray-500 line-clamp-2", "{og.description}" }
+                    p { class: "text-xs text-gray-400 mt-1", "{og.site_name} · {og.twitter_card} · {og.locale}" }
+                }
             }
 
             div {
@@ -197,36 +596,175 @@ pub fn ChatPanel(conversation_id: Signal<ObjectId>, user_token: Signal<String>)
                     }
                 }
 
-                button {
-                    class: "w-full sm:w-auto p-2 rounded-lg bg-blue-500 text-white hover:bg-blue-600",
-                    onclick: move |_| handle_send_query(),
-                    "Send"
+                if streaming() {
+                    button {
+                        class: "w-full sm:w-auto p-2 rounded-lg bg-red-500 text-white hover:bg-red-600",
+                        onclick: handle_stop,
+                        "Stop generating"
+                    }
+                } else {
+                    button {
+                        class: "w-full sm:w-auto p-2 rounded-lg bg-blue-500 text-white hover:bg-blue-600",
+                        onclick: move |_| handle_send_query(),
+                        "Send"
+                    }
                 }
             }
 
             div {
                 class: "flex-grow overflow-y-auto p-4 space-y-3",
 
-                for message in messages().iter() {
+                for (message, siblings) in thread.into_iter() {
                     div {
                         class: if message.sender == "user" { "text-right" } else { "text-left" },
-                        div {
-                            class: format!(
-                                "inline-block px-4 py-2 rounded-lg {} max-w-full md:max-w-2/3",
-                                if message.sender == "user" {
-                                    "bg-blue-500 text-white"
-                                } else {
-                                    "bg-gray-300 dark:bg-gray-700 text-black dark:text-white"
+                        if editing() == Some(message.id) {
+                            div {
+                                class: "inline-flex flex-col w-full md:w-2/3 space-y-2",
+                                textarea {
+                                    class: format!(
+                                        "p-2 rounded-lg border {}",
+                                        if theme() == Theme::Dark { "bg-gray-700 text-white border-gray-600" } else { "border-gray-300" }
+                                    ),
+                                    rows: 3,
+                                    value: "{edit_draft}",
+                                    oninput: move |evt| edit_draft.set(evt.value()),
                                 }
-                            ),
-                            if message.sender == "user" {
                                 div {
-                                    "{message.content}",
+                                    class: "flex justify-end space-x-2",
+                                    button {
+                                        class: "px-3 py-1 rounded-lg bg-gray-500 text-white hover:bg-gray-600",
+                                        onclick: move |_| editing.set(None),
+                                        "Cancel"
+                                    }
+                                    button {
+                                        class: "px-3 py-1 rounded-lg bg-blue-500 text-white hover:bg-blue-600",
+                                        disabled: edit_draft().trim().is_empty(),
+                                        onclick: move |_| {
+                                            editing.set(None);
+                                            send_turn(edit_draft(), message.parent, false);
+                                        },
+                                        "Save & submit"
+                                    }
                                 }
                             }
-                            else {
-                                div {
-                                    dangerous_inner_html: message.content.clone(),
+                        } else {
+                            div {
+                                class: format!(
+                                    "inline-block px-4 py-2 rounded-lg {} max-w-full md:max-w-2/3",
+                                    if message.sender == "user" {
+                                        "bg-blue-500 text-white"
+                                    } else {
+                                        "bg-gray-300 dark:bg-gray-700 text-black dark:text-white"
+                                    }
+                                ),
+                                if message.moderation == Some(ReviewStatus::Pending) {
+                                    p { class: "text-xs italic opacity-75 mb-1", "⚠️ Flagged for moderator review" }
+                                }
+                                if message.sender == "user" {
+                                    div {
+                                        "{message.content}",
+                                    }
+                                }
+                                else {
+                                    div {
+                                        class: "markdown text-left",
+                                        dangerous_inner_html: render_markdown(visible_text(&message.content)),
+                                    }
+                                    if let Some(edit) = message.edit.clone() {
+                                        div {
+                                            class: "mt-3 p-3 rounded-lg text-left text-sm bg-white dark:bg-gray-800 border border-gray-200 dark:border-gray-600",
+                                            p { class: "font-semibold mb-2", "Proposed edit" }
+                                            for change in edit.changes.iter() {
+                                                div {
+                                                    class: "mb-2",
+                                                    p { class: "text-xs uppercase text-gray-500", "{field_label(&change.field)}" }
+                                                    if !change.before.is_empty() {
+                                                        p { class: "line-through text-red-600 dark:text-red-400", "{change.before}" }
+                                                    }
+                                                    p { class: "text-green-700 dark:text-green-400", "{change.after}" }
+                                                }
+                                            }
+                                            match edit.status {
+                                                EditStatus::Pending => {
+                                                    let message_id = message.id;
+                                                    rsx! {
+                                                        div {
+                                                            class: "flex space-x-2 mt-2",
+                                                            button {
+                                                                class: "px-3 py-1 rounded-lg bg-green-600 text-white hover:bg-green-700",
+                                                                onclick: move |_| handle_review(message_id, true),
+                                                                "Accept"
+                                                            }
+                                                            button {
+                                                                class: "px-3 py-1 rounded-lg bg-gray-500 text-white hover:bg-gray-600",
+                                                                onclick: move |_| handle_review(message_id, false),
+                                                                "Reject"
+                                                            }
+                                                        }
+                                                    }
+                                                }
+                                                EditStatus::Accepted => rsx! {
+                                                    p { class: "mt-2 text-green-700 dark:text-green-400", "✓ Applied" }
+                                                },
+                                                EditStatus::Rejected => rsx! {
+                                                    p { class: "mt-2 text-gray-500", "Rejected" }
+                                                },
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                        div {
+                            class: "flex items-center space-x-2 mt-1 text-xs text-gray-500",
+                            class: if message.sender == "user" { "justify-end" } else { "justify-start" },
+                            if siblings.len() > 1 {
+                                {
+                                    let index = siblings.iter().position(|id| *id == message.id).unwrap_or_default();
+                                    let previous = index.checked_sub(1).map(|i| siblings[i]);
+                                    let next = siblings.get(index + 1).copied();
+                                    rsx! {
+                                        button {
+                                            disabled: previous.is_none() || streaming(),
+                                            onclick: move |_| {
+                                                if let Some(id) = previous {
+                                                    choices.write().insert(message.parent, id);
+                                                }
+                                            },
+                                            "‹"
+                                        }
+                                        span { "{index + 1}/{siblings.len()}" }
+                                        button {
+                                            disabled: next.is_none() || streaming(),
+                                            onclick: move |_| {
+                                                if let Some(id) = next {
+                                                    choices.write().insert(message.parent, id);
+                                                }
+                                            },
+                                            "›"
+                                        }
+                                    }
+                                }
+                            }
+                            if !streaming() && editing().is_none() {
+                                if message.sender == "user" {
+                                    button {
+                                        class: "hover:underline",
+                                        onclick: {
+                                            let content = message.content.clone();
+                                            move |_| {
+                                                edit_draft.set(content.clone());
+                                                editing.set(Some(message.id));
+                                            }
+                                        },
+                                        "Edit"
+                                    }
+                                } else if message.parent.is_some() {
+                                    button {
+                                        class: "hover:underline",
+                                        onclick: move |_| send_turn(String::new(), message.parent, true),
+                                        "Regenerate"
+                                    }
                                 }
                             }
                         }
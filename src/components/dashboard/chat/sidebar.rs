@@ -1,37 +1,205 @@
+use crate::components::dashboard::chat::cache_conversations;
+use crate::components::toast::manager::{ToastManager, ToastType};
+use crate::server::conversation::controller::archive_conversation;
 use crate::server::conversation::controller::create_conversation;
+use crate::server::conversation::controller::delete_conversation;
+use crate::server::conversation::controller::export_conversations;
 use crate::server::conversation::controller::get_conversations;
+use crate::server::conversation::controller::import_conversations;
+use crate::server::conversation::controller::rename_conversation;
+use crate::server::conversation::controller::search_conversations;
 use crate::server::conversation::model::Conversation;
+use crate::server::conversation::request::ArchiveConversationRequest;
 use crate::server::conversation::request::CreateConversationRequest;
+use crate::server::conversation::request::DeleteConversationRequest;
+use crate::server::conversation::request::ExportConversationsRequest;
+use crate::server::conversation::request::ExportFormat;
 use crate::server::conversation::request::GetConversationsRequest;
+use crate::server::conversation::request::ImportConversationsRequest;
+use crate::server::conversation::request::RenameConversationRequest;
+use crate::server::conversation::request::SearchConversationsRequest;
+use crate::server::conversation::response::ConversationFile;
+use crate::server::conversation::response::ConversationMatch;
 use crate::theme::Theme;
 use bson::oid::ObjectId;
-use chrono::Utc;
+use chrono::Duration;
 
 use dioxus::prelude::*;
 
+const MIN_SEARCH_CHARS: usize = 2;
+
+#[allow(unused_variables)]
+fn download_file(file: ConversationFile) {
+    client! {
+        let eval = document::eval(
+            r#"
+            const file = await dioxus.recv();
+            const url = URL.createObjectURL(new Blob([file.content], { type: file.mime }));
+            const link = document.createElement('a');
+            link.download = file.file_name;
+            link.href = url;
+            link.click();
+            URL.revokeObjectURL(url);
+            "#,
+        );
+        let _ = eval.send(file);
+    }
+}
+
 #[component]
 pub fn ConversationsSidebar(
     conversations: Signal<Vec<Conversation>>,
     selected_conversation: Signal<ObjectId>,
-    token: String,
     og_id: String,
 ) -> Element {
-    let token_clone = token.clone();
     let og_id_clone = og_id.clone();
+    let og_id_search = og_id.clone();
+    let og_id_cache = use_signal(|| og_id.clone());
     let theme = use_context::<Signal<Theme>>();
+    let mut toasts_manager = use_context::<Signal<ToastManager>>();
+    let mut show_archived = use_signal(|| false);
+    let mut search = use_signal(String::new);
+    let mut renaming = use_signal(|| None::<ObjectId>);
+    let mut draft_title = use_signal(String::new);
+    let mut export_format = use_signal(|| ExportFormat::Markdown);
+
+    let mut notify_error = move |err: ServerFnError| {
+        toasts_manager.set(
+            toasts_manager()
+                .add_toast(
+                    "Error".into(),
+                    err.to_string(),
+                    ToastType::Error,
+                    Some(Duration::seconds(5)),
+                )
+                .clone(),
+        );
+    };
 
     use_effect(move || {
-        let token = token.clone();
         let og_id = og_id.clone();
+        let archived = show_archived();
 
         spawn(async move {
-            if let Ok(conv_list) = get_conversations(GetConversationsRequest { token, og_id }).await
+            if let Ok(conv_list) = get_conversations(GetConversationsRequest {
+                og_id: og_id.clone(),
+                archived,
+            })
+            .await
             {
+                if !archived {
+                    cache_conversations(&og_id, conv_list.data.clone());
+                }
                 conversations.set(conv_list.data);
             }
         });
     });
 
+    let results = use_resource(move || {
+        let og_id = og_id_search.clone();
+        async move {
+            let query = search();
+            if query.trim().chars().count() < MIN_SEARCH_CHARS {
+                return Vec::<ConversationMatch>::new();
+            }
+            search_conversations(SearchConversationsRequest { og_id, query })
+                .await
+                .map(|response| response.data)
+                .unwrap_or_default()
+        }
+    });
+
+    // Swaps the listed conversation `id` for `conversation`, or drops it.
+    let mut replace = move |id: ObjectId, conversation: Option<Conversation>| {
+        let mut current_conversations = conversations();
+        current_conversations.retain(|c| c.id != id);
+        if let Some(conversation) = conversation {
+            current_conversations.push(conversation);
+            current_conversations.sort_by_key(|c| std::cmp::Reverse(c.updated_at));
+        }
+        if !show_archived() {
+            cache_conversations(&og_id_cache(), current_conversations.clone());
+        }
+        conversations.set(current_conversations);
+    };
+
+    let mut save_title = move |conversation_id: ObjectId| {
+        renaming.set(None);
+        let title = draft_title();
+        spawn(async move {
+            match rename_conversation(RenameConversationRequest {
+                conversation_id,
+                title,
+            })
+            .await
+            {
+                Ok(response) => replace(conversation_id, Some(response.data)),
+                Err(err) => notify_error(err),
+            }
+        });
+    };
+
+    let export = move |conversation_id: Option<ObjectId>| {
+        let og_id = og_id_cache();
+        let format = export_format();
+        spawn(async move {
+            match export_conversations(ExportConversationsRequest {
+                conversation_id,
+                og_id,
+                format,
+            })
+            .await
+            {
+                Ok(response) => download_file(response.data),
+                Err(err) => notify_error(err),
+            }
+        });
+    };
+
+    let handle_import = move |evt: FormEvent| {
+        spawn(async move {
+            let Some(files) = evt.files() else {
+                return;
+            };
+            for name in files.files() {
+                let Some(data) = files.read_file_to_string(&name).await else {
+                    continue;
+                };
+                match import_conversations(ImportConversationsRequest {
+                    og_id: og_id_cache(),
+                    data,
+                })
+                .await
+                {
+                    Ok(response) => {
+                        let count = response.data.len();
+                        for conversation in response.data {
+                            if conversation.og.to_hex() == og_id_cache()
+                                && conversation.archived == show_archived()
+                            {
+                                replace(conversation.id, Some(conversation));
+                            }
+                        }
+                        toasts_manager.set(
+                            toasts_manager()
+                                .add_toast(
+                                    "Imported".into(),
+                                    format!("Restored {count} conversation(s) from {name}"),
+                                    ToastType::Success,
+                                    Some(Duration::seconds(5)),
+                                )
+                                .clone(),
+                        );
+                    }
+                    Err(err) => notify_error(err),
+                }
+            }
+        });
+    };
+
+    let mut sorted = conversations();
+    sorted.sort_by_key(|c| std::cmp::Reverse(c.updated_at));
+
     rsx! {
         div {
             class: format!("p-4 {}", if theme() == Theme::Dark { "border-gray-600 bg-gray-900" } else { "border-gray-200" }),
@@ -41,32 +209,193 @@ pub fn ConversationsSidebar(
             button {
                 class: "w-full bg-blue-500 text-white p-2 rounded-lg mb-4",
                 onclick: move |_| {
-                    let token_clone = token_clone.clone();
                     let og_id_clone = og_id_clone.clone();
                     spawn(async move {
-                        let title = format!("Conversation {}", Utc::now().timestamp());
-                        if let Ok(new_conversation) = create_conversation(CreateConversationRequest { og_id: og_id_clone, token: token_clone, title }).await {
-                            let mut current_conversations = conversations();
-                            current_conversations.push(new_conversation.data);
-                            conversations.set(current_conversations);
+                        match create_conversation(CreateConversationRequest { og_id: og_id_clone, title: String::new() }).await {
+                            Ok(new_conversation) => {
+                                show_archived.set(false);
+                                selected_conversation.set(new_conversation.data.id);
+                                replace(new_conversation.data.id, Some(new_conversation.data));
+                            }
+                            Err(err) => notify_error(err),
                         }
                     });
                 },
                 "+ New Chat"
             }
 
-            ul {
-                for conversation in conversations().into_iter() {
-                    li {
-                        class: format!("p-2 cursor-pointer rounded-lg {}",
-                            if conversation.id == selected_conversation() {
-                                "bg-blue-200 text-black dark:bg-blue-600 font-bold"
+            input {
+                class: format!(
+                    "w-full p-2 mb-2 rounded-lg border text-sm {}",
+                    if theme() == Theme::Dark { "bg-gray-700 text-white border-gray-600" } else { "border-gray-300" }
+                ),
+                r#type: "search",
+                placeholder: "Search messages...",
+                value: "{search}",
+                oninput: move |evt| search.set(evt.value()),
+            }
+
+            label {
+                class: "flex items-center space-x-2 mb-4 text-sm text-gray-500",
+                input {
+                    r#type: "checkbox",
+                    checked: show_archived(),
+                    onchange: move |evt| show_archived.set(evt.checked()),
+                }
+                span { "Show archived" }
+            }
+
+            div {
+                class: "flex items-center space-x-2 mb-4 text-sm",
+                select {
+                    class: format!(
+                        "p-1 rounded border {}",
+                        if theme() == Theme::Dark { "bg-gray-700 text-white border-gray-600" } else { "border-gray-300" }
+                    ),
+                    onchange: move |evt| export_format.set(if evt.value() == "json" { ExportFormat::Json } else { ExportFormat::Markdown }),
+                    option { value: "markdown", selected: export_format() == ExportFormat::Markdown, "Markdown" }
+                    option { value: "json", selected: export_format() == ExportFormat::Json, "JSON" }
+                }
+                button {
+                    class: "px-2 py-1 rounded border border-blue-500 text-blue-500",
+                    onclick: move |_| export(None),
+                    "Export all"
+                }
+                label {
+                    class: "px-2 py-1 rounded border border-blue-500 text-blue-500 cursor-pointer",
+                    title: "Restore conversations from a JSON export",
+                    "Import"
+                    input {
+                        class: "hidden",
+                        r#type: "file",
+                        accept: ".json,application/json",
+                        onchange: handle_import,
+                    }
+                }
+            }
+
+            if search().trim().chars().count() >= MIN_SEARCH_CHARS {
+                ul {
+                    match results() {
+                        Some(results) if !results.is_empty() => rsx! {
+                            for result in results.into_iter() {
+                                li {
+                                    class: "p-2 cursor-pointer rounded-lg hover:bg-blue-100 hover:text-black dark:hover:bg-blue-800",
+                                    onclick: move |_| {
+                                        selected_conversation.set(result.conversation.id);
+                                        search.set(String::new());
+                                    },
+                                    p { class: "font-semibold truncate", "{result.conversation.title}" }
+                                    p { class: "text-xs text-gray-500", "{result.snippet}" }
+                                }
+                            }
+                        },
+                        Some(_) => rsx! {
+                            li { class: "p-2 text-sm text-gray-500", "No matches" }
+                        },
+                        None => rsx! {
+                            li { class: "p-2 text-sm text-gray-500", "Searching..." }
+                        },
+                    }
+                }
+            } else {
+                ul {
+                    for conversation in sorted.into_iter() {
+                        li {
+                            class: format!("group flex items-center p-2 cursor-pointer rounded-lg {}",
+                                if conversation.id == selected_conversation() {
+                                    "bg-blue-200 text-black dark:bg-blue-600 font-bold"
+                                } else {
+                                    "hover:bg-blue-100 hover:text-black dark:hover:bg-blue-800"
+                                }
+                            ),
+                            onclick: move |_| selected_conversation.set(conversation.id),
+
+                            if renaming() == Some(conversation.id) {
+                                input {
+                                    class: "flex-1 min-w-0 p-1 rounded text-black",
+                                    value: "{draft_title}",
+                                    autofocus: true,
+                                    onclick: move |evt| evt.stop_propagation(),
+                                    oninput: move |evt| draft_title.set(evt.value()),
+                                    onkeydown: move |evt| {
+                                        match evt.key() {
+                                            Key::Enter => save_title(conversation.id),
+                                            Key::Escape => renaming.set(None),
+                                            _ => {}
+                                        }
+                                    },
+                                    onblur: move |_| {
+                                        if renaming() == Some(conversation.id) {
+                                            save_title(conversation.id);
+                                        }
+                                    },
+                                }
                             } else {
-                                "hover:bg-blue-100 hover:text-black dark:hover:bg-blue-800"
+                                span { class: "flex-1 truncate", "{conversation.title}" }
+                                div {
+                                    class: "hidden group-hover:flex space-x-1 ml-2 text-sm",
+                                    button {
+                                        title: "Rename",
+                                        onclick: {
+                                            let title = conversation.title.clone();
+                                            move |evt: MouseEvent| {
+                                                evt.stop_propagation();
+                                                draft_title.set(title.clone());
+                                                renaming.set(Some(conversation.id));
+                                            }
+                                        },
+                                        "✎"
+                                    }
+                                    button {
+                                        title: "Export",
+                                        onclick: move |evt: MouseEvent| {
+                                            evt.stop_propagation();
+                                            export(Some(conversation.id));
+                                        },
+                                        "⬇"
+                                    }
+                                    button {
+                                        title: if conversation.archived { "Unarchive" } else { "Archive" },
+                                        onclick: move |evt: MouseEvent| {
+                                            evt.stop_propagation();
+                                            let archived = !conversation.archived;
+                                            spawn(async move {
+                                                match archive_conversation(ArchiveConversationRequest {
+                                                    conversation_id: conversation.id,
+                                                    archived,
+                                                })
+                                                .await
+                                                {
+                                                    Ok(_) => replace(conversation.id, None),
+                                                    Err(err) => notify_error(err),
+                                                }
+                                            });
+                                        },
+                                        if conversation.archived { "↩" } else { "🗄" }
+                                    }
+                                    button {
+                                        title: "Delete",
+                                        onclick: move |evt: MouseEvent| {
+                                            evt.stop_propagation();
+                                            let conversation_id = conversation.id;
+                                            spawn(async move {
+                                                match delete_conversation(DeleteConversationRequest { conversation_id }).await {
+                                                    Ok(_) => {
+                                                        if selected_conversation() == conversation_id {
+                                                            selected_conversation.set(ObjectId::new());
+                                                        }
+                                                        replace(conversation_id, None);
+                                                    }
+                                                    Err(err) => notify_error(err),
+                                                }
+                                            });
+                                        },
+                                        "🗑"
+                                    }
+                                }
                             }
-                        ),
-                        onclick: move |_| selected_conversation.set(conversation.id),
-                        "{conversation.title}"
+                        }
                     }
                 }
             }
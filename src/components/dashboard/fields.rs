@@ -1,3 +1,4 @@
 pub(crate) mod input;
 pub(crate) mod number;
 pub(crate) mod select;
+pub(crate) mod suggestions;
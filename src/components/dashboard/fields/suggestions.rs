@@ -0,0 +1,41 @@
+use crate::server::ai::response::Suggestion;
+use crate::theme::Theme;
+use dioxus::prelude::*;
+
+/// Ranked AI candidates for a field; picking one copies it into `value` and
+/// closes the list.
+#[component]
+pub fn SuggestionList(suggestions: Signal<Vec<Suggestion>>, value: Signal<String>) -> Element {
+    let theme = use_context::<Signal<Theme>>();
+    let dark_mode = theme() == Theme::Dark;
+
+    rsx! {
+        if !suggestions().is_empty() {
+            ul {
+                class: format!("mt-2 border rounded-md divide-y {}", if dark_mode { "border-gray-700 divide-gray-700 bg-gray-900" } else { "border-gray-300 divide-gray-200 bg-gray-50" }),
+                for suggestion in suggestions() {
+                    li {
+                        button {
+                            class: format!("w-full flex items-center justify-between gap-x-2 p-2 text-left text-sm {}", if dark_mode { "hover:bg-gray-700" } else { "hover:bg-gray-200" }),
+                            r#type: "button",
+                            onclick: move |_| {
+                                value.set(suggestion.text.clone());
+                                suggestions.set(Vec::new());
+                            },
+                            span { "{suggestion.text}" }
+                            span { class: "shrink-0 text-xs text-gray-500", "{suggestion.score}" }
+                        }
+                    }
+                }
+                li {
+                    button {
+                        class: "w-full p-2 text-xs text-gray-500 hover:underline",
+                        r#type: "button",
+                        onclick: move |_| suggestions.set(Vec::new()),
+                        "Dismiss"
+                    }
+                }
+            }
+        }
+    }
+}
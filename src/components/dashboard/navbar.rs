@@ -1,5 +1,6 @@
 use crate::components::spinner::Spinner;
 use crate::components::spinner::SpinnerSize;
+use crate::server::auth::controller::logout;
 use crate::theme::Theme;
 use crate::theme::ThemeToggle;
 use dioxus::prelude::*;
@@ -18,9 +19,12 @@ pub fn Navbar() -> Element {
         e.stop_propagation();
         loading.set(false);
 
-        SessionStorage::clear();
-        LocalStorage::clear();
-        navigator.push("/login");
+        spawn(async move {
+            let _ = logout().await;
+            SessionStorage::clear();
+            LocalStorage::clear();
+            navigator.push("/login");
+        });
     };
 
     rsx! {
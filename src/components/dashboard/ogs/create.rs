@@ -1,15 +1,31 @@
 use crate::components::dashboard::fields::input::InputField;
+use crate::components::dashboard::fields::suggestions::SuggestionList;
 use crate::components::spinner::Spinner;
 use crate::components::spinner::SpinnerSize;
 use crate::components::toast::manager::ToastManager;
 use crate::components::toast::manager::ToastType;
+use crate::markdown::escape;
+use crate::router::Route;
+use crate::server::ai::controller::{generate_alt_text, generate_background, generate_suggestions};
+use crate::server::ai::request::{
+    AltTextRequest, BackgroundRequest, SuggestRequest, SuggestionField,
+};
+use crate::server::ai::response::Suggestion;
 use crate::server::og::controller::store_og;
+use crate::server::og::model::IMAGE_ALT_MAX_CHARS;
 use crate::server::og::request::StoreOGRequest;
+use crate::server::search::controller::find_similar_ogs;
+use crate::server::search::request::SimilarOGsRequest;
+use crate::server::search::response::SearchHit;
+use crate::server::template::controller::list_templates;
+use crate::server::template::model::PromptTemplate;
+use crate::server::voice::controller::list_voices;
+use crate::server::voice::model::BrandVoice;
 use crate::theme::Theme;
+use bson::oid::ObjectId;
 use chrono::{Duration, Utc};
 use dioxus::prelude::*;
 use gloo_storage::{LocalStorage, Storage};
-use nano_ai::client::NanoAI;
 use serde::{Deserialize, Serialize};
 use wasm_bindgen_futures::JsFuture;
 
@@ -21,6 +37,8 @@ pub struct Metadata {
     pub description: String,
     pub site_name: String,
     pub image_url: String,
+    #[serde(default)]
+    pub image_alt: String,
     pub brand_url: String,
     pub author: String,
     pub locale: String,
@@ -35,7 +53,7 @@ struct CachedOGsData {
 }
 
 #[component]
-pub fn CreateOGPanel(user_token: Signal<String>) -> Element {
+pub fn CreateOGPanel() -> Element {
     let theme = use_context::<Signal<Theme>>();
     let dark_mode = theme() == Theme::Dark;
 
@@ -44,13 +62,25 @@ pub fn CreateOGPanel(user_token: Signal<String>) -> Element {
         use_signal(|| "Your Gateway to Secure Open-Source Rusty SaaS Solutions.".to_string());
     let site_name = use_signal(|| "opensass.org".to_string());
     let image_url = use_signal(|| "https://opensass.org/logo.webp".to_string());
+    let mut image_alt = use_signal(String::new);
     let author = use_signal(|| "Mahmoud".to_string());
     let locale = use_signal(|| "en_US".to_string());
     let twitter_card = use_signal(|| "summary_large_image".to_string());
     let twitter_site = use_signal(|| "@opensassorg".to_string());
+    let site_url = use_signal(|| "https://opensass.org".to_string());
+    let keywords = use_signal(String::new);
+    let tone = use_signal(String::new);
+    let title_template = use_signal(String::new);
+    let description_template = use_signal(String::new);
+    let mut templates = use_signal(Vec::<PromptTemplate>::new);
+    let mut voice = use_signal(String::new);
+    let mut voices = use_signal(Vec::<BrandVoice>::new);
 
     let mut from_color = use_signal(|| String::from("purple-300"));
     let mut to_color = use_signal(|| String::from("pink-300"));
+    let mut background_prompt = use_signal(String::new);
+    let mut background_url = use_signal(String::new);
+    let mut painting = use_signal(|| false);
     let mut img_drag = use_signal(|| None);
     let mut img_position = use_signal(|| (0., 0.));
     let mut author_drag = use_signal(|| None);
@@ -68,11 +98,23 @@ pub fn CreateOGPanel(user_token: Signal<String>) -> Element {
     let mut locale_valid = use_signal(|| true);
     let mut twitter_card_valid = use_signal(|| true);
     let mut twitter_site_valid = use_signal(|| true);
+    let site_url_valid = use_signal(|| true);
+    let keywords_valid = use_signal(|| true);
+    let tone_valid = use_signal(|| true);
     let mut from_color_valid = use_signal(|| true);
     let mut to_color_valid = use_signal(|| true);
 
     let mut loading = use_signal(|| false);
     let mut generated_metadata = use_signal(|| None::<Metadata>);
+    // Meta tags come from the server once the OG is saved, and not at all while
+    // moderation holds it back.
+    let mut published_tags = use_signal(|| None::<String>);
+    let mut held_back = use_signal(|| false);
+    let mut suggesting = use_signal(|| None::<SuggestionField>);
+    let mut describing = use_signal(|| false);
+    let mut title_suggestions = use_signal(Vec::<Suggestion>::new);
+    let mut description_suggestions = use_signal(Vec::<Suggestion>::new);
+    let mut duplicates = use_signal(Vec::<SearchHit>::new);
     let mut toasts_manager = use_context::<Signal<ToastManager>>();
 
     let validate_field = |value: &str| !value.trim().is_empty();
@@ -159,25 +201,33 @@ pub fn CreateOGPanel(user_token: Signal<String>) -> Element {
         save_preview_as_image();
 
         let request = StoreOGRequest {
-            token: user_token(),
             title: title(),
             description: description(),
             site_name: site_name(),
             image_url: image_url(),
+            image_alt: image_alt(),
             brand_url: image_url(),
+            from_color: from_color(),
+            to_color: to_color(),
+            background_url: background_url(),
             author: author(),
             locale: locale(),
             twitter_card: twitter_card(),
             twitter_site: twitter_site(),
+            keywords: keywords(),
         };
         spawn(async move {
             match store_og(request).await {
                 Ok(response) => {
+                    image_alt.set(response.data.image_alt.clone());
+                    held_back.set(response.data.meta_tags.is_none());
+                    published_tags.set(response.data.meta_tags.clone());
                     let new_metadata = Metadata {
                         title: title(),
                         description: description(),
                         site_name: site_name(),
                         image_url: response.data.image_url,
+                        image_alt: response.data.image_alt,
                         brand_url: image_url(),
                         author: author(),
                         locale: locale(),
@@ -235,49 +285,131 @@ pub fn CreateOGPanel(user_token: Signal<String>) -> Element {
     let copy_to_clipboard = move |_| {
         if let Some(window) = web_sys::window() {
             spawn(async move {
-                let tags = generate_meta_tags(generated_metadata().unwrap_or_default());
+                let tags =
+                    published_tags().unwrap_or_else(|| generate_meta_tags(Metadata::default()));
                 let promise = window.navigator().clipboard().write_text(&tags);
                 let _ = JsFuture::from(promise).await;
             });
         }
     };
-    let handle_generate_title = move |_| {
-        spawn(async move {
-            let ai_generated_title = generate_ai_title().await.unwrap_or_else(|err| {
-                toasts_manager.set(
-                    toasts_manager()
-                        .add_toast(
-                            "Error".into(),
-                            err.to_string(),
-                            ToastType::Error,
-                            Some(Duration::seconds(5)),
-                        )
-                        .clone(),
-                );
-                title()
-            });
+    let _ = use_resource(move || async move {
+        if let Ok(response) = list_templates().await {
+            templates.set(response.data);
+        }
+        if let Ok(response) = list_voices().await {
+            voices.set(response.data);
+        }
+    });
 
-            title.set(ai_generated_title);
+    let mut request_suggestions = move |field: SuggestionField| {
+        suggesting.set(Some(field));
+        let template = match field {
+            SuggestionField::Title => title_template(),
+            SuggestionField::Description => description_template(),
+        };
+        let request = SuggestRequest {
+            field,
+            title: title(),
+            description: description(),
+            site_name: site_name(),
+            site_url: site_url(),
+            author: author(),
+            locale: locale(),
+            keywords: keywords(),
+            model: None,
+            template_id: ObjectId::parse_str(&template).ok(),
+            tone: tone(),
+            voice_id: ObjectId::parse_str(voice()).ok(),
+        };
+        spawn(async move {
+            match generate_suggestions(request).await {
+                Ok(response) => match field {
+                    SuggestionField::Title => title_suggestions.set(response.data),
+                    SuggestionField::Description => description_suggestions.set(response.data),
+                },
+                Err(err) => {
+                    toasts_manager.set(
+                        toasts_manager()
+                            .add_toast(
+                                "Error".into(),
+                                err.to_string(),
+                                ToastType::Error,
+                                Some(Duration::seconds(5)),
+                            )
+                            .clone(),
+                    );
+                }
+            }
+            suggesting.set(None);
         });
     };
-
-    let handle_generate_description = move |_| {
+    // Advisory only, so failures leave the previous warning in place.
+    let check_duplicates = move |_| {
+        let request = SimilarOGsRequest {
+            title: title(),
+            description: description(),
+            site_name: site_name(),
+            keywords: keywords(),
+        };
         spawn(async move {
-            let ai_generated_description = generate_ai_description().await.unwrap_or_else(|err| {
-                toasts_manager.set(
-                    toasts_manager()
-                        .add_toast(
-                            "Error".into(),
-                            err.to_string(),
-                            ToastType::Error,
-                            Some(Duration::seconds(5)),
-                        )
-                        .clone(),
-                );
-                description()
-            });
-
-            description.set(ai_generated_description);
+            if let Ok(response) = find_similar_ogs(request).await {
+                duplicates.set(response.data);
+            }
+        });
+    };
+    let request_alt_text = move |_| {
+        describing.set(true);
+        spawn(async move {
+            let request = AltTextRequest {
+                title: title(),
+                description: description(),
+                site_name: site_name(),
+                author: author(),
+                from_color: from_color(),
+                to_color: to_color(),
+                image: capture_preview().await,
+                model: None,
+            };
+            match generate_alt_text(request).await {
+                Ok(response) => image_alt.set(response.data),
+                Err(err) => {
+                    toasts_manager.set(
+                        toasts_manager()
+                            .add_toast(
+                                "Error".into(),
+                                err.to_string(),
+                                ToastType::Error,
+                                Some(Duration::seconds(5)),
+                            )
+                            .clone(),
+                    );
+                }
+            }
+            describing.set(false);
+        });
+    };
+    let request_background = move |_| {
+        painting.set(true);
+        let request = BackgroundRequest {
+            prompt: background_prompt(),
+        };
+        spawn(async move {
+            match generate_background(request).await {
+                Ok(response) => background_url.set(response.data),
+                Err(err) => {
+                    toasts_manager.set(
+                        toasts_manager()
+                            .add_toast(
+                                "Error".into(),
+                                err.to_string(),
+                                ToastType::Error,
+                                Some(Duration::seconds(5)),
+                            )
+                            .clone(),
+                    );
+                }
+            }
+            painting.set(false);
         });
     };
     fn download_preview_as_image() {
@@ -339,17 +471,19 @@ pub fn CreateOGPanel(user_token: Signal<String>) -> Element {
                                         title.set(input_value.clone());
                                         title_valid.set(validate_field(&input_value));
                                     },
+                                    onfocusout: check_duplicates,
                                     required: true
                                 }
                                 button {
                                     class: "px-4 py-2 h-[2rem] rounded bg-gray-500 text-white hover:bg-gray-600 transition flex items-center justify-end",
                                     r#type: "button",
                                     title: "AI Suggestion",
-                                    onclick: handle_generate_title,
-                                    disabled: loading(),
-                                    "💡"
+                                    onclick: move |_| request_suggestions(SuggestionField::Title),
+                                    disabled: loading() || suggesting().is_some(),
+                                    if suggesting() == Some(SuggestionField::Title) { "⏳" } else { "💡" }
                                 }
                             }
+                            SuggestionList { suggestions: title_suggestions, value: title }
                             if !title_valid() {
                                 p { class: "text-red-500 text-sm mt-1", "Invalid input" }
                             }
@@ -374,23 +508,122 @@ pub fn CreateOGPanel(user_token: Signal<String>) -> Element {
                                         description.set(input_value.clone());
                                         description_valid.set(validate_field(&input_value));
                                     },
+                                    onfocusout: check_duplicates,
                                     required: true
                                 }
                                 button {
                                     class: "px-4 py-2 h-[2rem] rounded bg-gray-500 text-white hover:bg-gray-600 transition flex items-center justify-end",
                                     r#type: "button",
                                     title: "AI Suggestion",
-                                    onclick: handle_generate_description,
-                                    disabled: loading(),
-                                    "💡"
+                                    onclick: move |_| request_suggestions(SuggestionField::Description),
+                                    disabled: loading() || suggesting().is_some(),
+                                    if suggesting() == Some(SuggestionField::Description) { "⏳" } else { "💡" }
                                 }
                             }
+                            SuggestionList { suggestions: description_suggestions, value: description }
                             if !description_valid() {
                                 p { class: "text-red-500 text-sm mt-1", "Invalid input" }
                             }
                         },
+                        if !duplicates().is_empty() {
+                            div {
+                                class: "p-3 rounded-md border border-yellow-400 bg-yellow-100 text-yellow-900 text-sm",
+                                p { class: "font-semibold", "⚠️ This looks nearly identical to OGs you already have:" }
+                                ul { class: "list-disc ml-5 mt-1",
+                                    for hit in duplicates() {
+                                        li {
+                                            Link {
+                                                to: Route::ViewOG { id: hit.og.id.to_string() },
+                                                class: "underline",
+                                                "{hit.og.title}"
+                                            }
+                                            " ({(hit.similarity * 100.0).round()}% similar)"
+                                        }
+                                    }
+                                }
+                            }
+                        }
                         InputField { label: "Site Name", value: site_name, is_valid: site_name_valid, validate: validate_field, required: false },
+                        InputField { label: "Site URL", value: site_url, is_valid: site_url_valid, validate: |_| true, required: false },
+                        InputField { label: "Keywords (comma separated)", value: keywords, is_valid: keywords_valid, validate: |_| true, required: false },
+                        InputField { label: "Tone (e.g. playful, formal)", value: tone, is_valid: tone_valid, validate: |_| true, required: false },
+                        if !voices().is_empty() {
+                            div {
+                                label {
+                                    class: format!("block text-sm font-medium {}", if dark_mode { "text-gray-300" } else { "text-gray-700" }),
+                                    "Brand voice"
+                                }
+                                select {
+                                    class: format!("mt-1 block w-full p-2 border rounded-md shadow-sm {}", if dark_mode { "bg-gray-900 border-gray-700" } else { "border-gray-300" }),
+                                    value: "{voice}",
+                                    oninput: move |e| voice.set(e.value()),
+                                    option { value: "", "None" }
+                                    for profile in voices() {
+                                        option { value: "{profile.id}", "{profile.name}" }
+                                    }
+                                }
+                            }
+                        }
+                        div {
+                            class: "grid grid-cols-1 md:grid-cols-2 gap-4",
+                            for (label, field, mut selected) in [
+                                ("Title prompt", SuggestionField::Title, title_template),
+                                ("Description prompt", SuggestionField::Description, description_template),
+                            ] {
+                                div {
+                                    label {
+                                        class: format!("block text-sm font-medium {}", if dark_mode { "text-gray-300" } else { "text-gray-700" }),
+                                        "{label}"
+                                    }
+                                    select {
+                                        class: format!("mt-1 block w-full p-2 border rounded-md shadow-sm {}", if dark_mode { "bg-gray-900 border-gray-700" } else { "border-gray-300" }),
+                                        value: "{selected}",
+                                        oninput: move |e| selected.set(e.value()),
+                                        option { value: "", "Built-in" }
+                                        for template in templates().into_iter().filter(|template| template.field == field) {
+                                            option {
+                                                value: "{template.id}",
+                                                if template.is_global() {
+                                                    "{template.name} (shared, v{template.version})"
+                                                } else {
+                                                    "{template.name} (v{template.version})"
+                                                }
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
                         InputField { label: "Brand Image", value: image_url, is_valid: image_url_valid, validate: validate_field, required: false },
+                        div {
+                            class: "items-center gap-x-2 w-full",
+                            label {
+                                class: format!("block text-sm font-medium {}", if dark_mode { "text-gray-300" } else { "text-gray-700" }),
+                                "Image Alt Text"
+                            }
+                            div {
+                                class: "flex gap-x-2",
+                                textarea {
+                                    class: format!(
+                                        "flex-grow mt-1 block w-full p-2 border rounded-md shadow-sm border-gray-300 {}",
+                                        if dark_mode { "bg-gray-900" } else { "" }
+                                    ),
+                                    rows: "2",
+                                    maxlength: "{IMAGE_ALT_MAX_CHARS}",
+                                    placeholder: "Written from the card when left empty",
+                                    value: "{image_alt}",
+                                    oninput: move |e| image_alt.set(e.value()),
+                                }
+                                button {
+                                    class: "px-4 py-2 h-[2rem] rounded bg-gray-500 text-white hover:bg-gray-600 transition flex items-center justify-end",
+                                    r#type: "button",
+                                    title: "Describe the card with AI",
+                                    onclick: request_alt_text,
+                                    disabled: loading() || describing(),
+                                    if describing() { "⏳" } else { "💡" }
+                                }
+                            }
+                        }
                         InputField { label: "Author", value: author, is_valid: author_valid, validate: validate_field, required: false },
                         div {
                             class: "mb-4 w-full",
@@ -418,6 +651,42 @@ pub fn CreateOGPanel(user_token: Signal<String>) -> Element {
                                 }
                             },
                         },
+                        div {
+                            class: "items-center gap-x-2 w-full",
+                            label {
+                                class: format!("block text-sm font-medium {}", if dark_mode { "text-gray-300" } else { "text-gray-700" }),
+                                "Background Image"
+                            }
+                            div {
+                                class: "flex gap-x-2",
+                                input {
+                                    class: format!(
+                                        "flex-grow mt-1 block w-full p-2 border rounded-md shadow-sm border-gray-300 {}",
+                                        if dark_mode { "bg-gray-900" } else { "" }
+                                    ),
+                                    placeholder: "Describe a background, e.g. misty pine forest at dawn",
+                                    value: "{background_prompt}",
+                                    oninput: move |e| background_prompt.set(e.value()),
+                                }
+                                button {
+                                    class: "px-4 py-2 h-[2rem] rounded bg-gray-500 text-white hover:bg-gray-600 transition flex items-center justify-end",
+                                    r#type: "button",
+                                    title: "Generate a background image",
+                                    onclick: request_background,
+                                    disabled: loading() || painting() || background_prompt().trim().is_empty(),
+                                    if painting() { "⏳" } else { "🎨" }
+                                }
+                                if !background_url().is_empty() {
+                                    button {
+                                        class: "px-4 py-2 h-[2rem] rounded bg-red-500 text-white hover:bg-red-600 transition flex items-center justify-end",
+                                        r#type: "button",
+                                        title: "Back to the gradient",
+                                        onclick: move |_| background_url.set(String::new()),
+                                        "✕"
+                                    }
+                                }
+                            }
+                        }
                         InputField { label: "Locale", value: locale, is_valid: locale_valid, validate: validate_field, required: false },
                         InputField { label: "Twitter Card Type", value: twitter_card, is_valid: twitter_card_valid, validate: validate_field, required: false },
                         InputField { label: "Twitter Site", value: twitter_site, is_valid: twitter_site_valid, validate: validate_field, required: false },
@@ -460,6 +729,14 @@ pub fn CreateOGPanel(user_token: Signal<String>) -> Element {
                             from_color(),
                             to_color()
                         ),
+                        style: if background_url().is_empty() {
+                                "".to_string()
+                            } else {
+                                format!(
+                                    "background-image: url('{}'); background-size: cover; background-position: center;",
+                                    background_url()
+                                )
+                            },
                         div {
                             class: "absolute w-full",
                             draggable: true,
@@ -585,17 +862,25 @@ pub fn CreateOGPanel(user_token: Signal<String>) -> Element {
                         r#type: "button",
                         "Download as Image"
                     }
-                    div {
-                        class: "mt-6 bg-gray-900 text-white p-4 rounded-lg shadow-md",
-                        h4 { class: "text-lg font-semibold mb-2", "Generated Meta Tags" },
-                        pre {
-                            class: "bg-gray-800 p-3 rounded text-sm overflow-x-auto",
-                            "{generate_meta_tags(generated_metadata().unwrap_or_default())}"
-                        },
-                        button {
-                            class: "mt-4 px-4 py-2 bg-indigo-500 text-white rounded-md hover:bg-green-600 transition",
-                            onclick: copy_to_clipboard,
-                            "Copy to Clipboard"
+                    if held_back() {
+                        div {
+                            class: "mt-6 p-4 rounded-lg border border-yellow-400 bg-yellow-100 text-yellow-900",
+                            h4 { class: "text-lg font-semibold mb-1", "Generated Meta Tags" },
+                            p { "This OG's copy was flagged by moderation and is held back until a moderator reviews it." }
+                        }
+                    } else {
+                        div {
+                            class: "mt-6 bg-gray-900 text-white p-4 rounded-lg shadow-md",
+                            h4 { class: "text-lg font-semibold mb-2", "Generated Meta Tags" },
+                            pre {
+                                class: "bg-gray-800 p-3 rounded text-sm overflow-x-auto",
+                                "{published_tags().unwrap_or_else(|| generate_meta_tags(Metadata::default()))}"
+                            },
+                            button {
+                                class: "mt-4 px-4 py-2 bg-indigo-500 text-white rounded-md hover:bg-green-600 transition",
+                                onclick: copy_to_clipboard,
+                                "Copy to Clipboard"
+                            }
                         }
                     }
                 }
@@ -612,69 +897,43 @@ fn generate_meta_tags(metadata: Metadata) -> String {
 <meta property=\"og:description\" content=\"{}\" />
 <meta property=\"og:site_name\" content=\"{}\" />
 <meta property=\"og:image\" content=\"{}\" />
+<meta property=\"og:image:alt\" content=\"{}\" />
 <meta property=\"og:author\" content=\"{}\" />
 <meta property=\"og:locale\" content=\"{}\" />
 <meta name=\"twitter:card\" content=\"{}\" />
-<meta name=\"twitter:site\" content=\"{}\" />",
-        metadata.title,
-        metadata.description,
-        metadata.title,
-        metadata.description,
-        metadata.site_name,
-        metadata.image_url,
-        metadata.author,
-        metadata.locale,
-        metadata.twitter_card,
-        metadata.twitter_site,
+<meta name=\"twitter:site\" content=\"{}\" />
+<meta name=\"twitter:image:alt\" content=\"{}\" />",
+        escape(&metadata.title),
+        escape(&metadata.description),
+        escape(&metadata.title),
+        escape(&metadata.description),
+        escape(&metadata.site_name),
+        escape(&metadata.image_url),
+        escape(&metadata.image_alt),
+        escape(&metadata.author),
+        escape(&metadata.locale),
+        escape(&metadata.twitter_card),
+        escape(&metadata.twitter_site),
+        escape(&metadata.image_alt),
     )
 }
 
-async fn generate_ai_title() -> Result<String, String> {
-    let mut client = NanoAI::new();
-    let system_prompt = format!(
-        "
-        **System Prompt (SP):** You are an expert in content generation for web metadata and SEO optimization.
-
-        **Prompt (P):** Generate a unique, concise, and creative image title for an OG (Open Graph) metadata tag.
-        The title should align with modern web standards, capture user attention, and concisely describe the associated website content.
-
-        **Expected Format (EF):**
-        - A short, unique title (maximum 20 characters) that is impactful and descriptive.
-
-        **Roleplay (RP):** Act as an experienced SEO copywriter crafting metadata titles for websites.
-        "
-    );
-
-    match client.create_session(None).await {
-        Ok(_) => match client.send_prompt(&system_prompt).await {
-            Ok(response) => Ok(response),
-            Err(err) => Err(err.to_string()),
-        },
-        Err(err) => Err(err.to_string()),
-    }
-}
-
-async fn generate_ai_description() -> Result<String, String> {
-    let mut client = NanoAI::new();
-    let system_prompt = format!(
-        "
-        **System Prompt (SP):** You are an expert in content generation for web metadata and SEO optimization.
-
-        **Prompt (P):** Generate a unique and concise description for an OG (Open Graph) metadata tag.
-        The description should provide a brief, engaging summary of the associated content, optimized for search engines and user engagement.
-
-        **Expected Format (EF):**
-        - A short sentence (maximum 60 characters) that is compelling, informative, and aligned with modern web best practices.
-
-        **Roleplay (RP):** Act as an experienced SEO copywriter crafting metadata descriptions for websites.
-        "
+/// The preview card as a base64 encoded PNG, for describing it to the AI.
+async fn capture_preview() -> Option<String> {
+    let mut eval = document::eval(
+        r#"
+        const element = document.getElementById('preview-section');
+        if (element) {
+            html2canvas(element, { useCORS: true, allowTaint: true })
+                .then((canvas) => dioxus.send(canvas.toDataURL('image/png').replace(/^data:image\/png;base64,/, '')))
+                .catch(() => dioxus.send(''));
+        } else {
+            dioxus.send('');
+        }
+        "#,
     );
-
-    match client.create_session(None).await {
-        Ok(_) => match client.send_prompt(&system_prompt).await {
-            Ok(response) => Ok(response),
-            Err(err) => Err(err.to_string()),
-        },
-        Err(err) => Err(err.to_string()),
-    }
+    eval.recv::<String>()
+        .await
+        .ok()
+        .filter(|image| !image.is_empty())
 }
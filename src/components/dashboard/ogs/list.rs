@@ -1,13 +1,17 @@
 use crate::components::spinner::Spinner;
 use crate::components::spinner::SpinnerSize;
+use crate::components::toast::manager::{ToastManager, ToastType};
 use crate::router::Route;
-use crate::server::og::controller::get_ogs_for_user;
+use crate::server::og::controller::list_ogs;
 use crate::server::og::model::OG;
-use crate::server::og::request::GetOGsForUserRequest;
+use crate::server::og::request::{ListOGsRequest, OGFilters, OGSort};
+use crate::server::og::response::OGListItem;
+use crate::server::search::controller::{search_ogs, text_search};
+use crate::server::search::request::{SemanticSearchRequest, TextSearchRequest};
+use crate::server::search::response::{SearchHit, SnippetPart, TextSearchResponse};
 use crate::theme::Theme;
-use chrono::Utc;
+use chrono::Duration;
 use dioxus::prelude::*;
-use gloo_storage::{LocalStorage, Storage};
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize)]
@@ -19,135 +23,438 @@ pub struct CachedOGsData {
 pub const CACHE_KEY: &str = "ogs_cache";
 pub const CACHE_TIMEOUT: i64 = 2 * 60 * 60;
 
+const PAGE_SIZE: u64 = 24;
+
+/// Reports whenever the sentinel below the OG grid is near the viewport,
+/// also after a page too short to scroll, until the panel unmounts.
+const SCROLL_SCRIPT: &str = r#"
+    const check = () => {
+        const sentinel = document.getElementById("ogs-sentinel");
+        if (!sentinel) {
+            clearInterval(timer);
+            document.removeEventListener("scroll", check, true);
+        } else if (sentinel.getBoundingClientRect().top < window.innerHeight + 400) {
+            dioxus.send(true);
+        }
+    };
+    const timer = setInterval(check, 1000);
+    document.addEventListener("scroll", check, { capture: true, passive: true });
+    await new Promise(() => {});
+"#;
+
 #[component]
-pub fn OGsPanel(user_token: Signal<String>) -> Element {
+pub fn OGsPanel() -> Element {
     let theme = use_context::<Signal<Theme>>();
     let dark_mode = theme() == Theme::Dark;
-    let mut ogs = use_signal(Vec::new);
-    let mut displayed_ogs = use_signal(Vec::new);
+    let mut items = use_signal(Vec::<OGListItem>::new);
+    let mut next_cursor = use_signal(|| None::<String>);
     let mut loading = use_signal(|| true);
+    let mut generation = use_signal(|| 0_u32);
+    let mut filters = use_signal(OGFilters::default);
+    let mut tags = use_signal(String::new);
+    let mut sort = use_signal(OGSort::default);
     let mut search_query = use_signal(String::new);
+    let mut text_results = use_signal(|| None::<TextSearchResponse>);
+    let mut search_generation = use_signal(|| 0_u32);
+    let mut question = use_signal(String::new);
+    let mut semantic_hits = use_signal(|| None::<Vec<SearchHit>>);
+    let mut searching = use_signal(|| false);
+    let mut toasts_manager = use_context::<Signal<ToastManager>>();
 
-    let _ = use_resource(move || async move {
-        let now = Utc::now().timestamp();
+    let mut notify_error = move |message: String| {
+        toasts_manager.set(
+            toasts_manager()
+                .add_toast(
+                    "Error".into(),
+                    message,
+                    ToastType::Error,
+                    Some(Duration::seconds(5)),
+                )
+                .clone(),
+        );
+    };
 
-        if let Ok(cached_data) = LocalStorage::get::<CachedOGsData>(CACHE_KEY) {
-            if now - cached_data.timestamp < CACHE_TIMEOUT {
-                loading.set(false);
-                ogs.set(cached_data.data.clone());
-                displayed_ogs.set(cached_data.data);
+    // Loads the first page when `reset` is set, otherwise the page after the
+    // ones shown. Responses to an outdated query are dropped.
+    let mut load_page = move |reset: bool| {
+        if !reset && (*loading.peek() || next_cursor.peek().is_none()) {
+            return;
+        }
+        let current = if reset {
+            *generation.peek() + 1
+        } else {
+            *generation.peek()
+        };
+        generation.set(current);
+        let request = ListOGsRequest {
+            cursor: if reset {
+                None
+            } else {
+                next_cursor.peek().clone()
+            },
+            limit: PAGE_SIZE,
+            sort: sort(),
+            filters: filters(),
+        };
+        loading.set(true);
+        spawn(async move {
+            let result = list_ogs(request).await;
+            if *generation.peek() != current {
                 return;
             }
-        }
-
-        match get_ogs_for_user(GetOGsForUserRequest {
-            token: user_token(),
-        })
-        .await
-        {
-            Ok(response) => {
-                let cached_data = CachedOGsData {
-                    data: response.data.clone(),
-                    timestamp: now,
-                };
-                let _ = LocalStorage::set(CACHE_KEY, &cached_data);
-
-                loading.set(false);
-                ogs.set(response.data.clone());
-                displayed_ogs.set(response.data);
+            match result {
+                Ok(response) => {
+                    let page = response.data;
+                    if reset {
+                        items.set(page.items);
+                    } else {
+                        items.write().extend(page.items);
+                    }
+                    next_cursor.set(page.next_cursor);
+                }
+                Err(err) => notify_error(err.to_string()),
             }
-            Err(_) => {
-                loading.set(false);
+            loading.set(false);
+        });
+    };
+
+    // Starts over whenever the filters or the sort order change.
+    use_effect(move || load_page(true));
+
+    use_effect(move || {
+        spawn(async move {
+            let mut eval = document::eval(SCROLL_SCRIPT);
+            while eval.recv::<bool>().await.is_ok() {
+                if semantic_hits.peek().is_none() && text_results.peek().is_none() {
+                    load_page(false);
+                }
             }
-        }
+        });
     });
 
-    let mut filter_ogs = move || {
-        let query = search_query().to_lowercase();
+    let handle_search = move |e: Event<FormData>| {
+        let query = e.value();
+        search_query.set(query.clone());
+        let current = *search_generation.peek() + 1;
+        search_generation.set(current);
+        if query.trim().is_empty() {
+            text_results.set(None);
+            return;
+        }
+        spawn(async move {
+            let result = text_search(TextSearchRequest { query, limit: None }).await;
+            if *search_generation.peek() != current {
+                return;
+            }
+            match result {
+                Ok(response) => {
+                    semantic_hits.set(None);
+                    text_results.set(Some(response.data));
+                }
+                Err(err) => notify_error(err.to_string()),
+            }
+        });
+    };
 
-        let filtered_ogs = ogs()
-            .iter()
-            .filter(|og| {
-                let matches_query = if query.is_empty() {
-                    true
-                } else {
-                    let title_matches = og.title.to_lowercase().contains(&query);
-                    title_matches
-                };
+    let handle_ask = move |e: Event<FormData>| {
+        e.prevent_default();
+        let request = SemanticSearchRequest {
+            query: question(),
+            limit: None,
+        };
+        searching.set(true);
+        spawn(async move {
+            match search_ogs(request).await {
+                Ok(response) => {
+                    search_query.set(String::new());
+                    text_results.set(None);
+                    semantic_hits.set(Some(response.data));
+                }
+                Err(err) => notify_error(err.to_string()),
+            }
+            searching.set(false);
+        });
+    };
 
-                matches_query
+    let input_class = format!(
+        "mt-1 block w-full p-2 border rounded-md shadow-sm {}",
+        if dark_mode { "bg-gray-900" } else { "" },
+    );
+    let cards: Vec<(OG, String, &'static str)> = match semantic_hits() {
+        Some(hits) => hits
+            .into_iter()
+            .map(|hit| {
+                let similarity = format!("{:.0}% match", hit.similarity * 100.0);
+                (hit.og, similarity, "bg-blue-500 text-white")
             })
-            .cloned()
-            .collect::<Vec<_>>();
-
-        displayed_ogs.set(filtered_ogs);
+            .collect(),
+        None => items()
+            .into_iter()
+            .map(|item| (item.og, item.score.to_string(), score_class(item.score)))
+            .collect(),
     };
 
     rsx! {
         div {
             div {
                 div {
-                    class: "w-full md:w-1/3 pb-4 mb-4 md:mb-0 flex flex-col gap-8",
+                    class: "w-full pb-4 mb-4 grid grid-cols-1 md:grid-cols-3 gap-6",
 
                     div {
                         h3 { class: "text-2xl font-bold mb-4", "Search" }
                         input {
-                            class: format!(
-                                "mt-1 block w-full p-2 border rounded-md shadow-sm {}",
-                                if dark_mode { "bg-gray-900" } else { "" },
-                            ),
-                            placeholder: "Search by title...",
-                            value: "{search_query()}",
+                            class: "{input_class}",
+                            placeholder: "Search titles, descriptions and chats...",
+                            value: "{search_query}",
+                            oninput: handle_search,
+                        }
+                        form {
+                            class: "mt-4",
+                            onsubmit: handle_ask,
+                            h3 { class: "text-lg font-semibold mb-2", "Ask" }
+                            div { class: "flex gap-x-2",
+                                input {
+                                    class: "flex-grow {input_class}",
+                                    placeholder: "e.g. cards about our spring launch",
+                                    value: "{question}",
+                                    oninput: move |e| question.set(e.value()),
+                                }
+                                button {
+                                    class: "mt-1 px-4 rounded bg-blue-500 text-white",
+                                    r#type: "submit",
+                                    disabled: searching() || question().trim().is_empty(),
+                                    if searching() { "⏳" } else { "🔎" }
+                                }
+                            }
+                            if semantic_hits().is_some() {
+                                div { class: "flex items-center justify-between mt-2 text-sm text-gray-500",
+                                    span { "Showing the closest matches" }
+                                    button {
+                                        class: "underline",
+                                        r#type: "button",
+                                        onclick: move |_| semantic_hits.set(None),
+                                        "Clear"
+                                    }
+                                }
+                            }
+                        }
+                    }
+                    div { class: "grid grid-cols-2 gap-x-4 gap-y-2 content-start",
+                        h3 { class: "col-span-2 text-lg font-semibold", "Filters" }
+                        div {
+                            label { class: "block text-sm font-medium", "Locale" }
+                            input {
+                                class: "{input_class}",
+                                placeholder: "en_US",
+                                value: "{filters().locale}",
+                                oninput: move |e| filters.write().locale = e.value(),
+                            }
+                        }
+                        div {
+                            label { class: "block text-sm font-medium", "Site name" }
+                            input {
+                                class: "{input_class}",
+                                value: "{filters().site_name}",
+                                oninput: move |e| filters.write().site_name = e.value(),
+                            }
+                        }
+                        div {
+                            label { class: "block text-sm font-medium", "Twitter card" }
+                            select {
+                                class: "{input_class}",
+                                value: "{filters().twitter_card}",
+                                oninput: move |e| filters.write().twitter_card = e.value(),
+                                option { value: "", "Any" }
+                                option { value: "summary", "summary" }
+                                option { value: "summary_large_image", "summary_large_image" }
+                                option { value: "app", "app" }
+                                option { value: "player", "player" }
+                            }
+                        }
+                        div {
+                            label { class: "block text-sm font-medium", "Tags (comma separated)" }
+                            input {
+                                class: "{input_class}",
+                                value: "{tags}",
+                                oninput: move |e| {
+                                    tags.set(e.value());
+                                    filters.write().tags = e
+                                        .value()
+                                        .split(',')
+                                        .map(|tag| tag.trim().to_string())
+                                        .filter(|tag| !tag.is_empty())
+                                        .collect();
+                                },
+                            }
+                        }
+                        div {
+                            label { class: "block text-sm font-medium", "Created from" }
+                            input {
+                                class: "{input_class}",
+                                r#type: "date",
+                                value: "{filters().created_from}",
+                                oninput: move |e| filters.write().created_from = e.value(),
+                            }
+                        }
+                        div {
+                            label { class: "block text-sm font-medium", "Created to" }
+                            input {
+                                class: "{input_class}",
+                                r#type: "date",
+                                value: "{filters().created_to}",
+                                oninput: move |e| filters.write().created_to = e.value(),
+                            }
+                        }
+                    }
+                    div {
+                        h3 { class: "text-lg font-semibold mb-2", "Sort by" }
+                        select {
+                            class: "{input_class}",
+                            disabled: semantic_hits().is_some(),
                             oninput: move |e| {
-                                search_query.set(e.value());
-                                filter_ogs();
+                                sort.set(match e.value().as_str() {
+                                    "oldest" => OGSort::Oldest,
+                                    "recently_updated" => OGSort::RecentlyUpdated,
+                                    "least_recently_updated" => OGSort::LeastRecentlyUpdated,
+                                    "title_asc" => OGSort::TitleAsc,
+                                    "title_desc" => OGSort::TitleDesc,
+                                    "score_desc" => OGSort::ScoreDesc,
+                                    "score_asc" => OGSort::ScoreAsc,
+                                    _ => OGSort::Newest,
+                                })
                             },
+                            option { value: "newest", selected: sort() == OGSort::Newest, "Newest first" }
+                            option { value: "oldest", selected: sort() == OGSort::Oldest, "Oldest first" }
+                            option { value: "recently_updated", selected: sort() == OGSort::RecentlyUpdated, "Recently updated" }
+                            option { value: "least_recently_updated", selected: sort() == OGSort::LeastRecentlyUpdated, "Least recently updated" }
+                            option { value: "title_asc", selected: sort() == OGSort::TitleAsc, "Title, A to Z" }
+                            option { value: "title_desc", selected: sort() == OGSort::TitleDesc, "Title, Z to A" }
+                            option { value: "score_desc", selected: sort() == OGSort::ScoreDesc, "SEO score, best first" }
+                            option { value: "score_asc", selected: sort() == OGSort::ScoreAsc, "SEO score, worst first" }
                         }
                     }
                 }
-                h2 { class: "text-xl font-semibold mb-4", "All OGs" }
-                if displayed_ogs().len() > 0 {
-                    div {
-                        class: "grid grid-cols-1 sm:grid-cols-2 md:grid-cols-3 lg:grid-cols-4 gap-6",
-                        for og in displayed_ogs() {
-                            Link {
-                                to: Route::ViewOG { id: og.id.to_string() },
-                                class: format!(
-                                    "p-4 shadow rounded-lg {}",
-                                    if dark_mode { "bg-gray-700" } else { "bg-gray-100" }
-                                ),
-                                img {
-                                    src: og.image_url,
-                                    alt: "OG cover",
-                                    class: "w-full h-48 object-cover rounded-md mb-4"
+                if let Some(results) = text_results() {
+                    h2 { class: "text-xl font-semibold mb-4", "Search Results" }
+                    if results.ogs.is_empty() && results.messages.is_empty() {
+                        p { class: "px-4 py-2", "Nothing matches \"{search_query}\"." }
+                    }
+                    ul { class: "space-y-3 mb-6",
+                        for hit in results.ogs {
+                            li {
+                                class: format!("p-4 shadow rounded-lg {}", if dark_mode { "bg-gray-700" } else { "bg-gray-100" }),
+                                Link {
+                                    to: Route::ViewOG { id: hit.og.id.to_string() },
+                                    class: "text-lg font-semibold hover:underline",
+                                    if hit.og.title.trim().is_empty() { "Untitled OG" } else { "{hit.og.title}" }
                                 }
-                                p {
-                                    class: "text-sm text-gray-500 mb-2",
-                                    "{og.created_at.format(\"%B %d, %Y\")}"
+                                for snippet in hit.snippets.into_iter().filter(|snippet| snippet.field != "title") {
+                                    p { class: "text-sm mt-1",
+                                        span { class: "text-xs uppercase text-gray-500 mr-2", "{snippet.field.replace('_', \" \")}" }
+                                        Highlighted { parts: snippet.parts }
+                                    }
                                 }
-                                p {
-                                    class: "mt-2 text-xl text-gray-100",
-                                    "{og.title.chars().take(30).collect::<String>()}"
+                            }
+                        }
+                    }
+                    if !results.messages.is_empty() {
+                        h3 { class: "text-lg font-semibold mb-2", "In conversations" }
+                        ul { class: "space-y-3",
+                            for hit in results.messages {
+                                li {
+                                    class: format!("p-4 shadow rounded-lg {}", if dark_mode { "bg-gray-700" } else { "bg-gray-100" }),
+                                    Link {
+                                        to: Route::ViewOG { id: hit.conversation.og.to_string() },
+                                        class: "font-semibold hover:underline",
+                                        "{hit.conversation.title}"
+                                    }
+                                    p { class: "text-sm mt-1",
+                                        span { class: "text-xs uppercase text-gray-500 mr-2", "{hit.message.sender}" }
+                                        Highlighted { parts: hit.snippet }
+                                    }
                                 }
                             }
                         }
                     }
                 } else {
-                    p {
-                        class: "flex items-center space-x-2 px-4 py-2 rounded",
-                        if loading() {
-                            Spinner {
-                                aria_label: "Loading spinner".to_string(),
-                                size: SpinnerSize::Md,
-                                dark_mode: true,
+                    h2 { class: "text-xl font-semibold mb-4", "All OGs" }
+                    if !cards.is_empty() {
+                        div {
+                            class: "grid grid-cols-1 sm:grid-cols-2 md:grid-cols-3 lg:grid-cols-4 gap-6",
+                            for (og, badge, badge_class) in cards {
+                                Link {
+                                    to: Route::ViewOG { id: og.id.to_string() },
+                                    class: format!(
+                                        "p-4 shadow rounded-lg {}",
+                                        if dark_mode { "bg-gray-700" } else { "bg-gray-100" }
+                                    ),
+                                    img {
+                                        src: og.image_url,
+                                        alt: "OG cover",
+                                        class: "w-full h-48 object-cover rounded-md mb-4"
+                                    }
+                                    div {
+                                        class: "flex items-center justify-between mb-2",
+                                        p {
+                                            class: "text-sm text-gray-500",
+                                            "{og.created_at.format(\"%B %d, %Y\")}"
+                                        }
+                                        span {
+                                            class: format!("px-2 py-0.5 rounded-full text-xs font-bold {}", badge_class),
+                                            title: if semantic_hits().is_some() { "Similarity" } else { "SEO score" },
+                                            "{badge}"
+                                        }
+                                    }
+                                    p {
+                                        class: "mt-2 text-xl text-gray-100",
+                                        "{og.title.chars().take(30).collect::<String>()}"
+                                    }
+                                }
                             }
-                            span { "Loading ogs..." }
-                        } else {
+                        }
+                    } else if !loading() {
+                        p {
+                            class: "flex items-center space-x-2 px-4 py-2 rounded",
                             span { "No ogs match your search filter." }
                         }
                     }
                 }
+                div { id: "ogs-sentinel", class: "h-1" }
+                if loading() {
+                    p {
+                        class: "flex items-center space-x-2 px-4 py-2 rounded",
+                        Spinner {
+                            aria_label: "Loading spinner".to_string(),
+                            size: SpinnerSize::Md,
+                            dark_mode: true,
+                        }
+                        span { "Loading ogs..." }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Snippet text with the matched terms marked.
+#[component]
+fn Highlighted(parts: Vec<SnippetPart>) -> Element {
+    rsx! {
+        for part in parts {
+            if part.highlight {
+                mark { class: "bg-yellow-300 text-gray-900 rounded px-0.5", "{part.text}" }
+            } else {
+                span { "{part.text}" }
             }
         }
     }
 }
+
+/// Badge colors for an SEO score.
+pub fn score_class(score: u32) -> &'static str {
+    match score {
+        80.. => "bg-green-500 text-white",
+        50..=79 => "bg-yellow-400 text-gray-900",
+        _ => "bg-red-500 text-white",
+    }
+}
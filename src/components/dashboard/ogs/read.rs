@@ -1,34 +1,116 @@
+use crate::components::dashboard::ogs::list::score_class;
 use crate::components::spinner::Spinner;
 use crate::components::spinner::SpinnerSize;
-use crate::server::og::controller::get_og_for_user;
+use crate::components::toast::manager::{ToastManager, ToastType};
+use crate::router::Route;
+use crate::server::ai::controller::localize_og;
+use crate::server::ai::request::LocalizeOGRequest;
+use crate::server::moderation::model::ReviewStatus;
+use crate::server::og::controller::{
+    get_og_for_user, get_og_meta_tags, get_og_score, get_og_variants,
+};
 use crate::server::og::model::OG;
 use crate::server::og::request::GetOGForUserRequest;
+use crate::server::og::score::SeoScore;
 use crate::theme::Theme;
+use chrono::Duration;
 use dioxus::prelude::*;
 
 #[component]
-pub fn ViewOGPanel(og_id: String, user_token: Signal<String>) -> Element {
+pub fn ViewOGPanel(og_id: String) -> Element {
     let theme = use_context::<Signal<Theme>>();
     let dark_mode = theme() == Theme::Dark;
     let mut selected_og = use_signal(|| None::<OG>);
     let mut loading = use_signal(|| true);
+    let mut variants = use_signal(Vec::<OG>::new);
+    let mut tags = use_signal(|| None::<String>);
+    let mut score = use_signal(|| None::<SeoScore>);
+    let mut locales = use_signal(String::new);
+    let mut localizing = use_signal(|| false);
+    let mut toasts_manager = use_context::<Signal<ToastManager>>();
 
-    let _ = use_resource(move || {
-        let og_id_cloned = og_id.clone();
-        async move {
-            if let Ok(response) = get_og_for_user(GetOGForUserRequest {
-                og_id: og_id_cloned,
-                token: user_token(),
+    // Reruns when a variant link swaps the OG shown.
+    let _ = use_resource(use_reactive!(|(og_id,)| async move {
+        tags.set(
+            get_og_meta_tags(GetOGForUserRequest {
+                og_id: og_id.clone(),
             })
             .await
-            {
-                loading.set(false);
-                selected_og.set(Some(response.data));
-            } else {
-                loading.set(false);
-            }
+            .ok()
+            .and_then(|response| response.data),
+        );
+        if let Ok(response) = get_og_for_user(GetOGForUserRequest {
+            og_id: og_id.clone(),
+        })
+        .await
+        {
+            loading.set(false);
+            selected_og.set(Some(response.data));
+        } else {
+            loading.set(false);
+        }
+        if let Ok(response) = get_og_variants(GetOGForUserRequest {
+            og_id: og_id.clone(),
+        })
+        .await
+        {
+            variants.set(response.data);
         }
-    });
+        score.set(
+            get_og_score(GetOGForUserRequest { og_id })
+                .await
+                .ok()
+                .map(|response| response.data),
+        );
+    }));
+
+    let handle_localize = move |_| {
+        let Some(og) = selected_og() else {
+            return;
+        };
+        localizing.set(true);
+        let request = LocalizeOGRequest {
+            og_id: og.id.to_hex(),
+            locales: locales()
+                .split(',')
+                .map(|locale| locale.trim().to_string())
+                .filter(|locale| !locale.is_empty())
+                .collect(),
+            model: None,
+        };
+        spawn(async move {
+            let (title, message, toast_type) = match localize_og(request).await {
+                Ok(response) => {
+                    variants.set(response.data);
+                    locales.set(String::new());
+                    if let Ok(response) = get_og_meta_tags(GetOGForUserRequest {
+                        og_id: og.id.to_hex(),
+                    })
+                    .await
+                    {
+                        tags.set(response.data);
+                    }
+                    (
+                        "Info",
+                        "Localized variants are ready".to_string(),
+                        ToastType::Success,
+                    )
+                }
+                Err(err) => ("Error", err.to_string(), ToastType::Error),
+            };
+            toasts_manager.set(
+                toasts_manager()
+                    .add_toast(
+                        title.into(),
+                        message,
+                        toast_type,
+                        Some(Duration::seconds(5)),
+                    )
+                    .clone(),
+            );
+            localizing.set(false);
+        });
+    };
     fn download_preview_as_image() {
         client! {
             document::eval(r#"
@@ -54,7 +136,19 @@ pub fn ViewOGPanel(og_id: String, user_token: Signal<String>) -> Element {
                 if let Some(og) = selected_og() {
                     div {
                         id: "preview-section",
-                        class: "relative bg-gradient-to-r from-purple-300 to-pink-300 p-4 rounded-lg shadow-md min-h-screen w-full aspect-w-16 aspect-h-9",
+                        class: format!(
+                            "relative bg-gradient-to-r from-{} to-{} p-4 rounded-lg shadow-md min-h-screen w-full aspect-w-16 aspect-h-9",
+                            if og.from_color.is_empty() { "purple-300" } else { &og.from_color },
+                            if og.to_color.is_empty() { "pink-300" } else { &og.to_color },
+                        ),
+                        style: if og.background_url.is_empty() {
+                                "".to_string()
+                            } else {
+                                format!(
+                                    "background-image: url('{}'); background-size: cover; background-position: center;",
+                                    og.background_url
+                                )
+                            },
                         h1 {
                             class: "absolute top-4 left-4 text-4xl font-bold text-gray-900",
                             contenteditable: true,
@@ -82,6 +176,84 @@ pub fn ViewOGPanel(og_id: String, user_token: Signal<String>) -> Element {
                         r#type: "button",
                         "Download as Image"
                     }
+                    if let Some(score) = score() {
+                        div {
+                            class: format!("mt-6 p-4 rounded-lg border {}", if dark_mode { "border-gray-700" } else { "border-gray-300" }),
+                            div { class: "flex items-center justify-between mb-4",
+                                h4 { class: "text-lg font-semibold", "SEO Score" }
+                                span { class: format!("px-3 py-1 rounded-full text-lg font-bold {}", score_class(score.total)), "{score.total}/100" }
+                            }
+                            ul { class: "space-y-3",
+                                for factor in score.factors {
+                                    li {
+                                        div { class: "flex justify-between text-sm",
+                                            span { class: "font-medium", "{factor.name}" }
+                                            span { class: "text-gray-500", "{factor.points():.0}/{factor.weight}" }
+                                        }
+                                        div { class: "w-full h-2 rounded bg-gray-300 mt-1",
+                                            div {
+                                                class: "h-2 rounded bg-blue-500",
+                                                style: format!("width: {}%", (factor.score * 100.0).round()),
+                                            }
+                                        }
+                                        p { class: "text-xs text-gray-500 mt-1", "{factor.detail}" }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                    div {
+                        class: format!("mt-6 p-4 rounded-lg border {}", if dark_mode { "border-gray-700" } else { "border-gray-300" }),
+                        h4 { class: "text-lg font-semibold mb-2", "Localized Variants" }
+                        div { class: "flex flex-wrap gap-2 mb-4",
+                            for variant in variants() {
+                                Link {
+                                    class: format!("px-3 py-1 rounded-full text-sm {}",
+                                        if variant.id == og.id { "bg-blue-500 text-white" } else { "bg-gray-200 text-gray-900 hover:bg-blue-100" }
+                                    ),
+                                    to: Route::ViewOG { id: variant.id.to_string() },
+                                    if variant.locale.is_empty() { "—" } else { "{variant.locale}" }
+                                }
+                            }
+                        }
+                        div { class: "flex gap-x-2",
+                            input {
+                                class: format!("flex-grow p-2 border rounded-md shadow-sm {}", if dark_mode { "bg-gray-900 border-gray-700" } else { "border-gray-300" }),
+                                placeholder: "Target locales, e.g. fr_FR, de_DE, es_ES",
+                                value: "{locales}",
+                                oninput: move |e| locales.set(e.value()),
+                            }
+                            button {
+                                class: "px-4 py-2 rounded bg-blue-500 text-white",
+                                disabled: localizing() || locales().trim().is_empty(),
+                                onclick: handle_localize,
+                                if localizing() { "Translating..." } else { "Translate" }
+                            }
+                        }
+                        p { class: "text-xs text-gray-500 mt-1",
+                            "Variants keep this OG's layout and image; the title, description and alt text are translated."
+                        }
+                    }
+                    if let Some(meta) = tags() {
+                        div {
+                            class: "mt-6 bg-gray-900 text-white p-4 rounded-lg shadow-md",
+                            h4 { class: "text-lg font-semibold mb-2", "Meta Tags" }
+                            pre {
+                                class: "bg-gray-800 p-3 rounded text-sm overflow-x-auto",
+                                "{meta}"
+                            }
+                        }
+                    } else if !og.is_publishable() {
+                        div {
+                            class: "mt-6 p-4 rounded-lg border border-yellow-400 bg-yellow-100 text-yellow-900",
+                            h4 { class: "text-lg font-semibold mb-1", "Meta Tags" }
+                            if og.moderation == Some(ReviewStatus::Rejected) {
+                                p { "A moderator rejected this OG's copy, so it cannot be published. Edit the title or description to try again." }
+                            } else {
+                                p { "This OG's copy was flagged by moderation and is held back until a moderator reviews it." }
+                            }
+                        }
+                    }
                 } else {
                     p {
                         class: "flex items-center space-x-2 px-4 py-2 rounded",
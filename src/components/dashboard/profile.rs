@@ -1,39 +1,34 @@
+pub(crate) mod account;
 pub(crate) mod edit;
+pub(crate) mod usage;
 pub(crate) mod view;
 
+use crate::components::dashboard::profile::account::AccountData;
 use crate::components::dashboard::profile::edit::ProfileForm;
+use crate::components::dashboard::profile::usage::AIUsagePanel;
 use crate::components::dashboard::profile::view::ProfileDetails;
 use crate::server::auth::controller::about_me;
 use crate::server::auth::model::User;
 use crate::theme::Theme;
 
 use dioxus::prelude::*;
-use gloo_storage::SessionStorage;
-use gloo_storage::Storage;
 
 #[component]
 pub fn ProfilePagePanel() -> Element {
     let theme = use_context::<Signal<Theme>>();
     let dark_mode = theme() == Theme::Dark;
-    let mut user_token = use_signal(|| "".to_string());
     let mut user_data = use_signal(|| None::<User>);
     let mut edit_mode = use_signal(|| false);
     let navigator = use_navigator();
 
     use_effect(move || {
         spawn(async move {
-            let token: String = SessionStorage::get("jwt").unwrap_or_default();
-            if token.is_empty() {
-                navigator.push("/login");
-            } else {
-                match about_me(token.clone()).await {
-                    Ok(res) => {
-                        user_data.set(Some(res.data.user));
-                        user_token.set(token.clone());
-                    }
-                    Err(_) => {
-                        navigator.push("/login");
-                    }
+            match about_me().await {
+                Ok(res) => {
+                    user_data.set(Some(res.data.user));
+                }
+                Err(_) => {
+                    navigator.push("/login");
                 }
             }
         });
@@ -55,9 +50,11 @@ pub fn ProfilePagePanel() -> Element {
                         match user_data.as_ref() {
                             Some(user) => rsx! {
                                 if edit_mode() {
-                                    ProfileForm { user: user.clone(), dark_mode, user_token }
+                                    ProfileForm { user: user.clone(), dark_mode }
                                 } else {
-                                    ProfileDetails { user: user.clone(), dark_mode, user_token }
+                                    ProfileDetails { user: user.clone(), dark_mode }
+                                    AIUsagePanel { user: user.clone(), dark_mode }
+                                    AccountData { user: user.clone(), dark_mode }
                                 }
                             },
                             None => rsx!(p { "Loading..." })
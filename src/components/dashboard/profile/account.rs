@@ -0,0 +1,144 @@
+use crate::components::dashboard::profile::view::ProfileDetailsProps;
+use crate::components::toast::manager::{ToastManager, ToastType};
+use crate::server::account::controller::{
+    cancel_account_deletion, export_account_data, request_account_deletion,
+};
+use crate::server::account::request::DeleteAccountRequest;
+use chrono::Duration;
+use dioxus::prelude::*;
+
+#[allow(unused_variables)]
+fn download_archive(file_name: String, archive: String) {
+    client! {
+        document::eval(&format!(
+            r#"
+            const link = document.createElement('a');
+            link.download = '{}';
+            link.href = 'data:application/zip;base64,{}';
+            link.click();
+            "#,
+            file_name, archive
+        ));
+    }
+}
+
+#[component]
+pub fn AccountData(props: ProfileDetailsProps) -> Element {
+    let dark_mode = props.dark_mode;
+    let mut deletion_scheduled_at = use_signal(|| props.user.deletion_scheduled_at);
+    let mut password = use_signal(String::new);
+    let mut exporting = use_signal(|| false);
+    let mut toasts_manager = use_context::<Signal<ToastManager>>();
+
+    let mut notify = move |title: &str, body: String, toast_type: ToastType| {
+        toasts_manager.set(
+            toasts_manager()
+                .add_toast(title.into(), body, toast_type, Some(Duration::seconds(5)))
+                .clone(),
+        );
+    };
+    let error_message = |err: ServerFnError| {
+        err.to_string()
+            .split("error running server function:")
+            .nth(1)
+            .unwrap_or("")
+            .trim()
+            .to_string()
+    };
+
+    let handle_export = move |_| {
+        exporting.set(true);
+        spawn(async move {
+            match export_account_data().await {
+                Ok(response) => download_archive(response.data.file_name, response.data.archive),
+                Err(err) => notify("Error", error_message(err), ToastType::Error),
+            }
+            exporting.set(false);
+        });
+    };
+
+    let handle_delete = move |_| {
+        spawn(async move {
+            match request_account_deletion(DeleteAccountRequest {
+                password: password(),
+            })
+            .await
+            {
+                Ok(response) => {
+                    password.set(String::new());
+                    deletion_scheduled_at.set(response.data.deletion_scheduled_at);
+                    notify(
+                        "Warning",
+                        "Your account is scheduled for deletion.".into(),
+                        ToastType::Warning,
+                    );
+                }
+                Err(err) => notify("Error", error_message(err), ToastType::Error),
+            }
+        });
+    };
+
+    let handle_cancel = move |_| {
+        spawn(async move {
+            match cancel_account_deletion().await {
+                Ok(_) => {
+                    deletion_scheduled_at.set(None);
+                    notify(
+                        "Success",
+                        "Account deletion cancelled.".into(),
+                        ToastType::Success,
+                    );
+                }
+                Err(err) => notify("Error", error_message(err), ToastType::Error),
+            }
+        });
+    };
+
+    rsx! {
+        div { class: "mt-8 space-y-6",
+            div { class: "space-y-2",
+                h3 { class: "text-lg font-semibold", "Your Data" }
+                p { class: "text-sm text-gray-500",
+                    "Download a zip archive with your profile, OGs, images, conversations and messages."
+                }
+                button {
+                    class: "py-2 px-4 rounded bg-blue-500 text-white",
+                    disabled: exporting(),
+                    onclick: handle_export,
+                    if exporting() { "Preparing export..." } else { "Export My Data" }
+                }
+            }
+
+            div { class: "space-y-2 border border-red-500 rounded-md p-4",
+                h3 { class: "text-lg font-semibold text-red-500", "Delete Account" }
+                if let Some(scheduled_at) = deletion_scheduled_at() {
+                    p {
+                        "Your account and all of its data will be permanently deleted on {scheduled_at.format(\"%B %d, %Y\")}."
+                    }
+                    button {
+                        class: "py-2 px-4 rounded bg-gray-500 text-white",
+                        onclick: handle_cancel,
+                        "Cancel Deletion"
+                    }
+                } else {
+                    p { class: "text-sm text-gray-500",
+                        "Your OGs, conversations, stored images and the rest of your account data are removed once the grace period ends. You can cancel until then."
+                    }
+                    input {
+                        class: format!("mt-1 block w-full p-2 border rounded-md shadow-sm {}", if dark_mode { "bg-gray-900 border-gray-700" } else { "border-gray-300" }),
+                        r#type: "password",
+                        placeholder: "Confirm your password",
+                        value: "{password}",
+                        oninput: move |e| password.set(e.value()),
+                    }
+                    button {
+                        class: "py-2 px-4 rounded bg-red-600 text-white disabled:opacity-50",
+                        disabled: password().is_empty(),
+                        onclick: handle_delete,
+                        "Delete My Account"
+                    }
+                }
+            }
+        }
+    }
+}
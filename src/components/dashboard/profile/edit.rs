@@ -10,7 +10,6 @@ use dioxus::prelude::*;
 pub fn ProfileForm(props: ProfileDetailsProps) -> Element {
     let user = &props.user;
     let dark_mode = props.dark_mode;
-    let user_token = props.user_token;
 
     let name = use_signal(|| user.name.clone());
     // default to lord Ferris
@@ -41,7 +40,6 @@ pub fn ProfileForm(props: ProfileDetailsProps) -> Element {
 
     let handle_submit = move |evt: Event<FormData>| {
         evt.stop_propagation();
-        let user_token = user_token.clone();
 
         let mut all_valid = true;
 
@@ -84,7 +82,6 @@ pub fn ProfileForm(props: ProfileDetailsProps) -> Element {
             spawn({
                 async move {
                     match edit_profile(EditUserSchema {
-                        token: user_token,
                         name: Some(name()),
                         email: Some(email()),
                         photo: Some(photo()),
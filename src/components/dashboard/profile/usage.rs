@@ -0,0 +1,88 @@
+use crate::components::dashboard::profile::view::ProfileDetailsProps;
+use crate::server::usage::controller::get_ai_usage;
+use crate::server::usage::response::UsageResponse;
+use dioxus::prelude::*;
+
+/// `used` out of `limit`, where a zero limit means unlimited.
+fn allowance(used: u64, limit: u64) -> String {
+    if limit == 0 {
+        format!("{used} (unlimited)")
+    } else {
+        format!("{used} / {limit}")
+    }
+}
+
+fn percent(used: u64, limit: u64) -> u64 {
+    (used * 100).checked_div(limit).unwrap_or(0).min(100)
+}
+
+#[component]
+pub fn AIUsagePanel(props: ProfileDetailsProps) -> Element {
+    let dark_mode = props.dark_mode;
+    let mut usage = use_signal(|| None::<UsageResponse>);
+
+    let _ = use_resource(move || async move {
+        if let Ok(response) = get_ai_usage().await {
+            usage.set(Some(response.data));
+        }
+    });
+
+    let bar = if dark_mode {
+        "w-full h-2 rounded bg-gray-700"
+    } else {
+        "w-full h-2 rounded bg-gray-200"
+    };
+
+    rsx! {
+        div { class: "mt-8 space-y-2",
+            h3 { class: "text-lg font-semibold", "AI Usage" }
+            if let Some(usage) = usage() {
+                p { class: "text-sm text-gray-500",
+                    "Today's allowance resets at midnight UTC. Tokens are estimated from text length."
+                }
+                div { class: "grid grid-cols-1 gap-4 md:grid-cols-2",
+                    div { class: "space-y-1",
+                        span { class: "font-bold", "Requests today: " }
+                        span { "{allowance(usage.today.requests, usage.quota.requests)}" }
+                        div { class: "{bar}",
+                            div {
+                                class: "h-2 rounded bg-blue-500",
+                                style: "width: {percent(usage.today.requests, usage.quota.requests)}%",
+                            }
+                        }
+                    }
+                    div { class: "space-y-1",
+                        span { class: "font-bold", "Tokens today: " }
+                        span { "{allowance(usage.today.tokens, usage.quota.tokens)}" }
+                        div { class: "{bar}",
+                            div {
+                                class: "h-2 rounded bg-blue-500",
+                                style: "width: {percent(usage.today.tokens, usage.quota.tokens)}%",
+                            }
+                        }
+                    }
+                }
+                div { class: "grid grid-cols-2 gap-4 md:grid-cols-4 text-sm",
+                    div {
+                        p { class: "text-gray-500", "AI calls (30 days)" }
+                        p { class: "font-bold", "{usage.analytics.ai_usage.total_requests}" }
+                    }
+                    div {
+                        p { class: "text-gray-500", "Avg. response time" }
+                        p { class: "font-bold", "{usage.analytics.ai_usage.avg_gen_time:.1}s" }
+                    }
+                    div {
+                        p { class: "text-gray-500", "Success rate" }
+                        p { class: "font-bold", "{usage.analytics.ai_usage.success_rate * 100.0:.0}%" }
+                    }
+                    div {
+                        p { class: "text-gray-500", "AI calls per OG" }
+                        p { class: "font-bold", "{usage.analytics.engagement.ai_requests_per_og:.1}" }
+                    }
+                }
+            } else {
+                p { class: "text-sm text-gray-500", "Loading usage..." }
+            }
+        }
+    }
+}
@@ -5,7 +5,6 @@ use dioxus::prelude::*;
 pub struct ProfileDetailsProps {
     pub user: User,
     pub dark_mode: bool,
-    pub user_token: String,
 }
 
 #[component]
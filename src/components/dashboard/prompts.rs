@@ -0,0 +1,281 @@
+use crate::components::toast::manager::{ToastManager, ToastType};
+use crate::server::ai::request::SuggestionField;
+use crate::server::auth::controller::about_me;
+use crate::server::auth::model::Permission;
+use crate::server::template::controller::{
+    delete_template, list_templates, restore_template_version, save_template,
+};
+use crate::server::template::model::{PromptTemplate, TEMPLATE_VARIABLES};
+use crate::server::template::request::{
+    DeleteTemplateRequest, RestoreTemplateRequest, SaveTemplateRequest,
+};
+use crate::theme::Theme;
+use bson::oid::ObjectId;
+use chrono::Duration;
+use dioxus::prelude::*;
+
+fn field_label(field: SuggestionField) -> &'static str {
+    match field {
+        SuggestionField::Title => "Title",
+        SuggestionField::Description => "Description",
+    }
+}
+
+#[component]
+pub fn PromptsPanel() -> Element {
+    let theme = use_context::<Signal<Theme>>();
+    let dark_mode = theme() == Theme::Dark;
+    let mut toasts_manager = use_context::<Signal<ToastManager>>();
+    let mut templates = use_signal(Vec::<PromptTemplate>::new);
+    let mut can_share = use_signal(|| false);
+    let mut selected = use_signal(|| None::<ObjectId>);
+    let mut name = use_signal(String::new);
+    let mut field = use_signal(|| SuggestionField::Title);
+    let mut body = use_signal(String::new);
+    let mut global = use_signal(|| false);
+
+    let mut notify = move |title: &str, message: String, toast_type: ToastType| {
+        toasts_manager.set(
+            toasts_manager()
+                .add_toast(
+                    title.into(),
+                    message,
+                    toast_type,
+                    Some(Duration::seconds(5)),
+                )
+                .clone(),
+        );
+    };
+
+    let _ = use_resource(move || async move {
+        if let Ok(response) = about_me().await {
+            can_share.set(response.data.user.role.can(Permission::ManageTemplates));
+        }
+        if let Ok(response) = list_templates().await {
+            templates.set(response.data);
+        }
+    });
+
+    let mut open = move |template: Option<PromptTemplate>| match template {
+        Some(template) => {
+            selected.set(Some(template.id));
+            name.set(template.name);
+            field.set(template.field);
+            body.set(template.body);
+            global.set(template.user.is_none());
+        }
+        None => {
+            selected.set(None);
+            name.set(String::new());
+            field.set(SuggestionField::Title);
+            body.set(String::new());
+            global.set(false);
+        }
+    };
+
+    // Swaps the saved template into the list and keeps it open.
+    let mut store = move |template: PromptTemplate| {
+        let mut current = templates();
+        current.retain(|t| t.id != template.id);
+        current.push(template.clone());
+        current.sort_by(|a, b| (a.is_global(), &a.name).cmp(&(b.is_global(), &b.name)));
+        templates.set(current);
+        open(Some(template));
+    };
+
+    let handle_save = move |_| {
+        let request = SaveTemplateRequest {
+            template_id: selected(),
+            name: name(),
+            field: field(),
+            body: body(),
+            global: global(),
+        };
+        spawn(async move {
+            match save_template(request).await {
+                Ok(response) => {
+                    notify(
+                        "Saved",
+                        format!("Saved version {}", response.data.version),
+                        ToastType::Success,
+                    );
+                    store(response.data);
+                }
+                Err(err) => notify("Error", err.to_string(), ToastType::Error),
+            }
+        });
+    };
+
+    let handle_delete = move |_| {
+        let Some(template_id) = selected() else {
+            return;
+        };
+        spawn(async move {
+            match delete_template(DeleteTemplateRequest { template_id }).await {
+                Ok(_) => {
+                    templates.set(
+                        templates()
+                            .into_iter()
+                            .filter(|t| t.id != template_id)
+                            .collect(),
+                    );
+                    open(None);
+                }
+                Err(err) => notify("Error", err.to_string(), ToastType::Error),
+            }
+        });
+    };
+
+    let current = templates()
+        .into_iter()
+        .find(|template| Some(template.id) == selected());
+    let editable = current
+        .as_ref()
+        .is_none_or(|template| !template.is_global() || can_share());
+    let variables = TEMPLATE_VARIABLES.map(|variable| format!("{{{variable}}}"));
+    let input_class = format!(
+        "mt-1 block w-full p-2 border rounded-md shadow-sm {}",
+        if dark_mode {
+            "bg-gray-900 border-gray-700"
+        } else {
+            "border-gray-300"
+        }
+    );
+
+    rsx! {
+        div { class: "grid grid-cols-1 lg:grid-cols-3 gap-6",
+            div {
+                h2 { class: "text-xl font-semibold mb-4", "Prompt Templates" }
+                button {
+                    class: "w-full bg-blue-500 text-white p-2 rounded-lg mb-4",
+                    onclick: move |_| open(None),
+                    "+ New Template"
+                }
+                ul {
+                    for template in templates() {
+                        li {
+                            class: format!("p-2 cursor-pointer rounded-lg {}",
+                                if Some(template.id) == selected() {
+                                    "bg-blue-200 text-black dark:bg-blue-600 font-bold"
+                                } else {
+                                    "hover:bg-blue-100 hover:text-black dark:hover:bg-blue-800"
+                                }
+                            ),
+                            onclick: {
+                                let template = template.clone();
+                                move |_| open(Some(template.clone()))
+                            },
+                            p { class: "truncate", "{template.name}" }
+                            p { class: "text-xs text-gray-500",
+                                "{field_label(template.field)} · v{template.version}"
+                                if template.is_global() { " · shared" }
+                            }
+                        }
+                    }
+                }
+            }
+
+            div { class: "lg:col-span-2 space-y-4",
+                div {
+                    label { class: "block text-sm font-medium", "Name" }
+                    input {
+                        class: "{input_class}",
+                        value: "{name}",
+                        disabled: !editable,
+                        oninput: move |e| name.set(e.value()),
+                    }
+                }
+                div {
+                    label { class: "block text-sm font-medium", "Used for" }
+                    select {
+                        class: "{input_class}",
+                        disabled: !editable,
+                        oninput: move |e| field.set(if e.value() == "description" { SuggestionField::Description } else { SuggestionField::Title }),
+                        option { value: "title", selected: field() == SuggestionField::Title, "Title suggestions" }
+                        option { value: "description", selected: field() == SuggestionField::Description, "Description suggestions" }
+                    }
+                }
+                if selected().is_none() && can_share() {
+                    label { class: "flex items-center space-x-2 text-sm",
+                        input {
+                            r#type: "checkbox",
+                            checked: global(),
+                            onchange: move |e| global.set(e.checked()),
+                        }
+                        span { "Share with all users" }
+                    }
+                }
+                div {
+                    label { class: "block text-sm font-medium", "System prompt" }
+                    textarea {
+                        class: "{input_class} font-mono text-sm",
+                        rows: "14",
+                        value: "{body}",
+                        disabled: !editable,
+                        oninput: move |e| body.set(e.value()),
+                    }
+                    p { class: "text-xs text-gray-500 mt-1",
+                        "Variables: "
+                        for variable in variables {
+                            code { class: "mr-2", "{variable}" }
+                        }
+                    }
+                    p { class: "text-xs text-gray-500",
+                        "The expected output format is added automatically."
+                    }
+                }
+                if editable {
+                    div { class: "flex space-x-2",
+                        button {
+                            class: "py-2 px-4 rounded bg-blue-500 text-white",
+                            onclick: handle_save,
+                            if selected().is_some() { "Save new version" } else { "Create" }
+                        }
+                        if selected().is_some() {
+                            button {
+                                class: "py-2 px-4 rounded bg-red-600 text-white",
+                                onclick: handle_delete,
+                                "Delete"
+                            }
+                        }
+                    }
+                } else {
+                    p { class: "text-sm text-gray-500", "Shared templates can only be edited by the content team." }
+                }
+
+                if let Some(template) = current {
+                    h3 { class: "text-lg font-semibold mt-6", "History" }
+                    ul { class: "space-y-2",
+                        for version in template.versions.iter().rev().cloned() {
+                            li { class: "p-2 border rounded-md border-gray-500",
+                                div { class: "flex items-center justify-between text-sm",
+                                    span { class: "font-bold", "v{version.version}" }
+                                    span { class: "text-gray-500", "{version.created_at.format(\"%B %d, %Y\")}" }
+                                    if version.version == template.version {
+                                        span { class: "text-green-500", "current" }
+                                    } else if editable {
+                                        button {
+                                            class: "text-blue-500",
+                                            onclick: move |_| {
+                                                let template_id = template.id;
+                                                let version = version.version;
+                                                spawn(async move {
+                                                    match restore_template_version(RestoreTemplateRequest { template_id, version }).await {
+                                                        Ok(response) => store(response.data),
+                                                        Err(err) => notify("Error", err.to_string(), ToastType::Error),
+                                                    }
+                                                });
+                                            },
+                                            "Restore"
+                                        }
+                                    }
+                                }
+                                pre { class: "text-xs whitespace-pre-wrap text-gray-500 mt-1", "{version.body}" }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -9,6 +9,8 @@ pub enum Tab {
     Chat,
     CreateOG,
     ViewOG,
+    Prompts,
+    Voices,
     EditProfile,
 }
 
@@ -98,6 +100,34 @@ pub fn Sidebar(active_tab: Signal<Tab>, navigate: bool) -> Element {
                 },
                 span { class: "hidden md:inline", "View OG" }
             }
+            div { class: tab_style(Tab::Prompts),
+                onclick: move |_| {
+                    if navigate {
+                        navigator.push("/dashboard");
+                    }
+                    active_tab.set(Tab::Prompts);
+                },
+                i {
+                    width: 30,
+                    height: 30,
+                    class: "fa-solid fa-wand-magic-sparkles",
+                },
+                span { class: "hidden md:inline", "Prompts" }
+            }
+            div { class: tab_style(Tab::Voices),
+                onclick: move |_| {
+                    if navigate {
+                        navigator.push("/dashboard");
+                    }
+                    active_tab.set(Tab::Voices);
+                },
+                i {
+                    width: 30,
+                    height: 30,
+                    class: "fa-solid fa-bullhorn",
+                },
+                span { class: "hidden md:inline", "Brand Voice" }
+            }
             div { class: tab_style(Tab::EditProfile),
                 onclick: move |_| {
                     if navigate {
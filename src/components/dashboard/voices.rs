@@ -0,0 +1,257 @@
+use crate::components::toast::manager::{ToastManager, ToastType};
+use crate::server::voice::controller::{delete_voice, list_voices, save_voice};
+use crate::server::voice::model::{split_terms, BrandVoice};
+use crate::server::voice::request::{DeleteVoiceRequest, SaveVoiceRequest};
+use crate::theme::Theme;
+use bson::oid::ObjectId;
+use chrono::Duration;
+use dioxus::prelude::*;
+
+#[component]
+pub fn VoicesPanel() -> Element {
+    let theme = use_context::<Signal<Theme>>();
+    let dark_mode = theme() == Theme::Dark;
+    let mut toasts_manager = use_context::<Signal<ToastManager>>();
+    let mut voices = use_signal(Vec::<BrandVoice>::new);
+    let mut selected = use_signal(|| None::<ObjectId>);
+    let mut name = use_signal(String::new);
+    let mut tone = use_signal(String::new);
+    let mut banned_words = use_signal(String::new);
+    let mut preferred_terms = use_signal(String::new);
+    let mut example_copy = use_signal(String::new);
+    let mut max_title_chars = use_signal(String::new);
+    let mut max_description_chars = use_signal(String::new);
+
+    let mut notify = move |title: &str, message: String, toast_type: ToastType| {
+        toasts_manager.set(
+            toasts_manager()
+                .add_toast(
+                    title.into(),
+                    message,
+                    toast_type,
+                    Some(Duration::seconds(5)),
+                )
+                .clone(),
+        );
+    };
+
+    let _ = use_resource(move || async move {
+        if let Ok(response) = list_voices().await {
+            voices.set(response.data);
+        }
+    });
+
+    let mut open = move |voice: Option<BrandVoice>| {
+        let voice = voice.as_ref();
+        let limit = |max: u32| {
+            if max == 0 {
+                String::new()
+            } else {
+                max.to_string()
+            }
+        };
+        selected.set(voice.map(|voice| voice.id));
+        name.set(voice.map(|voice| voice.name.clone()).unwrap_or_default());
+        tone.set(voice.map(|voice| voice.tone.clone()).unwrap_or_default());
+        banned_words.set(
+            voice
+                .map(|voice| voice.banned_words.join(", "))
+                .unwrap_or_default(),
+        );
+        preferred_terms.set(
+            voice
+                .map(|voice| voice.preferred_terms.join(", "))
+                .unwrap_or_default(),
+        );
+        example_copy.set(
+            voice
+                .map(|voice| voice.example_copy.clone())
+                .unwrap_or_default(),
+        );
+        max_title_chars.set(
+            voice
+                .map(|voice| limit(voice.max_title_chars))
+                .unwrap_or_default(),
+        );
+        max_description_chars.set(
+            voice
+                .map(|voice| limit(voice.max_description_chars))
+                .unwrap_or_default(),
+        );
+    };
+
+    let handle_save = move |_| {
+        let request = SaveVoiceRequest {
+            voice_id: selected(),
+            name: name(),
+            tone: tone(),
+            banned_words: split_terms(&banned_words()),
+            preferred_terms: split_terms(&preferred_terms()),
+            example_copy: example_copy(),
+            max_title_chars: max_title_chars().trim().parse().unwrap_or(0),
+            max_description_chars: max_description_chars().trim().parse().unwrap_or(0),
+        };
+        spawn(async move {
+            match save_voice(request).await {
+                Ok(response) => {
+                    let voice = response.data;
+                    let mut current = voices();
+                    current.retain(|v| v.id != voice.id);
+                    current.push(voice.clone());
+                    current.sort_by(|a, b| a.name.cmp(&b.name));
+                    voices.set(current);
+                    open(Some(voice));
+                    notify("Saved", "Brand voice saved".into(), ToastType::Success);
+                }
+                Err(err) => notify("Error", err.to_string(), ToastType::Error),
+            }
+        });
+    };
+
+    let handle_delete = move |_| {
+        let Some(voice_id) = selected() else {
+            return;
+        };
+        spawn(async move {
+            match delete_voice(DeleteVoiceRequest { voice_id }).await {
+                Ok(_) => {
+                    voices.set(voices().into_iter().filter(|v| v.id != voice_id).collect());
+                    open(None);
+                }
+                Err(err) => notify("Error", err.to_string(), ToastType::Error),
+            }
+        });
+    };
+
+    let input_class = format!(
+        "mt-1 block w-full p-2 border rounded-md shadow-sm {}",
+        if dark_mode {
+            "bg-gray-900 border-gray-700"
+        } else {
+            "border-gray-300"
+        }
+    );
+
+    rsx! {
+        div { class: "grid grid-cols-1 lg:grid-cols-3 gap-6",
+            div {
+                h2 { class: "text-xl font-semibold mb-4", "Brand Voices" }
+                button {
+                    class: "w-full bg-blue-500 text-white p-2 rounded-lg mb-4",
+                    onclick: move |_| open(None),
+                    "+ New Voice"
+                }
+                ul {
+                    for voice in voices() {
+                        li {
+                            class: format!("p-2 cursor-pointer rounded-lg {}",
+                                if Some(voice.id) == selected() {
+                                    "bg-blue-200 text-black dark:bg-blue-600 font-bold"
+                                } else {
+                                    "hover:bg-blue-100 hover:text-black dark:hover:bg-blue-800"
+                                }
+                            ),
+                            onclick: {
+                                let voice = voice.clone();
+                                move |_| open(Some(voice.clone()))
+                            },
+                            p { class: "truncate", "{voice.name}" }
+                            p { class: "text-xs text-gray-500 truncate", "{voice.tone}" }
+                        }
+                    }
+                }
+            }
+
+            div { class: "lg:col-span-2 space-y-4",
+                p { class: "text-sm text-gray-500",
+                    "Pick a voice next to the 💡 buttons or in chat. Tone, preferred terms and example copy guide the AI; "
+                    "banned words and maximum lengths are checked afterwards, and copy breaking them is regenerated or left out."
+                }
+                div {
+                    label { class: "block text-sm font-medium", "Name" }
+                    input {
+                        class: "{input_class}",
+                        value: "{name}",
+                        oninput: move |e| name.set(e.value()),
+                    }
+                }
+                div {
+                    label { class: "block text-sm font-medium", "Tone" }
+                    input {
+                        class: "{input_class}",
+                        placeholder: "Warm, witty, never pushy",
+                        value: "{tone}",
+                        oninput: move |e| tone.set(e.value()),
+                    }
+                }
+                div { class: "grid grid-cols-1 md:grid-cols-2 gap-4",
+                    div {
+                        label { class: "block text-sm font-medium", "Banned words (comma separated)" }
+                        textarea {
+                            class: "{input_class}",
+                            rows: "3",
+                            value: "{banned_words}",
+                            oninput: move |e| banned_words.set(e.value()),
+                        }
+                    }
+                    div {
+                        label { class: "block text-sm font-medium", "Preferred terms (comma separated)" }
+                        textarea {
+                            class: "{input_class}",
+                            rows: "3",
+                            value: "{preferred_terms}",
+                            oninput: move |e| preferred_terms.set(e.value()),
+                        }
+                    }
+                }
+                div {
+                    label { class: "block text-sm font-medium", "Example copy" }
+                    textarea {
+                        class: "{input_class}",
+                        rows: "5",
+                        value: "{example_copy}",
+                        oninput: move |e| example_copy.set(e.value()),
+                    }
+                }
+                div { class: "grid grid-cols-1 md:grid-cols-2 gap-4",
+                    div {
+                        label { class: "block text-sm font-medium", "Max title length" }
+                        input {
+                            class: "{input_class}",
+                            r#type: "number",
+                            min: "0",
+                            placeholder: "No limit",
+                            value: "{max_title_chars}",
+                            oninput: move |e| max_title_chars.set(e.value()),
+                        }
+                    }
+                    div {
+                        label { class: "block text-sm font-medium", "Max description length" }
+                        input {
+                            class: "{input_class}",
+                            r#type: "number",
+                            min: "0",
+                            placeholder: "No limit",
+                            value: "{max_description_chars}",
+                            oninput: move |e| max_description_chars.set(e.value()),
+                        }
+                    }
+                }
+                div { class: "flex space-x-2",
+                    button {
+                        class: "py-2 px-4 rounded bg-blue-500 text-white",
+                        onclick: handle_save,
+                        if selected().is_some() { "Save" } else { "Create" }
+                    }
+                    if selected().is_some() {
+                        button {
+                            class: "py-2 px-4 rounded bg-red-600 text-white",
+                            onclick: handle_delete,
+                            "Delete"
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,192 @@
+use axum::extract::Request;
+use axum::http::header::{COOKIE, HOST, ORIGIN, REFERER};
+use axum::http::{HeaderMap, Method, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use dioxus_logger::tracing;
+use std::env;
+
+use crate::server::auth::guard::SESSION_COOKIE;
+
+/// Rejects state-changing API requests whose `Origin` (or `Referer`) does not
+/// match the host serving the app or one of `ALLOWED_ORIGINS`. Requests that
+/// carry a session cookie must send one of the two headers.
+pub async fn verify_origin(req: Request, next: Next) -> Response {
+    if !req.uri().path().starts_with("/api/")
+        || matches!(*req.method(), Method::GET | Method::HEAD | Method::OPTIONS)
+    {
+        return next.run(req).await;
+    }
+
+    if !origin_allowed(req.headers()) {
+        tracing::warn!("rejected cross-origin request to {}", req.uri().path());
+        return (StatusCode::FORBIDDEN, "Cross-origin request rejected").into_response();
+    }
+
+    next.run(req).await
+}
+
+/// Whether a request with `headers` may change state. A `null` origin counts
+/// as no origin at all.
+fn origin_allowed(headers: &HeaderMap) -> bool {
+    let source = headers
+        .get(ORIGIN)
+        .or_else(|| headers.get(REFERER))
+        .and_then(|value| value.to_str().ok())
+        .filter(|value| *value != "null");
+
+    match source {
+        Some(source) => is_allowed(source, headers),
+        None => !has_session(headers),
+    }
+}
+
+fn is_allowed(source: &str, headers: &HeaderMap) -> bool {
+    let Some(origin) = origin_of(source) else {
+        return false;
+    };
+
+    let same_host = headers
+        .get(HOST)
+        .and_then(|host| host.to_str().ok())
+        .is_some_and(|host| origin.split_once("://").map(|(_, h)| h) == Some(host));
+
+    same_host
+        || env::var("ALLOWED_ORIGINS")
+            .unwrap_or_default()
+            .split(',')
+            .map(|allowed| allowed.trim().trim_end_matches('/'))
+            .any(|allowed| allowed.eq_ignore_ascii_case(origin))
+}
+
+/// Scheme and authority of an `Origin` or `Referer` value.
+fn origin_of(source: &str) -> Option<&str> {
+    let (scheme, rest) = source.split_once("://")?;
+    if scheme.is_empty() || rest.is_empty() {
+        return None;
+    }
+    let end = rest.find(['/', '?', '#']).unwrap_or(rest.len());
+    Some(&source[..scheme.len() + 3 + end])
+}
+
+fn has_session(headers: &HeaderMap) -> bool {
+    headers
+        .get_all(COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(';'))
+        .any(|pair| {
+            pair.trim()
+                .split_once('=')
+                .is_some_and(|(name, _)| name == SESSION_COOKIE)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{has_session, is_allowed, origin_allowed, origin_of};
+    use crate::server::auth::guard::SESSION_COOKIE;
+    use axum::http::header::{COOKIE, HOST, ORIGIN, REFERER};
+    use axum::http::HeaderMap;
+
+    fn headers(pairs: &[(axum::http::HeaderName, &str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.append(name.clone(), value.parse().unwrap());
+        }
+        headers
+    }
+
+    fn session() -> String {
+        format!("theme=dark; {SESSION_COOKIE}=token")
+    }
+
+    #[test]
+    fn takes_scheme_and_authority() {
+        assert_eq!(
+            origin_of("https://app.test:8080/dashboard?x=1"),
+            Some("https://app.test:8080")
+        );
+        assert_eq!(origin_of("https://app.test#top"), Some("https://app.test"));
+        assert_eq!(origin_of("https://app.test"), Some("https://app.test"));
+        assert_eq!(origin_of("null"), None);
+        assert_eq!(origin_of("://app.test"), None);
+        assert_eq!(origin_of("https://"), None);
+    }
+
+    #[test]
+    fn allows_the_serving_host_only() {
+        let host = headers(&[(HOST, "app.test")]);
+        assert!(is_allowed("https://app.test/ogs", &host));
+        assert!(!is_allowed("https://evil.test", &host));
+        assert!(!is_allowed("https://app.test.evil.test", &host));
+        assert!(!is_allowed("app.test", &host));
+        assert!(!is_allowed("https://app.test", &HeaderMap::new()));
+    }
+
+    #[test]
+    fn finds_the_session_cookie() {
+        assert!(has_session(&headers(&[(COOKIE, &session())])));
+        assert!(has_session(&headers(&[
+            (COOKIE, "theme=dark"),
+            (COOKIE, &format!("{SESSION_COOKIE}=token")),
+        ])));
+        assert!(!has_session(&headers(&[(COOKIE, "theme=dark")])));
+        assert!(!has_session(&headers(&[(
+            COOKIE,
+            &format!("x{SESSION_COOKIE}=token")
+        )])));
+        assert!(!has_session(&HeaderMap::new()));
+    }
+
+    #[test]
+    fn requires_an_origin_with_a_session() {
+        let cookie = session();
+        // Missing or `null` origins are fine without a cookie to ride on.
+        assert!(origin_allowed(&headers(&[(HOST, "app.test")])));
+        assert!(origin_allowed(&headers(&[
+            (HOST, "app.test"),
+            (ORIGIN, "null")
+        ])));
+        assert!(!origin_allowed(&headers(&[
+            (HOST, "app.test"),
+            (COOKIE, &cookie)
+        ])));
+        assert!(!origin_allowed(&headers(&[
+            (HOST, "app.test"),
+            (ORIGIN, "null"),
+            (COOKIE, &cookie),
+        ])));
+    }
+
+    #[test]
+    fn rejects_mismatched_origins() {
+        let cookie = session();
+        assert!(origin_allowed(&headers(&[
+            (HOST, "app.test"),
+            (ORIGIN, "https://app.test"),
+            (COOKIE, &cookie),
+        ])));
+        assert!(!origin_allowed(&headers(&[
+            (HOST, "app.test"),
+            (ORIGIN, "https://evil.test"),
+            (COOKIE, &cookie),
+        ])));
+        // Without a cookie a foreign origin is still refused.
+        assert!(!origin_allowed(&headers(&[
+            (HOST, "app.test"),
+            (ORIGIN, "https://evil.test"),
+        ])));
+        // The referer stands in for a missing origin.
+        assert!(origin_allowed(&headers(&[
+            (HOST, "app.test"),
+            (REFERER, "https://app.test/dashboard"),
+            (COOKIE, &cookie),
+        ])));
+        assert!(!origin_allowed(&headers(&[
+            (HOST, "app.test"),
+            (REFERER, "https://evil.test/page"),
+            (COOKIE, &cookie),
+        ])));
+    }
+}
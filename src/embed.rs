@@ -0,0 +1,177 @@
+pub(crate) mod local;
+pub(crate) mod ollama;
+pub(crate) mod openai;
+
+use std::env;
+use std::future::Future;
+use std::time::Duration;
+use tokio::sync::OnceCell;
+
+use crate::embed::local::LocalEmbedder;
+use crate::embed::ollama::OllamaEmbedder;
+use crate::embed::openai::OpenAIEmbedder;
+use crate::llm::LlmError;
+
+static EMBEDDER: OnceCell<Embedder> = OnceCell::const_new();
+
+/// A backend turning texts into vectors whose cosine similarity reflects how
+/// close their meanings are.
+pub trait EmbeddingProvider {
+    fn embed(
+        &self,
+        model: &str,
+        inputs: &[String],
+    ) -> impl Future<Output = Result<Vec<Vec<f32>>, LlmError>> + Send;
+}
+
+#[derive(Debug, Clone)]
+pub struct EmbedConfig {
+    pub provider: String,
+    pub base_url: String,
+    pub api_key: Option<String>,
+    pub model: String,
+    pub timeout: Duration,
+    /// Similarity from which a new OG is reported as a near duplicate.
+    pub duplicate_threshold: f32,
+    /// Name of an Atlas Vector Search index over `og_embeddings`. Without one,
+    /// the user's stored vectors are compared in process.
+    pub vector_index: Option<String>,
+}
+
+impl EmbedConfig {
+    pub fn from_env() -> Self {
+        let provider = env::var("EMBED_PROVIDER").unwrap_or_else(|_| "local".into());
+        let (default_url, default_model) = match provider.as_str() {
+            "openai" => ("https://api.openai.com/v1", "text-embedding-3-small"),
+            "ollama" => ("http://localhost:11434", "nomic-embed-text"),
+            _ => ("", local::MODEL),
+        };
+
+        Self {
+            base_url: env::var("EMBED_BASE_URL")
+                .unwrap_or_else(|_| default_url.into())
+                .trim_end_matches('/')
+                .to_string(),
+            api_key: env::var("EMBED_API_KEY")
+                .or_else(|_| env::var("LLM_API_KEY"))
+                .ok()
+                .filter(|key| !key.is_empty()),
+            model: env::var("EMBED_MODEL").unwrap_or_else(|_| default_model.into()),
+            timeout: Duration::from_secs(
+                env::var("EMBED_TIMEOUT_SECS")
+                    .ok()
+                    .and_then(|secs| secs.parse().ok())
+                    .unwrap_or(30),
+            ),
+            duplicate_threshold: env::var("EMBED_DUPLICATE_THRESHOLD")
+                .ok()
+                .and_then(|threshold| threshold.parse().ok())
+                .unwrap_or(0.9),
+            vector_index: env::var("EMBED_VECTOR_INDEX")
+                .ok()
+                .filter(|index| !index.is_empty()),
+            provider,
+        }
+    }
+}
+
+pub enum Provider {
+    OpenAI(OpenAIEmbedder),
+    Ollama(OllamaEmbedder),
+    Local(LocalEmbedder),
+}
+
+impl EmbeddingProvider for Provider {
+    async fn embed(&self, model: &str, inputs: &[String]) -> Result<Vec<Vec<f32>>, LlmError> {
+        match self {
+            Provider::OpenAI(provider) => provider.embed(model, inputs).await,
+            Provider::Ollama(provider) => provider.embed(model, inputs).await,
+            Provider::Local(provider) => provider.embed(model, inputs).await,
+        }
+    }
+}
+
+pub struct Embedder {
+    pub config: EmbedConfig,
+    pub provider: Provider,
+}
+
+impl Embedder {
+    pub fn new(config: EmbedConfig) -> Self {
+        let provider = match config.provider.as_str() {
+            "openai" => Provider::OpenAI(OpenAIEmbedder::new(&config)),
+            "ollama" => Provider::Ollama(OllamaEmbedder::new(&config)),
+            _ => Provider::Local(LocalEmbedder),
+        };
+
+        Self { config, provider }
+    }
+
+    /// Whether embedding calls leave the server and count against AI quotas.
+    pub fn is_remote(&self) -> bool {
+        !matches!(self.provider, Provider::Local(_))
+    }
+
+    /// The model vectors are tagged with, so a model change reindexes them.
+    pub fn model(&self) -> &str {
+        match self.provider {
+            Provider::Local(_) => local::MODEL,
+            _ => &self.config.model,
+        }
+    }
+
+    pub async fn embed(&self, inputs: &[String]) -> Result<Vec<Vec<f32>>, LlmError> {
+        if inputs.is_empty() {
+            return Ok(Vec::new());
+        }
+        let vectors = self.provider.embed(self.model(), inputs).await?;
+        if vectors.len() != inputs.len() {
+            return Err(LlmError::InvalidResponse(format!(
+                "expected {} embeddings, got {}",
+                inputs.len(),
+                vectors.len()
+            )));
+        }
+        Ok(vectors)
+    }
+}
+
+/// Cosine similarity of two vectors; 0 when their sizes differ.
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() || a.is_empty() {
+        return 0.0;
+    }
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm = |v: &[f32]| v.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norms = norm(a) * norm(b);
+    if norms == 0.0 {
+        0.0
+    } else {
+        dot / norms
+    }
+}
+
+pub async fn get_embedder() -> &'static Embedder {
+    EMBEDDER
+        .get_or_init(|| async { Embedder::new(EmbedConfig::from_env()) })
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::cosine_similarity;
+
+    #[test]
+    fn cosine_of_aligned_opposite_and_orthogonal_vectors() {
+        assert!((cosine_similarity(&[1.0, 2.0], &[2.0, 4.0]) - 1.0).abs() < 1e-6);
+        assert!((cosine_similarity(&[1.0, 2.0], &[-1.0, -2.0]) + 1.0).abs() < 1e-6);
+        assert_eq!(cosine_similarity(&[1.0, 0.0], &[0.0, 3.0]), 0.0);
+    }
+
+    #[test]
+    fn cosine_of_mismatched_or_empty_vectors_is_zero() {
+        assert_eq!(cosine_similarity(&[1.0], &[1.0, 0.0]), 0.0);
+        assert_eq!(cosine_similarity(&[], &[]), 0.0);
+        assert_eq!(cosine_similarity(&[0.0, 0.0], &[1.0, 1.0]), 0.0);
+    }
+}
@@ -0,0 +1,88 @@
+use crate::embed::EmbeddingProvider;
+use crate::llm::LlmError;
+
+/// Name stored with vectors from this embedder.
+pub const MODEL: &str = "local-hash-v1";
+const DIMENSIONS: usize = 384;
+const WORD_WEIGHT: f32 = 1.0;
+const TRIGRAM_WEIGHT: f32 = 0.4;
+const STOP_WORDS: [&str; 24] = [
+    "a", "an", "and", "are", "as", "at", "be", "by", "for", "from", "in", "is", "it", "of", "on",
+    "or", "that", "the", "this", "to", "was", "with", "you", "your",
+];
+
+/// Offline fallback hashing words and character trigrams into a fixed number
+/// of buckets. It knows nothing of synonyms but needs no model, and the same
+/// text always yields the same vector.
+pub struct LocalEmbedder;
+
+impl EmbeddingProvider for LocalEmbedder {
+    async fn embed(&self, _model: &str, inputs: &[String]) -> Result<Vec<Vec<f32>>, LlmError> {
+        Ok(inputs.iter().map(|input| embed(input)).collect())
+    }
+}
+
+fn embed(text: &str) -> Vec<f32> {
+    let mut vector = vec![0.0; DIMENSIONS];
+    let mut add = |feature: &str, weight: f32| {
+        let hash = fnv1a(feature.as_bytes());
+        let sign = if hash >> 63 == 0 { 1.0 } else { -1.0 };
+        vector[(hash % DIMENSIONS as u64) as usize] += sign * weight;
+    };
+
+    let text = text.to_lowercase();
+    for word in text
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty() && !STOP_WORDS.contains(word))
+    {
+        add(word, WORD_WEIGHT);
+        let chars: Vec<char> = format!(" {word} ").chars().collect();
+        for trigram in chars.windows(3) {
+            add(&trigram.iter().collect::<String>(), TRIGRAM_WEIGHT);
+        }
+    }
+
+    let norm = vector.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        vector.iter_mut().for_each(|x| *x /= norm);
+    }
+    vector
+}
+
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{embed, DIMENSIONS};
+    use crate::embed::cosine_similarity;
+
+    #[test]
+    fn same_text_same_vector() {
+        let vector = embed("Fresh roasted coffee beans");
+        assert_eq!(vector.len(), DIMENSIONS);
+        assert_eq!(vector, embed("Fresh roasted coffee beans"));
+        assert_eq!(vector, embed("fresh ROASTED coffee, beans!"));
+    }
+
+    #[test]
+    fn vectors_are_unit_length() {
+        let norm = embed("Small batch coffee")
+            .iter()
+            .map(|x| x * x)
+            .sum::<f32>();
+        assert!((norm - 1.0).abs() < 1e-5);
+        assert!(embed("the and of").iter().all(|x| *x == 0.0));
+    }
+
+    #[test]
+    fn related_text_is_closer() {
+        let coffee = embed("Fresh roasted coffee beans");
+        let related = embed("Roasted coffee delivered fresh");
+        let unrelated = embed("Mountain bike repair manual");
+        assert!(cosine_similarity(&coffee, &related) > cosine_similarity(&coffee, &unrelated));
+    }
+}
@@ -0,0 +1,55 @@
+use serde::{Deserialize, Serialize};
+
+use crate::embed::{EmbedConfig, EmbeddingProvider};
+use crate::llm::LlmError;
+
+/// A local Ollama server using its native `/api/embed` endpoint.
+pub struct OllamaEmbedder {
+    http: reqwest::Client,
+    base_url: String,
+}
+
+#[derive(Serialize)]
+struct EmbedRequest<'a> {
+    model: &'a str,
+    input: &'a [String],
+}
+
+#[derive(Deserialize)]
+struct EmbedResponse {
+    embeddings: Vec<Vec<f32>>,
+}
+
+impl OllamaEmbedder {
+    pub fn new(config: &EmbedConfig) -> Self {
+        Self {
+            http: reqwest::Client::builder()
+                .connect_timeout(config.timeout)
+                .read_timeout(config.timeout)
+                .build()
+                .expect("failed to build embedding HTTP client"),
+            base_url: config.base_url.clone(),
+        }
+    }
+}
+
+impl EmbeddingProvider for OllamaEmbedder {
+    async fn embed(&self, model: &str, inputs: &[String]) -> Result<Vec<Vec<f32>>, LlmError> {
+        let response = self
+            .http
+            .post(format!("{}/api/embed", self.base_url))
+            .json(&EmbedRequest {
+                model,
+                input: inputs,
+            })
+            .send()
+            .await?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(LlmError::Status(status.as_u16(), body));
+        }
+        let body: EmbedResponse = response.json().await?;
+        Ok(body.embeddings)
+    }
+}
@@ -0,0 +1,67 @@
+use serde::{Deserialize, Serialize};
+
+use crate::embed::{EmbedConfig, EmbeddingProvider};
+use crate::llm::LlmError;
+
+/// The OpenAI embeddings API, or any server implementing `/embeddings`.
+pub struct OpenAIEmbedder {
+    http: reqwest::Client,
+    base_url: String,
+    api_key: Option<String>,
+}
+
+#[derive(Serialize)]
+struct EmbeddingRequest<'a> {
+    model: &'a str,
+    input: &'a [String],
+}
+
+#[derive(Deserialize)]
+struct EmbeddingResponse {
+    data: Vec<EmbeddingData>,
+}
+
+#[derive(Deserialize)]
+struct EmbeddingData {
+    index: usize,
+    embedding: Vec<f32>,
+}
+
+impl OpenAIEmbedder {
+    pub fn new(config: &EmbedConfig) -> Self {
+        Self {
+            http: reqwest::Client::builder()
+                .connect_timeout(config.timeout)
+                .read_timeout(config.timeout)
+                .build()
+                .expect("failed to build embedding HTTP client"),
+            base_url: config.base_url.clone(),
+            api_key: config.api_key.clone(),
+        }
+    }
+}
+
+impl EmbeddingProvider for OpenAIEmbedder {
+    async fn embed(&self, model: &str, inputs: &[String]) -> Result<Vec<Vec<f32>>, LlmError> {
+        let mut builder = self
+            .http
+            .post(format!("{}/embeddings", self.base_url))
+            .json(&EmbeddingRequest {
+                model,
+                input: inputs,
+            });
+        if let Some(key) = &self.api_key {
+            builder = builder.bearer_auth(key);
+        }
+
+        let response = builder.send().await?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(LlmError::Status(status.as_u16(), body));
+        }
+        let mut body: EmbeddingResponse = response.json().await?;
+        body.data.sort_by_key(|data| data.index);
+        Ok(body.data.into_iter().map(|data| data.embedding).collect())
+    }
+}
@@ -0,0 +1,132 @@
+pub(crate) mod openai;
+pub(crate) mod procedural;
+
+use std::env;
+use std::future::Future;
+use std::time::Duration;
+use tokio::sync::OnceCell;
+
+use crate::imagegen::openai::OpenAIImageProvider;
+use crate::imagegen::procedural::ProceduralProvider;
+use crate::llm::LlmError;
+
+static IMAGE_GEN: OnceCell<ImageGen> = OnceCell::const_new();
+
+/// Width and height of generated card backgrounds, roughly 16:9.
+pub const BACKGROUND_SIZE: (u32, u32) = (1792, 1024);
+
+#[derive(Debug, Clone)]
+pub struct ImageRequest {
+    pub model: String,
+    pub prompt: String,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// An encoded image ready to be stored.
+#[derive(Debug, Clone)]
+pub struct GeneratedImage {
+    pub bytes: Vec<u8>,
+    /// File extension matching the encoding, e.g. `png` or `svg`.
+    pub extension: &'static str,
+}
+
+/// A backend turning a text prompt into an image. Failures reuse
+/// [`LlmError`], as image providers fail the same ways chat providers do.
+pub trait ImageProvider {
+    fn generate(
+        &self,
+        request: &ImageRequest,
+    ) -> impl Future<Output = Result<GeneratedImage, LlmError>> + Send;
+}
+
+#[derive(Debug, Clone)]
+pub struct ImageGenConfig {
+    pub provider: String,
+    pub base_url: String,
+    pub api_key: Option<String>,
+    pub model: String,
+    pub timeout: Duration,
+}
+
+impl ImageGenConfig {
+    pub fn from_env() -> Self {
+        Self {
+            provider: env::var("IMAGE_PROVIDER").unwrap_or_else(|_| "procedural".into()),
+            base_url: env::var("IMAGE_BASE_URL")
+                .unwrap_or_else(|_| "https://api.openai.com/v1".into())
+                .trim_end_matches('/')
+                .to_string(),
+            api_key: env::var("IMAGE_API_KEY")
+                .or_else(|_| env::var("LLM_API_KEY"))
+                .ok()
+                .filter(|key| !key.is_empty()),
+            model: env::var("IMAGE_MODEL").unwrap_or_else(|_| "dall-e-3".into()),
+            timeout: Duration::from_secs(
+                env::var("IMAGE_TIMEOUT_SECS")
+                    .ok()
+                    .and_then(|secs| secs.parse().ok())
+                    .unwrap_or(120),
+            ),
+        }
+    }
+}
+
+pub enum Provider {
+    OpenAI(OpenAIImageProvider),
+    Procedural(ProceduralProvider),
+}
+
+impl ImageProvider for Provider {
+    async fn generate(&self, request: &ImageRequest) -> Result<GeneratedImage, LlmError> {
+        match self {
+            Provider::OpenAI(provider) => provider.generate(request).await,
+            Provider::Procedural(provider) => provider.generate(request).await,
+        }
+    }
+}
+
+pub struct ImageGen {
+    pub config: ImageGenConfig,
+    pub provider: Provider,
+}
+
+impl ImageGen {
+    pub fn new(config: ImageGenConfig) -> Self {
+        let provider = match config.provider.as_str() {
+            "openai" => Provider::OpenAI(OpenAIImageProvider::new(&config)),
+            _ => Provider::Procedural(ProceduralProvider),
+        };
+
+        Self { config, provider }
+    }
+
+    /// The model name recorded for usage metering.
+    pub fn model(&self) -> &str {
+        match self.provider {
+            Provider::OpenAI(_) => &self.config.model,
+            Provider::Procedural(_) => "procedural",
+        }
+    }
+
+    pub async fn generate(
+        &self,
+        prompt: &str,
+        (width, height): (u32, u32),
+    ) -> Result<GeneratedImage, LlmError> {
+        self.provider
+            .generate(&ImageRequest {
+                model: self.model().to_string(),
+                prompt: prompt.to_string(),
+                width,
+                height,
+            })
+            .await
+    }
+}
+
+pub async fn get_image_gen() -> &'static ImageGen {
+    IMAGE_GEN
+        .get_or_init(|| async { ImageGen::new(ImageGenConfig::from_env()) })
+        .await
+}
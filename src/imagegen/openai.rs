@@ -0,0 +1,84 @@
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use serde::{Deserialize, Serialize};
+
+use crate::imagegen::{GeneratedImage, ImageGenConfig, ImageProvider, ImageRequest};
+use crate::llm::LlmError;
+
+/// The OpenAI images API, or any server implementing `/images/generations`.
+pub struct OpenAIImageProvider {
+    http: reqwest::Client,
+    base_url: String,
+    api_key: Option<String>,
+}
+
+#[derive(Serialize)]
+struct GenerationRequest<'a> {
+    model: &'a str,
+    prompt: &'a str,
+    n: u32,
+    size: String,
+    response_format: &'a str,
+}
+
+#[derive(Deserialize)]
+struct GenerationResponse {
+    data: Vec<ImageData>,
+}
+
+#[derive(Deserialize)]
+struct ImageData {
+    b64_json: Option<String>,
+}
+
+impl OpenAIImageProvider {
+    pub fn new(config: &ImageGenConfig) -> Self {
+        Self {
+            http: reqwest::Client::builder()
+                .connect_timeout(config.timeout)
+                .read_timeout(config.timeout)
+                .build()
+                .expect("failed to build image HTTP client"),
+            base_url: config.base_url.clone(),
+            api_key: config.api_key.clone(),
+        }
+    }
+}
+
+impl ImageProvider for OpenAIImageProvider {
+    async fn generate(&self, request: &ImageRequest) -> Result<GeneratedImage, LlmError> {
+        let mut builder = self
+            .http
+            .post(format!("{}/images/generations", self.base_url))
+            .json(&GenerationRequest {
+                model: &request.model,
+                prompt: &request.prompt,
+                n: 1,
+                size: format!("{}x{}", request.width, request.height),
+                response_format: "b64_json",
+            });
+        if let Some(key) = &self.api_key {
+            builder = builder.bearer_auth(key);
+        }
+
+        let response = builder.send().await?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(LlmError::Status(status.as_u16(), body));
+        }
+        let body: GenerationResponse = response.json().await?;
+        let encoded = body
+            .data
+            .into_iter()
+            .find_map(|image| image.b64_json)
+            .ok_or(LlmError::InvalidResponse("no image returned".into()))?;
+        let bytes = STANDARD
+            .decode(encoded)
+            .map_err(|err| LlmError::InvalidResponse(err.to_string()))?;
+
+        Ok(GeneratedImage {
+            bytes,
+            extension: "png",
+        })
+    }
+}
@@ -0,0 +1,96 @@
+use crate::imagegen::{GeneratedImage, ImageProvider, ImageRequest};
+use crate::llm::LlmError;
+
+const SHAPES: u64 = 6;
+
+/// Offline provider for development and tests. Draws an SVG of soft shapes
+/// whose colors and positions only depend on the prompt, so the same prompt
+/// always yields the same picture.
+pub struct ProceduralProvider;
+
+impl ImageProvider for ProceduralProvider {
+    async fn generate(&self, request: &ImageRequest) -> Result<GeneratedImage, LlmError> {
+        Ok(GeneratedImage {
+            bytes: render(&request.prompt, request.width, request.height).into_bytes(),
+            extension: "svg",
+        })
+    }
+}
+
+fn render(prompt: &str, width: u32, height: u32) -> String {
+    let mut rng = Fnv(fnv1a(prompt.trim().to_lowercase().as_bytes()));
+    let hue = rng.next() % 360;
+    let accent = (hue + 120 + rng.next() % 120) % 360;
+
+    let mut svg = format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}">
+<defs><linearGradient id="bg" x1="0" y1="0" x2="1" y2="1">
+<stop offset="0" stop-color="hsl({hue},70%,80%)"/><stop offset="1" stop-color="hsl({accent},70%,70%)"/>
+</linearGradient><filter id="blur"><feGaussianBlur stdDeviation="40"/></filter></defs>
+<rect width="100%" height="100%" fill="url(#bg)"/>
+<g filter="url(#blur)" opacity="0.6">
+"#
+    );
+    for _ in 0..SHAPES {
+        let cx = rng.next() % width as u64;
+        let cy = rng.next() % height as u64;
+        let r = height as u64 / 8 + rng.next() % (height as u64 / 3);
+        let shade = (hue + rng.next() % 180) % 360;
+        svg.push_str(&format!(
+            "<circle cx=\"{cx}\" cy=\"{cy}\" r=\"{r}\" fill=\"hsl({shade},75%,65%)\"/>\n"
+        ));
+    }
+    svg.push_str("</g>\n</svg>\n");
+    svg
+}
+
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// A tiny deterministic generator seeded from the prompt hash.
+struct Fnv(u64);
+
+impl Fnv {
+    fn next(&mut self) -> u64 {
+        self.0 = fnv1a(&self.0.to_le_bytes());
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{render, ProceduralProvider};
+    use crate::imagegen::{ImageProvider, ImageRequest};
+
+    #[test]
+    fn same_prompt_draws_the_same_picture() {
+        let first = render("Sunset over the bay", 320, 180);
+        assert_eq!(first, render("Sunset over the bay", 320, 180));
+        assert_eq!(first, render("  sunset OVER the bay ", 320, 180));
+        assert_ne!(first, render("Sunrise over the bay", 320, 180));
+    }
+
+    #[test]
+    fn draws_at_the_requested_size() {
+        let svg = render("grid", 320, 180);
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.contains(r#"width="320" height="180" viewBox="0 0 320 180""#));
+        assert_eq!(svg.matches("<circle").count(), 6);
+    }
+
+    #[tokio::test]
+    async fn generates_svg_images() {
+        let request = ImageRequest {
+            model: "procedural".into(),
+            prompt: "grid".into(),
+            width: 320,
+            height: 180,
+        };
+        let image = ProceduralProvider.generate(&request).await.unwrap();
+        assert_eq!(image.extension, "svg");
+        assert_eq!(image.bytes, render("grid", 320, 180).into_bytes());
+    }
+}
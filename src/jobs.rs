@@ -0,0 +1,63 @@
+use dioxus_logger::tracing;
+use tokio::time::{interval, Duration};
+
+use crate::server::account::controller::purge_due_accounts;
+use crate::server::admin::controller::record_stats_snapshot;
+use crate::server::og::controller::backfill_scores;
+use crate::server::search::controller::backfill_embeddings;
+
+const ACCOUNT_PURGE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+const STATS_SNAPSHOT_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Runs the account deletion purge hourly for the lifetime of the server.
+pub fn spawn_account_purge() {
+    tokio::spawn(async {
+        let mut ticker = interval(ACCOUNT_PURGE_INTERVAL);
+        loop {
+            ticker.tick().await;
+            match purge_due_accounts().await {
+                Ok(0) => {}
+                Ok(count) => tracing::info!("purged {count} deleted accounts"),
+                Err(err) => tracing::error!("account purge failed: {err}"),
+            }
+        }
+    });
+}
+
+/// Refreshes today's stats snapshot hourly, so every day the server runs
+/// leaves an entry in the admin stats history.
+pub fn spawn_stats_snapshots() {
+    tokio::spawn(async {
+        let mut ticker = interval(STATS_SNAPSHOT_INTERVAL);
+        loop {
+            ticker.tick().await;
+            if let Err(err) = record_stats_snapshot().await {
+                tracing::error!("stats snapshot failed: {err}");
+            }
+        }
+    });
+}
+
+/// Embeds OGs saved before indexing on write, or under another embedding
+/// model, once at startup.
+pub fn spawn_embedding_backfill() {
+    tokio::spawn(async {
+        match backfill_embeddings().await {
+            Ok(0) => {}
+            Ok(count) => tracing::info!("embedded {count} OGs for search"),
+            Err(err) => tracing::error!("embedding backfill failed: {err}"),
+        }
+    });
+}
+
+/// Stores SEO scores for OGs saved before scores were stored, once at
+/// startup.
+pub fn spawn_score_backfill() {
+    tokio::spawn(async {
+        match backfill_scores().await {
+            Ok(0) => {}
+            Ok(count) => tracing::info!("stored {count} SEO scores"),
+            Err(err) => tracing::error!("score backfill failed: {err}"),
+        }
+    });
+}
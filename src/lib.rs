@@ -1,9 +1,26 @@
 pub mod components;
 #[cfg(feature = "server")]
+pub mod csrf;
+#[cfg(feature = "server")]
 pub(crate) mod db;
+#[cfg(feature = "server")]
+pub(crate) mod embed;
+#[cfg(feature = "server")]
+pub(crate) mod imagegen;
+#[cfg(feature = "server")]
+pub mod jobs;
+#[cfg(feature = "server")]
+pub mod limiter;
+#[cfg(feature = "server")]
+pub(crate) mod llm;
+pub(crate) mod markdown;
+#[cfg(feature = "server")]
+pub(crate) mod moderation;
 pub(crate) mod pages;
 #[cfg(feature = "server")]
 pub(crate) mod pinata;
 pub mod router;
 pub(crate) mod server;
+#[cfg(feature = "server")]
+pub mod stream;
 pub mod theme;
@@ -0,0 +1,366 @@
+pub(crate) mod memory;
+pub(crate) mod mongo;
+
+use axum::body::{to_bytes, Body};
+use axum::extract::{ConnectInfo, Request};
+use axum::http::header::{CONTENT_TYPE, RETRY_AFTER};
+use axum::http::StatusCode;
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use chrono::Duration;
+use dioxus_logger::tracing;
+use std::env;
+use std::net::SocketAddr;
+use tokio::sync::OnceCell;
+
+use crate::limiter::memory::MemoryStore;
+use crate::limiter::mongo::MongoStore;
+
+const AUTH_ENDPOINTS: [&str; 2] = ["login_user", "register_user"];
+/// Every endpoint that calls an AI provider, LLM, image, embedding or
+/// moderation alike. Names are matched exactly, so a new AI endpoint must be
+/// listed here to be throttled. `upload_og` is also the path `update_og` is
+/// served under.
+const AI_ENDPOINTS: [&str; 13] = [
+    "generate_suggestions",
+    "generate_alt_text",
+    "generate_background",
+    "localize_og",
+    "send_query",
+    "stream_query",
+    "auto_title_conversation",
+    "search_ogs",
+    "find_similar_ogs",
+    "store_og",
+    "upload_og",
+    "save_message_to_db",
+    "import_conversations",
+];
+const LOGIN_ENDPOINT: &str = "login_user";
+/// Longest lockout whatever `LOGIN_LOCKOUT_MAX_SECS` says, so lock expiries
+/// stay far from the end of the time range.
+const MAX_LOCKOUT_DAYS: i64 = 365;
+const API_KEY_HEADER: &str = "x-api-key";
+const MAX_AUTH_BODY: usize = 16 * 1024;
+
+static LIMITER: OnceCell<Limiter> = OnceCell::const_new();
+
+#[derive(Debug, Clone, Copy)]
+pub struct Limit {
+    pub max: u32,
+    pub window: Duration,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Lockout {
+    pub threshold: u32,
+    pub base: Duration,
+    pub max: Duration,
+}
+
+impl Lockout {
+    /// Lock duration after `failures` consecutive failed logins, doubling on
+    /// every failure past the threshold and capped at `max`, and at
+    /// [`MAX_LOCKOUT_DAYS`] whatever `max` is.
+    pub fn duration(&self, failures: u32) -> Option<Duration> {
+        if failures < self.threshold {
+            return None;
+        }
+        let exponent = (failures - self.threshold).min(16);
+        let cap = self.max.min(Duration::days(MAX_LOCKOUT_DAYS));
+        let lock = self.base.checked_mul(2_i32.pow(exponent)).unwrap_or(cap);
+        Some(lock.min(cap))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LimiterConfig {
+    pub auth: Limit,
+    pub ai: Limit,
+    pub lockout: Lockout,
+    pub trust_proxy: bool,
+}
+
+impl LimiterConfig {
+    pub fn from_env() -> Self {
+        Self {
+            auth: Limit {
+                max: env_or("RATE_LIMIT_AUTH_MAX", 10),
+                window: Duration::seconds(env_or("RATE_LIMIT_AUTH_WINDOW_SECS", 60)),
+            },
+            ai: Limit {
+                max: env_or("RATE_LIMIT_AI_MAX", 20),
+                window: Duration::seconds(env_or("RATE_LIMIT_AI_WINDOW_SECS", 60)),
+            },
+            lockout: Lockout {
+                threshold: env_or("LOGIN_LOCKOUT_THRESHOLD", 5),
+                base: Duration::seconds(env_or("LOGIN_LOCKOUT_BASE_SECS", 30)),
+                max: Duration::seconds(env_or("LOGIN_LOCKOUT_MAX_SECS", 60 * 60)),
+            },
+            trust_proxy: env_or("RATE_LIMIT_TRUST_PROXY", false),
+        }
+    }
+}
+
+fn env_or<T: std::str::FromStr>(key: &str, default: T) -> T {
+    env::var(key)
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(default)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Decision {
+    Allowed,
+    Limited(Duration),
+}
+
+pub enum Store {
+    Memory(MemoryStore),
+    Mongo(MongoStore),
+}
+
+impl Store {
+    pub async fn hit(&self, key: &str, limit: Limit) -> Decision {
+        match self {
+            Store::Memory(store) => store.hit(key, limit).await,
+            Store::Mongo(store) => store.hit(key, limit).await,
+        }
+    }
+
+    pub async fn locked_for(&self, key: &str) -> Option<Duration> {
+        match self {
+            Store::Memory(store) => store.locked_for(key).await,
+            Store::Mongo(store) => store.locked_for(key).await,
+        }
+    }
+
+    pub async fn record_failure(&self, key: &str, lockout: Lockout) {
+        match self {
+            Store::Memory(store) => store.record_failure(key, lockout).await,
+            Store::Mongo(store) => store.record_failure(key, lockout).await,
+        }
+    }
+
+    pub async fn reset(&self, key: &str) {
+        match self {
+            Store::Memory(store) => store.reset(key).await,
+            Store::Mongo(store) => store.reset(key).await,
+        }
+    }
+}
+
+pub struct Limiter {
+    pub config: LimiterConfig,
+    pub store: Store,
+}
+
+async fn init_limiter() -> Limiter {
+    let store = match env::var("RATE_LIMIT_STORE").as_deref() {
+        Ok("mongo") => Store::Mongo(MongoStore::new().await),
+        _ => Store::Memory(MemoryStore::default()),
+    };
+
+    Limiter {
+        config: LimiterConfig::from_env(),
+        store,
+    }
+}
+
+pub async fn get_limiter() -> &'static Limiter {
+    LIMITER.get_or_init(init_limiter).await
+}
+
+/// Axum middleware throttling the auth and AI server functions per client IP,
+/// per `x-api-key` and, for auth calls, per account email. Failed logins also
+/// lock the account out progressively.
+pub async fn rate_limit(req: Request, next: Next) -> Response {
+    let endpoint = endpoint(req.uri().path()).map(str::to_string);
+    let endpoint = endpoint.as_deref();
+    let is_auth = endpoint.is_some_and(|name| AUTH_ENDPOINTS.contains(&name));
+    let is_ai = endpoint.is_some_and(|name| AI_ENDPOINTS.contains(&name));
+    if !is_auth && !is_ai {
+        return next.run(req).await;
+    }
+
+    let limiter = get_limiter().await;
+    let (scope, limit) = if is_auth {
+        ("auth", limiter.config.auth)
+    } else {
+        ("ai", limiter.config.ai)
+    };
+
+    let mut keys = Vec::new();
+    if let Some(ip) = client_ip(&req, limiter.config.trust_proxy) {
+        keys.push(format!("{scope}:ip:{ip}"));
+    }
+    if let Some(api_key) = req
+        .headers()
+        .get(API_KEY_HEADER)
+        .and_then(|value| value.to_str().ok())
+    {
+        keys.push(format!("{scope}:key:{api_key}"));
+    }
+
+    let (req, account) = if is_auth {
+        match read_account(req).await {
+            Ok(result) => result,
+            Err(response) => return response,
+        }
+    } else {
+        (req, None)
+    };
+    if let Some(email) = &account {
+        keys.push(format!("{scope}:account:{email}"));
+    }
+
+    let login_key = account
+        .as_ref()
+        .filter(|_| endpoint == Some(LOGIN_ENDPOINT))
+        .map(|email| format!("login:{email}"));
+
+    if let Some(key) = &login_key {
+        if let Some(remaining) = limiter.store.locked_for(key).await {
+            return too_many_requests(remaining);
+        }
+    }
+
+    for key in &keys {
+        if let Decision::Limited(retry_after) = limiter.store.hit(key, limit).await {
+            tracing::warn!("rate limit exceeded for {key}");
+            return too_many_requests(retry_after);
+        }
+    }
+
+    let response = next.run(req).await;
+
+    if let Some(key) = &login_key {
+        if response.status().is_success() {
+            limiter.store.reset(key).await;
+        } else {
+            limiter
+                .store
+                .record_failure(key, limiter.config.lockout)
+                .await;
+        }
+    }
+
+    response
+}
+
+/// The name of the API endpoint at `path`. Server functions are served at
+/// `/api/<name><hash>`, with a numeric hash the name never ends in.
+fn endpoint(path: &str) -> Option<&str> {
+    let name = path.strip_prefix("/api/")?.trim_end_matches('/');
+    let name = name.trim_end_matches(|c: char| c.is_ascii_digit());
+    (!name.is_empty() && !name.contains('/')).then_some(name)
+}
+
+fn client_ip(req: &Request, trust_proxy: bool) -> Option<String> {
+    if trust_proxy {
+        let forwarded = req
+            .headers()
+            .get("x-forwarded-for")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.split(',').next())
+            .map(|ip| ip.trim().to_string());
+        if forwarded.is_some() {
+            return forwarded;
+        }
+    }
+
+    req.extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip().to_string())
+}
+
+/// Buffers the url-encoded server function body to read `body[email]`, then
+/// hands back an equivalent request.
+async fn read_account(req: Request) -> Result<(Request, Option<String>), Response> {
+    let is_form = req
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("application/x-www-form-urlencoded"));
+    if !is_form {
+        return Ok((req, None));
+    }
+
+    let (parts, body) = req.into_parts();
+    let bytes = to_bytes(body, MAX_AUTH_BODY)
+        .await
+        .map_err(|_| (StatusCode::PAYLOAD_TOO_LARGE, "Request body too large").into_response())?;
+    let email = form_urlencoded::parse(&bytes)
+        .find(|(key, _)| key == "body[email]")
+        .map(|(_, value)| value.trim().to_lowercase())
+        .filter(|value| !value.is_empty());
+
+    Ok((Request::from_parts(parts, Body::from(bytes)), email))
+}
+
+fn too_many_requests(retry_after: Duration) -> Response {
+    let seconds = retry_after.num_seconds().max(1);
+    (
+        StatusCode::TOO_MANY_REQUESTS,
+        [(RETRY_AFTER, seconds.to_string())],
+        format!("Too many requests, retry in {seconds} seconds"),
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{endpoint, Lockout, AI_ENDPOINTS};
+    use chrono::Duration;
+
+    #[test]
+    fn names_server_function_paths() {
+        assert_eq!(endpoint("/api/localize_og15392846123"), Some("localize_og"));
+        assert_eq!(endpoint("/api/stream_query"), Some("stream_query"));
+        assert_eq!(endpoint("/api/search_ogs/"), Some("search_ogs"));
+        assert_eq!(endpoint("/api/"), None);
+        assert_eq!(endpoint("/api/nested/search_ogs1"), None);
+        assert_eq!(endpoint("/dashboard/ogs"), None);
+    }
+
+    #[test]
+    fn matches_ai_endpoints_exactly() {
+        let is_ai = |path| endpoint(path).is_some_and(|name| AI_ENDPOINTS.contains(&name));
+        assert!(is_ai("/api/auto_title_conversation998"));
+        assert!(is_ai("/api/find_similar_ogs12"));
+        assert!(is_ai("/api/upload_og"));
+        assert!(is_ai("/api/import_conversations31"));
+        assert!(!is_ai("/api/search_conversations12"));
+        assert!(!is_ai("/api/get_ai_models12"));
+    }
+
+    #[test]
+    fn lockout_doubles_up_to_the_cap() {
+        let lockout = Lockout {
+            threshold: 3,
+            base: Duration::seconds(10),
+            max: Duration::seconds(60),
+        };
+        assert_eq!(lockout.duration(2), None);
+        assert_eq!(lockout.duration(3), Some(Duration::seconds(10)));
+        assert_eq!(lockout.duration(4), Some(Duration::seconds(20)));
+        assert_eq!(lockout.duration(10), Some(Duration::seconds(60)));
+    }
+
+    #[test]
+    fn lockout_saturates_instead_of_overflowing() {
+        let lockout = Lockout {
+            threshold: 1,
+            base: Duration::MAX / 2,
+            max: Duration::days(1),
+        };
+        assert_eq!(lockout.duration(1), Some(Duration::days(1)));
+        assert_eq!(lockout.duration(u32::MAX), Some(Duration::days(1)));
+
+        let unbounded = Lockout {
+            max: Duration::MAX,
+            ..lockout
+        };
+        assert_eq!(unbounded.duration(u32::MAX), Some(Duration::days(365)));
+    }
+}
@@ -0,0 +1,88 @@
+use chrono::prelude::*;
+use chrono::Duration;
+use std::collections::HashMap;
+use tokio::sync::Mutex;
+
+use crate::limiter::{Decision, Limit, Lockout};
+
+const PRUNE_THRESHOLD: usize = 10_000;
+
+struct Window {
+    count: u32,
+    reset_at: DateTime<Utc>,
+}
+
+struct Failures {
+    count: u32,
+    locked_until: Option<DateTime<Utc>>,
+    expires_at: DateTime<Utc>,
+}
+
+#[derive(Default)]
+pub struct MemoryStore {
+    windows: Mutex<HashMap<String, Window>>,
+    failures: Mutex<HashMap<String, Failures>>,
+}
+
+impl MemoryStore {
+    pub async fn hit(&self, key: &str, limit: Limit) -> Decision {
+        let now = Utc::now();
+        let mut windows = self.windows.lock().await;
+
+        if windows.len() > PRUNE_THRESHOLD {
+            windows.retain(|_, window| window.reset_at > now);
+        }
+
+        let window = windows.entry(key.to_string()).or_insert(Window {
+            count: 0,
+            reset_at: now + limit.window,
+        });
+        if window.reset_at <= now {
+            window.count = 0;
+            window.reset_at = now + limit.window;
+        }
+        window.count += 1;
+
+        if window.count > limit.max {
+            Decision::Limited(window.reset_at - now)
+        } else {
+            Decision::Allowed
+        }
+    }
+
+    pub async fn locked_for(&self, key: &str) -> Option<Duration> {
+        let now = Utc::now();
+        let failures = self.failures.lock().await;
+
+        failures
+            .get(key)
+            .and_then(|entry| entry.locked_until)
+            .filter(|until| *until > now)
+            .map(|until| until - now)
+    }
+
+    pub async fn record_failure(&self, key: &str, lockout: Lockout) {
+        let now = Utc::now();
+        let mut failures = self.failures.lock().await;
+
+        if failures.len() > PRUNE_THRESHOLD {
+            failures.retain(|_, entry| entry.expires_at > now);
+        }
+
+        let entry = failures.entry(key.to_string()).or_insert(Failures {
+            count: 0,
+            locked_until: None,
+            expires_at: now,
+        });
+        if entry.expires_at <= now {
+            entry.count = 0;
+        }
+        entry.count += 1;
+        entry.expires_at = now + lockout.max;
+        entry.locked_until = lockout.duration(entry.count).map(|lock| now + lock);
+    }
+
+    pub async fn reset(&self, key: &str) {
+        self.failures.lock().await.remove(key);
+    }
+}
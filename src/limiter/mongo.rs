@@ -0,0 +1,144 @@
+use bson::{doc, serde_helpers::chrono_datetime_as_bson_datetime};
+use chrono::prelude::*;
+use chrono::Duration;
+use dioxus_logger::tracing;
+use mongodb::options::ReturnDocument;
+use mongodb::Collection;
+use serde::{Deserialize, Serialize};
+
+use crate::db::get_client;
+use crate::limiter::{Decision, Limit, Lockout};
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+struct Window {
+    #[serde(rename = "_id")]
+    key: String,
+    count: u32,
+    #[serde(with = "chrono_datetime_as_bson_datetime", rename = "resetAt")]
+    reset_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+struct Failures {
+    #[serde(rename = "_id")]
+    key: String,
+    count: u32,
+    #[serde(with = "chrono_datetime_as_bson_datetime", rename = "lockedUntil")]
+    locked_until: DateTime<Utc>,
+    #[serde(with = "chrono_datetime_as_bson_datetime", rename = "expiresAt")]
+    expires_at: DateTime<Utc>,
+}
+
+/// Shares counters between server instances through the `rate_limits` and
+/// `login_failures` collections. Database errors fail open so an outage does
+/// not lock every user out.
+pub struct MongoStore {
+    windows: Collection<Window>,
+    failures: Collection<Failures>,
+}
+
+impl MongoStore {
+    pub async fn new() -> Self {
+        let client = get_client().await;
+        let db = client
+            .database(&std::env::var("MONGODB_DB_NAME").expect("MONGODB_DB_NAME must be set."));
+
+        Self {
+            windows: db.collection::<Window>("rate_limits"),
+            failures: db.collection::<Failures>("login_failures"),
+        }
+    }
+
+    pub async fn hit(&self, key: &str, limit: Limit) -> Decision {
+        let now = Utc::now();
+
+        let current = self
+            .windows
+            .find_one_and_update(
+                doc! { "_id": key, "resetAt": { "$gt": now } },
+                doc! { "$inc": { "count": 1 } },
+            )
+            .return_document(ReturnDocument::After)
+            .await;
+
+        let window = match current {
+            Ok(Some(window)) => window,
+            Ok(None) => {
+                let window = Window {
+                    key: key.to_string(),
+                    count: 1,
+                    reset_at: now + limit.window,
+                };
+                if let Err(err) = self
+                    .windows
+                    .replace_one(doc! { "_id": key }, window.clone())
+                    .upsert(true)
+                    .await
+                {
+                    tracing::error!("rate limit store error: {err}");
+                }
+                window
+            }
+            Err(err) => {
+                tracing::error!("rate limit store error: {err}");
+                return Decision::Allowed;
+            }
+        };
+
+        if window.count > limit.max {
+            Decision::Limited(window.reset_at - now)
+        } else {
+            Decision::Allowed
+        }
+    }
+
+    pub async fn locked_for(&self, key: &str) -> Option<Duration> {
+        let now = Utc::now();
+
+        match self.failures.find_one(doc! { "_id": key }).await {
+            Ok(entry) => entry
+                .map(|entry| entry.locked_until)
+                .filter(|until| *until > now)
+                .map(|until| until - now),
+            Err(err) => {
+                tracing::error!("rate limit store error: {err}");
+                None
+            }
+        }
+    }
+
+    pub async fn record_failure(&self, key: &str, lockout: Lockout) {
+        let now = Utc::now();
+
+        let previous = match self.failures.find_one(doc! { "_id": key }).await {
+            Ok(entry) => entry.filter(|entry| entry.expires_at > now),
+            Err(err) => {
+                tracing::error!("rate limit store error: {err}");
+                return;
+            }
+        };
+
+        let count = previous.map_or(0, |entry| entry.count) + 1;
+        let entry = Failures {
+            key: key.to_string(),
+            count,
+            locked_until: lockout.duration(count).map_or(now, |lock| now + lock),
+            expires_at: now + lockout.max,
+        };
+
+        if let Err(err) = self
+            .failures
+            .replace_one(doc! { "_id": key }, entry)
+            .upsert(true)
+            .await
+        {
+            tracing::error!("rate limit store error: {err}");
+        }
+    }
+
+    pub async fn reset(&self, key: &str) {
+        if let Err(err) = self.failures.delete_one(doc! { "_id": key }).await {
+            tracing::error!("rate limit store error: {err}");
+        }
+    }
+}
@@ -0,0 +1,485 @@
+pub(crate) mod mock;
+pub(crate) mod ollama;
+pub(crate) mod openai;
+
+use dioxus_logger::tracing;
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::fmt;
+use std::future::Future;
+use std::time::Duration;
+use tokio::sync::mpsc::Sender;
+use tokio::sync::OnceCell;
+
+use crate::llm::mock::MockProvider;
+use crate::llm::ollama::OllamaProvider;
+use crate::llm::openai::OpenAIProvider;
+
+static LLM: OnceCell<Llm> = OnceCell::const_new();
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChatRole {
+    System,
+    User,
+    Assistant,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatMessage {
+    pub role: ChatRole,
+    pub content: String,
+    /// Base64 encoded PNG images sent along with the text, for vision models.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub images: Vec<String>,
+}
+
+impl ChatMessage {
+    pub fn system(content: impl Into<String>) -> Self {
+        Self {
+            role: ChatRole::System,
+            content: content.into(),
+            images: Vec::new(),
+        }
+    }
+
+    pub fn user(content: impl Into<String>) -> Self {
+        Self {
+            role: ChatRole::User,
+            content: content.into(),
+            images: Vec::new(),
+        }
+    }
+
+    pub fn assistant(content: impl Into<String>) -> Self {
+        Self {
+            role: ChatRole::Assistant,
+            content: content.into(),
+            images: Vec::new(),
+        }
+    }
+
+    /// Attaches a base64 encoded PNG to the message.
+    pub fn with_image(mut self, image: impl Into<String>) -> Self {
+        self.images.push(image.into());
+        self
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Completion {
+    pub model: String,
+    pub messages: Vec<ChatMessage>,
+    pub temperature: f32,
+    pub max_tokens: u32,
+}
+
+#[derive(Debug)]
+pub enum LlmError {
+    Timeout,
+    Status(u16, String),
+    Transport(String),
+    InvalidResponse(String),
+    UnknownModel(String),
+    /// The stream broke after tokens had already been delivered.
+    Interrupted(String),
+    /// The receiving end of a stream went away.
+    Cancelled,
+}
+
+impl LlmError {
+    /// Timeouts, dropped connections, rate limiting and upstream 5xx errors
+    /// are worth another attempt; everything else fails straight away.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            LlmError::Timeout | LlmError::Transport(_) => true,
+            LlmError::Status(status, _) => *status == 429 || *status >= 500,
+            LlmError::InvalidResponse(_)
+            | LlmError::UnknownModel(_)
+            | LlmError::Interrupted(_)
+            | LlmError::Cancelled => false,
+        }
+    }
+}
+
+impl fmt::Display for LlmError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LlmError::Timeout => write!(f, "AI provider timed out"),
+            LlmError::Status(status, body) => write!(f, "AI provider returned {status}: {body}"),
+            LlmError::Transport(err) => write!(f, "AI provider unreachable: {err}"),
+            LlmError::InvalidResponse(err) => write!(f, "Invalid AI provider response: {err}"),
+            LlmError::UnknownModel(model) => write!(f, "Model '{model}' is not available"),
+            LlmError::Interrupted(err) => write!(f, "AI response interrupted: {err}"),
+            LlmError::Cancelled => write!(f, "AI response cancelled"),
+        }
+    }
+}
+
+impl std::error::Error for LlmError {}
+
+impl From<reqwest::Error> for LlmError {
+    fn from(err: reqwest::Error) -> Self {
+        if err.is_timeout() {
+            LlmError::Timeout
+        } else if err.is_decode() {
+            LlmError::InvalidResponse(err.to_string())
+        } else {
+            LlmError::Transport(err.to_string())
+        }
+    }
+}
+
+/// Rough token count of `text`, about four characters per token. Providers
+/// differ in what usage they report, so metering relies on this estimate.
+pub fn estimate_tokens(text: &str) -> u64 {
+    (text.chars().count() as u64).div_ceil(4)
+}
+
+/// A backend able to turn a chat transcript into a completion, either in one
+/// piece or token by token.
+pub trait LlmProvider {
+    fn complete(
+        &self,
+        request: &Completion,
+    ) -> impl Future<Output = Result<String, LlmError>> + Send;
+
+    /// Sends completion deltas into `tx` as they arrive. `tx` is bounded, so a
+    /// slow reader pauses the upstream read; a closed `tx` ends the stream
+    /// with [`LlmError::Cancelled`].
+    fn stream(
+        &self,
+        request: &Completion,
+        tx: &Sender<String>,
+    ) -> impl Future<Output = Result<(), LlmError>> + Send;
+}
+
+/// Reassembles the lines of a chunked streaming body. Bytes are buffered
+/// until a newline so multi-byte characters split across chunks survive.
+#[derive(Default)]
+pub(crate) struct LineBuffer {
+    pending: Vec<u8>,
+}
+
+impl LineBuffer {
+    pub fn push(&mut self, chunk: &[u8]) -> Vec<String> {
+        self.pending.extend_from_slice(chunk);
+        let mut lines = Vec::new();
+        while let Some(end) = self.pending.iter().position(|byte| *byte == b'\n') {
+            let line: Vec<u8> = self.pending.drain(..=end).collect();
+            let line = String::from_utf8_lossy(&line).trim().to_string();
+            if !line.is_empty() {
+                lines.push(line);
+            }
+        }
+        lines
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LlmConfig {
+    pub provider: String,
+    pub base_url: String,
+    pub api_key: Option<String>,
+    pub model: String,
+    pub models: Vec<String>,
+    /// Model able to read images, used where a picture helps, e.g. alt text.
+    pub vision_model: Option<String>,
+    pub timeout: Duration,
+    pub max_retries: u32,
+    pub retry_backoff: Duration,
+}
+
+impl LlmConfig {
+    pub fn from_env() -> Self {
+        let provider = env::var("LLM_PROVIDER").unwrap_or_else(|_| "openai".into());
+        let default_url = match provider.as_str() {
+            "ollama" => "http://localhost:11434",
+            _ => "https://api.openai.com/v1",
+        };
+        let model = env::var("LLM_MODEL").unwrap_or_else(|_| "gpt-4o-mini".into());
+        let mut models: Vec<String> = env::var("LLM_MODELS")
+            .unwrap_or_default()
+            .split(',')
+            .map(|model| model.trim().to_string())
+            .filter(|model| !model.is_empty())
+            .collect();
+        if !models.contains(&model) {
+            models.insert(0, model.clone());
+        }
+
+        Self {
+            base_url: env::var("LLM_BASE_URL")
+                .unwrap_or_else(|_| default_url.into())
+                .trim_end_matches('/')
+                .to_string(),
+            api_key: env::var("LLM_API_KEY").ok().filter(|key| !key.is_empty()),
+            model,
+            models,
+            vision_model: env::var("LLM_VISION_MODEL")
+                .ok()
+                .map(|model| model.trim().to_string())
+                .filter(|model| !model.is_empty()),
+            timeout: Duration::from_secs(env_or("LLM_TIMEOUT_SECS", 30)),
+            max_retries: env_or("LLM_MAX_RETRIES", 2),
+            retry_backoff: Duration::from_millis(env_or("LLM_RETRY_BACKOFF_MS", 500)),
+            provider,
+        }
+    }
+}
+
+fn env_or<T: std::str::FromStr>(key: &str, default: T) -> T {
+    env::var(key)
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(default)
+}
+
+pub enum Provider {
+    OpenAI(OpenAIProvider),
+    Ollama(OllamaProvider),
+    Mock(MockProvider),
+}
+
+impl LlmProvider for Provider {
+    async fn complete(&self, request: &Completion) -> Result<String, LlmError> {
+        match self {
+            Provider::OpenAI(provider) => provider.complete(request).await,
+            Provider::Ollama(provider) => provider.complete(request).await,
+            Provider::Mock(provider) => provider.complete(request).await,
+        }
+    }
+
+    async fn stream(&self, request: &Completion, tx: &Sender<String>) -> Result<(), LlmError> {
+        match self {
+            Provider::OpenAI(provider) => provider.stream(request, tx).await,
+            Provider::Ollama(provider) => provider.stream(request, tx).await,
+            Provider::Mock(provider) => provider.stream(request, tx).await,
+        }
+    }
+}
+
+pub struct Llm {
+    pub config: LlmConfig,
+    pub provider: Provider,
+}
+
+impl Llm {
+    pub fn new(config: LlmConfig) -> Self {
+        let provider = match config.provider.as_str() {
+            "ollama" => Provider::Ollama(OllamaProvider::new(&config)),
+            "mock" => Provider::Mock(MockProvider),
+            _ => Provider::OpenAI(OpenAIProvider::new(&config)),
+        };
+
+        Self { config, provider }
+    }
+
+    /// The default model when none is requested, otherwise `requested` as long
+    /// as it is one of the configured models or the vision model.
+    pub fn resolve_model(&self, requested: Option<&str>) -> Result<String, LlmError> {
+        match requested.map(str::trim).filter(|model| !model.is_empty()) {
+            None => Ok(self.config.model.clone()),
+            Some(model)
+                if self.config.models.iter().any(|m| m == model)
+                    || self.config.vision_model.as_deref() == Some(model) =>
+            {
+                Ok(model.into())
+            }
+            Some(model) => Err(LlmError::UnknownModel(model.into())),
+        }
+    }
+
+    /// Sends `messages` to the configured provider, retrying transient
+    /// failures with exponential backoff.
+    pub async fn complete(
+        &self,
+        model: Option<&str>,
+        messages: Vec<ChatMessage>,
+        temperature: f32,
+        max_tokens: u32,
+    ) -> Result<String, LlmError> {
+        let request = Completion {
+            model: self.resolve_model(model)?,
+            messages,
+            temperature,
+            max_tokens,
+        };
+
+        let mut attempt = 0;
+        loop {
+            match self.provider.complete(&request).await {
+                Ok(text) => return Ok(text.trim().to_string()),
+                Err(err) if err.is_retryable() && attempt < self.config.max_retries => {
+                    self.backoff(attempt, &err).await;
+                    attempt += 1;
+                }
+                Err(err) => return Err(err),
+            }
+        }
+    }
+
+    /// Streaming counterpart of [`Llm::complete`]. Only failures before the
+    /// first token are retried; providers report later ones as
+    /// [`LlmError::Interrupted`].
+    pub async fn stream(
+        &self,
+        model: Option<&str>,
+        messages: Vec<ChatMessage>,
+        temperature: f32,
+        max_tokens: u32,
+        tx: &Sender<String>,
+    ) -> Result<(), LlmError> {
+        let request = Completion {
+            model: self.resolve_model(model)?,
+            messages,
+            temperature,
+            max_tokens,
+        };
+
+        let mut attempt = 0;
+        loop {
+            match self.provider.stream(&request, tx).await {
+                Err(err) if err.is_retryable() && attempt < self.config.max_retries => {
+                    self.backoff(attempt, &err).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    async fn backoff(&self, attempt: u32, err: &LlmError) {
+        let delay = self.config.retry_backoff * 2_u32.pow(attempt.min(6));
+        tracing::warn!("LLM attempt {} failed: {err}, retrying", attempt + 1);
+        tokio::time::sleep(delay).await;
+    }
+}
+
+pub async fn get_llm() -> &'static Llm {
+    LLM.get_or_init(|| async { Llm::new(LlmConfig::from_env()) })
+        .await
+}
+
+/// An [`Llm`] backed by [`MockProvider`], for tests that need a model without
+/// the network or the environment.
+#[cfg(test)]
+pub(crate) fn mock_llm() -> Llm {
+    Llm::new(LlmConfig {
+        provider: "mock".into(),
+        base_url: String::new(),
+        api_key: None,
+        model: "mock-model".into(),
+        models: vec!["mock-model".into(), "mock-large".into()],
+        vision_model: Some("mock-vision".into()),
+        timeout: Duration::from_secs(1),
+        max_retries: 0,
+        retry_backoff: Duration::ZERO,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{estimate_tokens, mock_llm, ChatMessage, LineBuffer, LlmError, Provider};
+    use tokio::sync::mpsc;
+
+    #[test]
+    fn selects_mock_provider() {
+        assert!(matches!(mock_llm().provider, Provider::Mock(_)));
+    }
+
+    #[test]
+    fn resolves_only_configured_models() {
+        let llm = mock_llm();
+        assert_eq!(llm.resolve_model(None).unwrap(), "mock-model");
+        assert_eq!(llm.resolve_model(Some("  ")).unwrap(), "mock-model");
+        assert_eq!(llm.resolve_model(Some("mock-large")).unwrap(), "mock-large");
+        assert_eq!(
+            llm.resolve_model(Some("mock-vision")).unwrap(),
+            "mock-vision"
+        );
+        assert!(matches!(
+            llm.resolve_model(Some("gpt-unknown")),
+            Err(LlmError::UnknownModel(model)) if model == "gpt-unknown"
+        ));
+    }
+
+    #[tokio::test]
+    async fn complete_uses_requested_model() {
+        let llm = mock_llm();
+        let messages = vec![
+            ChatMessage::system("be brief"),
+            ChatMessage::user("name my coffee shop"),
+        ];
+
+        let reply = llm
+            .complete(Some("mock-large"), messages.clone(), 0.5, 50)
+            .await
+            .unwrap();
+        assert_eq!(reply, "[mock-large] name my coffee shop");
+        assert!(llm
+            .complete(Some("other"), messages, 0.5, 50)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn stream_matches_complete() {
+        let llm = mock_llm();
+        let messages = vec![ChatMessage::user("stream these few words")];
+        let (tx, mut rx) = mpsc::channel(64);
+
+        llm.stream(None, messages.clone(), 0.5, 50, &tx)
+            .await
+            .unwrap();
+        drop(tx);
+        let mut streamed = String::new();
+        while let Some(token) = rx.recv().await {
+            streamed.push_str(&token);
+        }
+
+        let completed = llm.complete(None, messages, 0.5, 50).await.unwrap();
+        assert_eq!(streamed, completed);
+    }
+
+    #[tokio::test]
+    async fn stream_stops_when_receiver_is_gone() {
+        let llm = mock_llm();
+        let (tx, rx) = mpsc::channel(1);
+        drop(rx);
+
+        let result = llm
+            .stream(None, vec![ChatMessage::user("hello there")], 0.5, 50, &tx)
+            .await;
+        assert!(matches!(result, Err(LlmError::Cancelled)));
+    }
+
+    #[test]
+    fn retries_only_transient_errors() {
+        assert!(LlmError::Timeout.is_retryable());
+        assert!(LlmError::Transport("reset".into()).is_retryable());
+        assert!(LlmError::Status(429, String::new()).is_retryable());
+        assert!(LlmError::Status(503, String::new()).is_retryable());
+        assert!(!LlmError::Status(400, String::new()).is_retryable());
+        assert!(!LlmError::UnknownModel("x".into()).is_retryable());
+        assert!(!LlmError::Interrupted("x".into()).is_retryable());
+        assert!(!LlmError::Cancelled.is_retryable());
+    }
+
+    #[test]
+    fn line_buffer_joins_split_chunks() {
+        let mut buffer = LineBuffer::default();
+        let bytes = "data: caf\u{e9}\n\ndata: [DONE]\n".as_bytes();
+        let (first, rest) = bytes.split_at(10);
+
+        assert!(buffer.push(first).is_empty());
+        assert_eq!(buffer.push(rest), vec!["data: café", "data: [DONE]"]);
+    }
+
+    #[test]
+    fn estimates_four_characters_per_token() {
+        assert_eq!(estimate_tokens(""), 0);
+        assert_eq!(estimate_tokens("abcd"), 1);
+        assert_eq!(estimate_tokens("abcde"), 2);
+    }
+}
@@ -0,0 +1,93 @@
+use tokio::sync::mpsc::Sender;
+
+use crate::llm::{ChatRole, Completion, LlmError, LlmProvider};
+
+const ECHO_WORDS: usize = 12;
+
+/// Offline provider for development and tests. The reply only depends on the
+/// model name and the last user message, so the same request always yields
+/// the same text.
+pub struct MockProvider;
+
+impl LlmProvider for MockProvider {
+    async fn complete(&self, request: &Completion) -> Result<String, LlmError> {
+        let prompt = request
+            .messages
+            .iter()
+            .rev()
+            .find(|message| message.role == ChatRole::User)
+            .map(|message| message.content.as_str())
+            .unwrap_or_default();
+        let echo: Vec<&str> = prompt.split_whitespace().take(ECHO_WORDS).collect();
+
+        Ok(format!("[{}] {}", request.model, echo.join(" ")))
+    }
+
+    async fn stream(&self, request: &Completion, tx: &Sender<String>) -> Result<(), LlmError> {
+        let reply = self.complete(request).await?;
+        for (index, word) in reply.split(' ').enumerate() {
+            let token = if index == 0 {
+                word.to_string()
+            } else {
+                format!(" {word}")
+            };
+            tx.send(token).await.map_err(|_| LlmError::Cancelled)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MockProvider, ECHO_WORDS};
+    use crate::llm::{ChatMessage, Completion, LlmProvider};
+
+    fn completion(model: &str, messages: Vec<ChatMessage>) -> Completion {
+        Completion {
+            model: model.into(),
+            messages,
+            temperature: 0.9,
+            max_tokens: 10,
+        }
+    }
+
+    #[tokio::test]
+    async fn same_request_same_reply() {
+        let request = completion("m", vec![ChatMessage::user("write a title")]);
+        let first = MockProvider.complete(&request).await.unwrap();
+        let second = MockProvider.complete(&request).await.unwrap();
+        assert_eq!(first, second);
+        assert_eq!(first, "[m] write a title");
+    }
+
+    #[tokio::test]
+    async fn echoes_the_last_user_message() {
+        let request = completion(
+            "m",
+            vec![
+                ChatMessage::system("ignored system"),
+                ChatMessage::user("first question"),
+                ChatMessage::assistant("an answer"),
+                ChatMessage::user("second question"),
+            ],
+        );
+        assert_eq!(
+            MockProvider.complete(&request).await.unwrap(),
+            "[m] second question"
+        );
+    }
+
+    #[tokio::test]
+    async fn caps_the_echo() {
+        let words: Vec<String> = (0..ECHO_WORDS + 5).map(|i| format!("w{i}")).collect();
+        let request = completion("m", vec![ChatMessage::user(words.join(" "))]);
+        let reply = MockProvider.complete(&request).await.unwrap();
+        assert_eq!(reply.split(' ').count(), ECHO_WORDS + 1);
+    }
+
+    #[tokio::test]
+    async fn replies_without_a_user_message() {
+        let request = completion("m", vec![ChatMessage::system("only system")]);
+        assert_eq!(MockProvider.complete(&request).await.unwrap(), "[m] ");
+    }
+}
@@ -0,0 +1,103 @@
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc::Sender;
+
+use crate::llm::{ChatMessage, Completion, LineBuffer, LlmConfig, LlmError, LlmProvider};
+
+/// A local Ollama server using its native `/api/chat` endpoint.
+pub struct OllamaProvider {
+    http: reqwest::Client,
+    base_url: String,
+}
+
+#[derive(Serialize)]
+struct ChatRequest<'a> {
+    model: &'a str,
+    messages: &'a [ChatMessage],
+    stream: bool,
+    options: Options,
+}
+
+#[derive(Serialize)]
+struct Options {
+    temperature: f32,
+    num_predict: u32,
+}
+
+#[derive(Deserialize)]
+struct ChatResponse {
+    message: ChatMessage,
+    #[serde(default)]
+    done: bool,
+}
+
+impl OllamaProvider {
+    pub fn new(config: &LlmConfig) -> Self {
+        Self {
+            http: reqwest::Client::builder()
+                .connect_timeout(config.timeout)
+                .read_timeout(config.timeout)
+                .build()
+                .expect("failed to build LLM HTTP client"),
+            base_url: config.base_url.clone(),
+        }
+    }
+
+    async fn send(
+        &self,
+        request: &Completion,
+        stream: bool,
+    ) -> Result<reqwest::Response, LlmError> {
+        let response = self
+            .http
+            .post(format!("{}/api/chat", self.base_url))
+            .json(&ChatRequest {
+                model: &request.model,
+                messages: &request.messages,
+                stream,
+                options: Options {
+                    temperature: request.temperature,
+                    num_predict: request.max_tokens,
+                },
+            })
+            .send()
+            .await?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(LlmError::Status(status.as_u16(), body));
+        }
+        Ok(response)
+    }
+}
+
+impl LlmProvider for OllamaProvider {
+    async fn complete(&self, request: &Completion) -> Result<String, LlmError> {
+        let body: ChatResponse = self.send(request, false).await?.json().await?;
+        Ok(body.message.content)
+    }
+
+    async fn stream(&self, request: &Completion, tx: &Sender<String>) -> Result<(), LlmError> {
+        let mut response = self.send(request, true).await?;
+        let mut lines = LineBuffer::default();
+
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .map_err(|err| LlmError::Interrupted(err.to_string()))?
+        {
+            for line in lines.push(&chunk) {
+                let chunk: ChatResponse = serde_json::from_str(&line)
+                    .map_err(|err| LlmError::InvalidResponse(err.to_string()))?;
+                if !chunk.message.content.is_empty()
+                    && tx.send(chunk.message.content).await.is_err()
+                {
+                    return Err(LlmError::Cancelled);
+                }
+                if chunk.done {
+                    return Ok(());
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,160 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tokio::sync::mpsc::Sender;
+
+use crate::llm::{ChatMessage, ChatRole, Completion, LineBuffer, LlmConfig, LlmError, LlmProvider};
+
+/// Any server speaking the OpenAI chat completions API: OpenAI itself,
+/// llama.cpp's `llama-server`, vLLM, LM Studio and similar.
+pub struct OpenAIProvider {
+    http: reqwest::Client,
+    base_url: String,
+    api_key: Option<String>,
+}
+
+#[derive(Serialize)]
+struct ChatRequest<'a> {
+    model: &'a str,
+    messages: Vec<WireMessage>,
+    temperature: f32,
+    max_tokens: u32,
+    stream: bool,
+}
+
+/// A message as the API expects it: images turn the content into a list of
+/// text and `image_url` parts.
+#[derive(Serialize)]
+struct WireMessage {
+    role: ChatRole,
+    content: Value,
+}
+
+impl From<&ChatMessage> for WireMessage {
+    fn from(message: &ChatMessage) -> Self {
+        let content = if message.images.is_empty() {
+            json!(message.content)
+        } else {
+            let mut parts = vec![json!({ "type": "text", "text": message.content })];
+            parts.extend(message.images.iter().map(|image| {
+                json!({
+                    "type": "image_url",
+                    "image_url": { "url": format!("data:image/png;base64,{image}") },
+                })
+            }));
+            Value::Array(parts)
+        };
+        Self {
+            role: message.role,
+            content,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct ChatResponse {
+    choices: Vec<Choice>,
+}
+
+#[derive(Deserialize)]
+struct Choice {
+    message: ChatMessage,
+}
+
+#[derive(Deserialize)]
+struct StreamChunk {
+    choices: Vec<StreamChoice>,
+}
+
+#[derive(Deserialize)]
+struct StreamChoice {
+    delta: Delta,
+}
+
+#[derive(Deserialize)]
+struct Delta {
+    content: Option<String>,
+}
+
+impl OpenAIProvider {
+    pub fn new(config: &LlmConfig) -> Self {
+        Self {
+            http: reqwest::Client::builder()
+                .connect_timeout(config.timeout)
+                .read_timeout(config.timeout)
+                .build()
+                .expect("failed to build LLM HTTP client"),
+            base_url: config.base_url.clone(),
+            api_key: config.api_key.clone(),
+        }
+    }
+
+    async fn send(
+        &self,
+        request: &Completion,
+        stream: bool,
+    ) -> Result<reqwest::Response, LlmError> {
+        let mut builder = self
+            .http
+            .post(format!("{}/chat/completions", self.base_url))
+            .json(&ChatRequest {
+                model: &request.model,
+                messages: request.messages.iter().map(WireMessage::from).collect(),
+                temperature: request.temperature,
+                max_tokens: request.max_tokens,
+                stream,
+            });
+        if let Some(key) = &self.api_key {
+            builder = builder.bearer_auth(key);
+        }
+
+        let response = builder.send().await?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(LlmError::Status(status.as_u16(), body));
+        }
+        Ok(response)
+    }
+}
+
+impl LlmProvider for OpenAIProvider {
+    async fn complete(&self, request: &Completion) -> Result<String, LlmError> {
+        let body: ChatResponse = self.send(request, false).await?.json().await?;
+        body.choices
+            .into_iter()
+            .next()
+            .map(|choice| choice.message.content)
+            .ok_or(LlmError::InvalidResponse("no choices returned".into()))
+    }
+
+    async fn stream(&self, request: &Completion, tx: &Sender<String>) -> Result<(), LlmError> {
+        let mut response = self.send(request, true).await?;
+        let mut lines = LineBuffer::default();
+
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .map_err(|err| LlmError::Interrupted(err.to_string()))?
+        {
+            for line in lines.push(&chunk) {
+                let Some(data) = line.strip_prefix("data:").map(str::trim) else {
+                    continue;
+                };
+                if data == "[DONE]" {
+                    return Ok(());
+                }
+                let chunk: StreamChunk = serde_json::from_str(data)
+                    .map_err(|err| LlmError::InvalidResponse(err.to_string()))?;
+                let delta = chunk
+                    .choices
+                    .into_iter()
+                    .filter_map(|choice| choice.delta.content)
+                    .collect::<String>();
+                if !delta.is_empty() && tx.send(delta).await.is_err() {
+                    return Err(LlmError::Cancelled);
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -20,8 +20,16 @@ fn main() {
     {
         use axum::http::header::{ACCEPT, AUTHORIZATION, CONTENT_TYPE};
         use axum::http::Method;
-        use axum::{Extension, Router};
+        use axum::routing::post;
+        use axum::{middleware, Extension, Router};
         use dotenv::dotenv;
+        use nano_og::csrf::verify_origin;
+        use nano_og::jobs::{
+            spawn_account_purge, spawn_embedding_backfill, spawn_score_backfill,
+            spawn_stats_snapshots,
+        };
+        use nano_og::limiter::rate_limit;
+        use nano_og::stream::stream_query;
         use std::sync::Arc;
         use tower_http::cors::{Any, CorsLayer};
 
@@ -31,6 +39,11 @@ fn main() {
         tokio::runtime::Runtime::new()
             .unwrap()
             .block_on(async move {
+                spawn_account_purge();
+                spawn_stats_snapshots();
+                spawn_embedding_backfill();
+                spawn_score_backfill();
+
                 let cors = CorsLayer::new()
                     .allow_origin(Any)
                     // TODO
@@ -45,15 +58,21 @@ fn main() {
                     .allow_headers([AUTHORIZATION, ACCEPT, CONTENT_TYPE]);
 
                 let app = Router::new()
+                    .route("/api/stream_query", post(stream_query))
                     .layer(cors)
-                    .serve_dioxus_application(ServeConfig::new().unwrap(), App);
+                    .serve_dioxus_application(ServeConfig::new().unwrap(), App)
+                    .layer(middleware::from_fn(rate_limit))
+                    .layer(middleware::from_fn(verify_origin));
 
                 let addr = std::net::SocketAddr::from(([0, 0, 0, 0], 3000));
                 let listener = tokio::net::TcpListener::bind(&addr).await.unwrap();
 
-                axum::serve(listener, app.into_make_service())
-                    .await
-                    .unwrap();
+                axum::serve(
+                    listener,
+                    app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+                )
+                .await
+                .unwrap();
             });
     }
 }
@@ -0,0 +1,333 @@
+use ammonia::{Builder, UrlRelative};
+use pulldown_cmark::{html, CodeBlockKind, Event, Options, Parser, Tag, TagEnd};
+use std::collections::HashSet;
+
+const KEYWORDS: [&str; 48] = [
+    "as",
+    "async",
+    "await",
+    "break",
+    "case",
+    "class",
+    "const",
+    "continue",
+    "def",
+    "default",
+    "do",
+    "else",
+    "enum",
+    "export",
+    "extends",
+    "false",
+    "fn",
+    "for",
+    "from",
+    "func",
+    "function",
+    "if",
+    "impl",
+    "import",
+    "in",
+    "interface",
+    "let",
+    "loop",
+    "match",
+    "mod",
+    "mut",
+    "new",
+    "null",
+    "pub",
+    "return",
+    "self",
+    "static",
+    "struct",
+    "super",
+    "switch",
+    "trait",
+    "true",
+    "try",
+    "type",
+    "use",
+    "var",
+    "where",
+    "while",
+];
+
+const ALLOWED_TAGS: [&str; 26] = [
+    "p",
+    "br",
+    "hr",
+    "strong",
+    "em",
+    "del",
+    "code",
+    "pre",
+    "span",
+    "ul",
+    "ol",
+    "li",
+    "blockquote",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "a",
+    "table",
+    "thead",
+    "tbody",
+    "tr",
+    "th",
+    "td",
+];
+
+/// Renders assistant markdown to HTML that is safe to inject. Raw HTML in
+/// the source is shown as text, images are reduced to their alt text, and
+/// the output is passed through an allowlist sanitizer as a second line of
+/// defense.
+pub fn render_markdown(markdown: &str) -> String {
+    let parser = Parser::new_ext(
+        markdown,
+        Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TABLES,
+    );
+
+    let mut events = Vec::new();
+    let mut code_block: Option<(String, String)> = None;
+    for event in parser {
+        match event {
+            Event::Start(Tag::CodeBlock(kind)) => {
+                let language = match kind {
+                    CodeBlockKind::Fenced(info) => info
+                        .split_whitespace()
+                        .next()
+                        .unwrap_or_default()
+                        .to_string(),
+                    CodeBlockKind::Indented => String::new(),
+                };
+                code_block = Some((language, String::new()));
+            }
+            Event::End(TagEnd::CodeBlock) => {
+                if let Some((language, source)) = code_block.take() {
+                    events.push(Event::Html(highlight(&language, &source).into()));
+                }
+            }
+            Event::Text(text) if code_block.is_some() => {
+                if let Some((_, source)) = code_block.as_mut() {
+                    source.push_str(&text);
+                }
+            }
+            Event::Html(raw) | Event::InlineHtml(raw) => events.push(Event::Text(raw)),
+            Event::Start(Tag::Image { .. }) | Event::End(TagEnd::Image) => {}
+            event => events.push(event),
+        }
+    }
+
+    let mut output = String::new();
+    html::push_html(&mut output, events.into_iter());
+    sanitizer().clean(&output).to_string()
+}
+
+fn sanitizer() -> Builder<'static> {
+    let mut builder = Builder::empty();
+    builder
+        .add_tags(ALLOWED_TAGS)
+        .add_tag_attributes("a", ["href"])
+        .add_tag_attributes("ol", ["start"])
+        .add_allowed_classes("pre", ["code-block"])
+        .add_allowed_classes(
+            "span",
+            ["hl-keyword", "hl-string", "hl-comment", "hl-number"],
+        )
+        .url_schemes(HashSet::from(["http", "https", "mailto"]))
+        .url_relative(UrlRelative::Deny)
+        .link_rel(Some("noopener noreferrer nofollow"))
+        .set_tag_attribute_value("a", "target", "_blank");
+    builder
+}
+
+/// A small language-agnostic highlighter: comments, strings, numbers and
+/// common keywords are wrapped in spans, everything else is escaped text.
+fn highlight(language: &str, source: &str) -> String {
+    let hash_comments = matches!(
+        language,
+        "python" | "py" | "bash" | "sh" | "shell" | "ruby" | "rb" | "yaml" | "yml" | "toml"
+    );
+    let chars: Vec<char> = source.chars().collect();
+    let mut html = String::from("<pre class=\"code-block\"><code>");
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let start = i;
+        let class = if (c == '/' && chars.get(i + 1) == Some(&'/')) || (c == '#' && hash_comments) {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+            Some("hl-comment")
+        } else if c == '"' || c == '\'' || c == '`' {
+            i += 1;
+            while i < chars.len() && chars[i] != c && chars[i] != '\n' {
+                if chars[i] == '\\' {
+                    i += 1;
+                }
+                i += 1;
+            }
+            i = (i + 1).min(chars.len());
+            Some("hl-string")
+        } else if c.is_ascii_digit() {
+            while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '.') {
+                i += 1;
+            }
+            Some("hl-number")
+        } else if c.is_alphabetic() || c == '_' {
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            let word: String = chars[start..i].iter().collect();
+            KEYWORDS.contains(&word.as_str()).then_some("hl-keyword")
+        } else {
+            i += 1;
+            None
+        };
+
+        let token: String = chars[start..i].iter().collect();
+        match class {
+            Some(class) => html.push_str(&format!(
+                "<span class=\"{class}\">{}</span>",
+                escape(&token)
+            )),
+            None => html.push_str(&escape(&token)),
+        }
+    }
+
+    html.push_str("</code></pre>");
+    html
+}
+
+/// Escapes `text` for use in HTML text and quoted attribute values.
+pub(crate) fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{render_markdown, ALLOWED_TAGS};
+    use regex::Regex;
+
+    /// Every tag left in `html` must be on the allowlist and carry no event
+    /// handler, inline style or script URL. Escaped text is harmless.
+    fn assert_inert(html: &str) {
+        let tag = Regex::new(r"<\s*/?\s*([a-zA-Z0-9]+)([^>]*)>").unwrap();
+        for caps in tag.captures_iter(html) {
+            let name = caps[1].to_lowercase();
+            let attributes = caps[2].to_lowercase();
+            assert!(
+                ALLOWED_TAGS.contains(&name.as_str()),
+                "<{name}> survived in {html:?}"
+            );
+            for needle in ["javascript:", "vbscript:", "data:", " on", "style", "src="] {
+                assert!(
+                    !attributes.contains(needle),
+                    "{needle:?} survived in <{name}{attributes}> of {html:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn renders_basic_markdown() {
+        let html = render_markdown("**bold** _em_ ~~gone~~\n\n- one\n- two\n\n1. first");
+        assert!(html.contains("<strong>bold</strong>"));
+        assert!(html.contains("<em>em</em>"));
+        assert!(html.contains("<del>gone</del>"));
+        assert!(html.contains("<ul>\n<li>one</li>"));
+        assert!(html.contains("<ol>\n<li>first</li>"));
+    }
+
+    #[test]
+    fn links_open_safely() {
+        let html = render_markdown("[site](https://example.com)");
+        assert!(html.contains("href=\"https://example.com\""));
+        assert!(html.contains("rel=\"noopener noreferrer nofollow\""));
+        assert!(html.contains("target=\"_blank\""));
+    }
+
+    #[test]
+    fn highlights_code_blocks() {
+        let html = render_markdown("```rust\nlet x = \"hi\"; // note\n```");
+        assert!(html.contains("<pre class=\"code-block\"><code>"));
+        assert!(html.contains("<span class=\"hl-keyword\">let</span>"));
+        assert!(html.contains("<span class=\"hl-string\">\"hi\"</span>"));
+        assert!(html.contains("<span class=\"hl-comment\">// note</span>"));
+    }
+
+    #[test]
+    fn escapes_html_inside_code() {
+        let html = render_markdown("```html\n<script>alert(1)</script>\n```\n\n`<b>x</b>`");
+        assert_inert(&html);
+        assert!(html.contains("&lt;script&gt;"));
+        assert!(html.contains("<code>&lt;b&gt;x&lt;/b&gt;</code>"));
+    }
+
+    #[test]
+    fn neutralizes_raw_html() {
+        for payload in [
+            "<script>alert(1)</script>",
+            "<img src=x onerror=alert(1)>",
+            "<svg onload=alert(1)>",
+            "<iframe src=\"javascript:alert(1)\"></iframe>",
+            "<a href=\"javascript:alert(1)\">x</a>",
+            "<div style=\"background:url(javascript:alert(1))\">x</div>",
+            "<details open ontoggle=alert(1)>",
+            "<<script>script>alert(1)<</script>/script>",
+            "<scr<script>ipt>alert(1)</script>",
+            "<body onload=alert(1)>",
+            "<form action=javascript:alert(1)><button>x</button></form>",
+            "<object data=\"data:text/html;base64,PHNjcmlwdD5hbGVydCgxKTwvc2NyaXB0Pg==\">",
+        ] {
+            let html = render_markdown(payload);
+            assert_inert(&html);
+        }
+    }
+
+    #[test]
+    fn drops_dangerous_link_schemes() {
+        for payload in [
+            "[x](javascript:alert(1))",
+            "[x](JaVaScRiPt:alert(1))",
+            "[x](jav&#x09;ascript:alert(1))",
+            "[x](vbscript:msgbox(1))",
+            "[x](data:text/html;base64,PHNjcmlwdD5hbGVydCgxKTwvc2NyaXB0Pg==)",
+            "<javascript:alert(1)>",
+            "[x][ref]\n\n[ref]: javascript:alert(1)",
+        ] {
+            let html = render_markdown(payload);
+            assert_inert(&html);
+        }
+    }
+
+    #[test]
+    fn drops_images() {
+        let html = render_markdown("![alt text](https://example.com/x.png \"t\")");
+        assert_inert(&html);
+        assert!(html.contains("alt text"));
+    }
+
+    #[test]
+    fn escapes_attribute_breakouts() {
+        let html = render_markdown("[x](https://example.com/\"onmouseover=\"alert(1))");
+        assert_inert(&html);
+    }
+}
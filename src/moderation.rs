@@ -0,0 +1,126 @@
+pub(crate) mod openai;
+pub(crate) mod rules;
+
+use dioxus_logger::tracing;
+use std::env;
+use std::time::Duration;
+use tokio::sync::OnceCell;
+
+use crate::moderation::openai::OpenAIModerator;
+use crate::moderation::rules::RuleClassifier;
+use crate::server::moderation::model::Verdict;
+
+static MODERATOR: OnceCell<Moderator> = OnceCell::const_new();
+
+#[derive(Debug, Clone)]
+pub struct ModerationConfig {
+    /// `none` to rely on the local rules alone, or `openai`.
+    pub provider: String,
+    pub base_url: String,
+    pub api_key: Option<String>,
+    pub model: String,
+    pub timeout: Duration,
+    pub blocked_terms: Vec<String>,
+    pub flagged_terms: Vec<String>,
+}
+
+impl ModerationConfig {
+    pub fn from_env() -> Self {
+        let terms = |name: &str| {
+            env::var(name)
+                .unwrap_or_default()
+                .split(',')
+                .map(|term| term.trim().to_string())
+                .filter(|term| !term.is_empty())
+                .collect()
+        };
+
+        Self {
+            provider: env::var("MODERATION_PROVIDER").unwrap_or_else(|_| "none".into()),
+            base_url: env::var("MODERATION_BASE_URL")
+                .unwrap_or_else(|_| "https://api.openai.com/v1".into())
+                .trim_end_matches('/')
+                .to_string(),
+            api_key: env::var("MODERATION_API_KEY")
+                .or_else(|_| env::var("LLM_API_KEY"))
+                .ok()
+                .filter(|key| !key.is_empty()),
+            model: env::var("MODERATION_MODEL").unwrap_or_else(|_| "omni-moderation-latest".into()),
+            timeout: Duration::from_secs(
+                env::var("MODERATION_TIMEOUT_SECS")
+                    .ok()
+                    .and_then(|secs| secs.parse().ok())
+                    .unwrap_or(10),
+            ),
+            blocked_terms: terms("MODERATION_BLOCKED_TERMS"),
+            flagged_terms: terms("MODERATION_FLAGGED_TERMS"),
+        }
+    }
+}
+
+/// Local rules, optionally backed by a provider's classifier. The stricter
+/// of the two verdicts wins.
+pub struct Moderator {
+    pub config: ModerationConfig,
+    rules: RuleClassifier,
+    provider: Option<OpenAIModerator>,
+}
+
+/// Verdicts for a batch of texts, in order, and how the provider call went
+/// so it can be metered: `None` when only the local rules ran.
+pub struct Classification {
+    pub verdicts: Vec<Verdict>,
+    pub provider_result: Option<Result<(), String>>,
+}
+
+impl Moderator {
+    pub fn new(config: ModerationConfig) -> Self {
+        let provider = match config.provider.as_str() {
+            "openai" => Some(OpenAIModerator::new(&config)),
+            _ => None,
+        };
+
+        Self {
+            rules: RuleClassifier::new(&config.blocked_terms, &config.flagged_terms),
+            provider,
+            config,
+        }
+    }
+
+    /// Classifies `texts`, sending them to the provider in batches. A
+    /// provider outage falls back to the local verdicts rather than holding
+    /// up every save.
+    pub async fn classify_many(&self, texts: &[&str]) -> Classification {
+        let mut verdicts: Vec<Verdict> =
+            texts.iter().map(|text| self.rules.classify(text)).collect();
+        let provider_result = match &self.provider {
+            Some(provider) if !texts.is_empty() => {
+                Some(match provider.classify_many(texts).await {
+                    Ok(provider_verdicts) => {
+                        for (verdict, provider_verdict) in
+                            verdicts.iter_mut().zip(provider_verdicts)
+                        {
+                            verdict.merge(provider_verdict);
+                        }
+                        Ok(())
+                    }
+                    Err(err) => {
+                        tracing::warn!("moderation provider failed: {err}");
+                        Err(err.to_string())
+                    }
+                })
+            }
+            _ => None,
+        };
+        Classification {
+            verdicts,
+            provider_result,
+        }
+    }
+}
+
+pub async fn get_moderator() -> &'static Moderator {
+    MODERATOR
+        .get_or_init(|| async { Moderator::new(ModerationConfig::from_env()) })
+        .await
+}
@@ -0,0 +1,113 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::llm::LlmError;
+use crate::moderation::ModerationConfig;
+use crate::server::moderation::model::Verdict;
+
+/// Categories severe enough to block instead of flag.
+const BLOCKED_CATEGORIES: [&str; 5] = [
+    "sexual/minors",
+    "hate/threatening",
+    "harassment/threatening",
+    "self-harm/instructions",
+    "illicit/violent",
+];
+
+/// The OpenAI moderation API, or any server implementing `/moderations`.
+pub struct OpenAIModerator {
+    http: reqwest::Client,
+    base_url: String,
+    api_key: Option<String>,
+    model: String,
+}
+
+/// Inputs sent in one request; longer batches are split.
+const BATCH_SIZE: usize = 32;
+
+#[derive(Serialize)]
+struct ModerationRequest<'a> {
+    model: &'a str,
+    input: &'a [&'a str],
+}
+
+#[derive(Deserialize)]
+struct ModerationResponse {
+    results: Vec<ModerationResult>,
+}
+
+#[derive(Deserialize)]
+struct ModerationResult {
+    #[serde(default)]
+    categories: HashMap<String, bool>,
+}
+
+impl OpenAIModerator {
+    pub fn new(config: &ModerationConfig) -> Self {
+        Self {
+            http: reqwest::Client::builder()
+                .connect_timeout(config.timeout)
+                .read_timeout(config.timeout)
+                .build()
+                .expect("failed to build moderation HTTP client"),
+            base_url: config.base_url.clone(),
+            api_key: config.api_key.clone(),
+            model: config.model.clone(),
+        }
+    }
+
+    /// One verdict per text, in order, with as few requests as the batch
+    /// size allows.
+    pub async fn classify_many(&self, texts: &[&str]) -> Result<Vec<Verdict>, LlmError> {
+        let mut verdicts = Vec::with_capacity(texts.len());
+        for batch in texts.chunks(BATCH_SIZE) {
+            let mut builder = self
+                .http
+                .post(format!("{}/moderations", self.base_url))
+                .json(&ModerationRequest {
+                    model: &self.model,
+                    input: batch,
+                });
+            if let Some(key) = &self.api_key {
+                builder = builder.bearer_auth(key);
+            }
+
+            let response = builder.send().await?;
+            let status = response.status();
+            if !status.is_success() {
+                let body = response.text().await.unwrap_or_default();
+                return Err(LlmError::Status(status.as_u16(), body));
+            }
+            let body: ModerationResponse = response.json().await?;
+            if body.results.len() != batch.len() {
+                return Err(LlmError::InvalidResponse(format!(
+                    "expected {} moderation results, got {}",
+                    batch.len(),
+                    body.results.len()
+                )));
+            }
+            verdicts.extend(body.results.into_iter().map(verdict_of));
+        }
+        Ok(verdicts)
+    }
+}
+
+fn verdict_of(result: ModerationResult) -> Verdict {
+    let mut verdict = Verdict::default();
+    let mut categories: Vec<String> = result
+        .categories
+        .into_iter()
+        .filter(|(_, flagged)| *flagged)
+        .map(|(category, _)| category)
+        .collect();
+    categories.sort();
+    for category in categories {
+        let reason = format!("Provider flagged {category}");
+        if BLOCKED_CATEGORIES.contains(&category.as_str()) {
+            verdict.merge(Verdict::block(reason));
+        } else {
+            verdict.merge(Verdict::flag(reason));
+        }
+    }
+    verdict
+}
@@ -0,0 +1,124 @@
+use crate::server::moderation::model::Verdict;
+
+const BLOCKED_TERMS: [&str; 3] = ["kill yourself", "kys", "go die"];
+const PROFANITY: [&str; 10] = [
+    "fuck", "fucking", "shit", "bitch", "bastard", "asshole", "cunt", "whore", "slut", "dickhead",
+];
+const SPAM_PHRASES: [&str; 9] = [
+    "click here",
+    "free money",
+    "guaranteed income",
+    "act now",
+    "risk free",
+    "make money fast",
+    "crypto giveaway",
+    "double your money",
+    "100% free",
+];
+const MAX_LINKS: usize = 2;
+const MAX_REPEATED_CHARS: usize = 5;
+/// Shouting is only judged once a text has this many letters.
+const SHOUTING_MIN_LETTERS: usize = 20;
+const SHOUTING_RATIO: f32 = 0.7;
+
+/// Word lists and heuristics. Terms match whole words, ignoring case,
+/// punctuation and common digit-for-letter swaps.
+pub struct RuleClassifier {
+    blocked: Vec<String>,
+    flagged: Vec<String>,
+}
+
+impl RuleClassifier {
+    /// Extends the built-in lists with the operator's own terms.
+    pub fn new(blocked: &[String], flagged: &[String]) -> Self {
+        let list = |builtin: &[&str], extra: &[String]| {
+            builtin
+                .iter()
+                .map(|term| term.to_string())
+                .chain(extra.iter().cloned())
+                .map(|term| normalize(&term))
+                .filter(|term| !term.trim().is_empty())
+                .collect()
+        };
+
+        Self {
+            blocked: list(&BLOCKED_TERMS, blocked),
+            flagged: list(&PROFANITY, flagged),
+        }
+    }
+
+    pub fn classify(&self, text: &str) -> Verdict {
+        let mut verdict = Verdict::default();
+        let normalized = normalize(text);
+        let has = |term: &String| normalized.contains(term.as_str());
+
+        for term in self.blocked.iter().filter(|term| has(term)) {
+            verdict.merge(Verdict::block(format!("Banned term \"{}\"", term.trim())));
+        }
+        for term in self.flagged.iter().filter(|term| has(term)) {
+            verdict.merge(Verdict::flag(format!(
+                "Inappropriate term \"{}\"",
+                term.trim()
+            )));
+        }
+        for phrase in SPAM_PHRASES.iter().filter(|phrase| has(&normalize(phrase))) {
+            verdict.merge(Verdict::flag(format!("Spam phrase \"{phrase}\"")));
+        }
+
+        let lower = text.to_lowercase();
+        let links = lower.matches("http://").count()
+            + lower.matches("https://").count()
+            + lower.matches("www.").count();
+        if links > MAX_LINKS {
+            verdict.merge(Verdict::flag(format!("{links} links")));
+        }
+
+        let letters: Vec<char> = text.chars().filter(|c| c.is_alphabetic()).collect();
+        let upper = letters.iter().filter(|c| c.is_uppercase()).count();
+        if letters.len() >= SHOUTING_MIN_LETTERS
+            && upper as f32 / letters.len() as f32 > SHOUTING_RATIO
+        {
+            verdict.merge(Verdict::flag("Mostly capital letters".into()));
+        }
+
+        let mut run = 1;
+        let mut longest = 1;
+        let chars: Vec<char> = text.chars().collect();
+        for pair in chars.windows(2) {
+            run = if pair[0] == pair[1] && !pair[0].is_whitespace() {
+                run + 1
+            } else {
+                1
+            };
+            longest = longest.max(run);
+        }
+        if longest > MAX_REPEATED_CHARS {
+            verdict.merge(Verdict::flag("Repeated characters".into()));
+        }
+
+        verdict
+    }
+}
+
+/// Lowercase words separated and surrounded by single spaces, so a term can
+/// be found as ` term `.
+fn normalize(text: &str) -> String {
+    let letters: String = text
+        .to_lowercase()
+        .chars()
+        .map(|c| match c {
+            '0' => 'o',
+            '1' => 'i',
+            '3' => 'e',
+            '4' | '@' => 'a',
+            '5' | '$' => 's',
+            '7' => 't',
+            c if c.is_alphanumeric() => c,
+            _ => ' ',
+        })
+        .collect();
+    format!(
+        " {} ",
+        letters.split_whitespace().collect::<Vec<_>>().join(" ")
+    )
+}
@@ -1,3 +1,4 @@
+pub(crate) mod admin;
 pub(crate) mod dashboard;
 pub(crate) mod home;
 pub(crate) mod login;
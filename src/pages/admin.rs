@@ -0,0 +1,92 @@
+use crate::components::admin::moderation::ModerationPanel;
+use crate::components::admin::stats::StatsPanel;
+use crate::components::admin::usage::UsagePanel;
+use crate::components::admin::user::UserPanel;
+use crate::components::admin::users::UsersPanel;
+use crate::components::dashboard::navbar::Navbar;
+use crate::router::Route;
+use crate::server::auth::controller::about_me;
+use crate::server::auth::model::Permission;
+use crate::theme::Theme;
+use dioxus::prelude::*;
+
+/// Checks the session and sends anyone who may not manage users back to the
+/// dashboard. Turns true once the current user is cleared to see the console.
+fn use_admin_access() -> Signal<bool> {
+    let mut allowed = use_signal(|| false);
+    let navigator = use_navigator();
+
+    use_effect(move || {
+        spawn(async move {
+            match about_me().await {
+                Ok(data) => {
+                    if data.data.user.role.can(Permission::ViewUsers) {
+                        allowed.set(true);
+                    } else {
+                        navigator.push("/dashboard");
+                    }
+                }
+                Err(_) => {
+                    navigator.push("/login");
+                }
+            }
+        });
+    });
+
+    allowed
+}
+
+#[component]
+pub fn AdminConsole() -> Element {
+    let theme = use_context::<Signal<Theme>>();
+    let dark_mode = theme() == Theme::Dark;
+    let allowed = use_admin_access();
+
+    rsx! {
+        div { class: format!("min-h-screen p-4 md:p-8 {}", if dark_mode { "bg-gray-900 text-white" } else { "bg-white text-gray-900" }),
+            Navbar {}
+
+            if allowed() {
+                div { class: "space-y-6",
+                    div { class: format!("p-4 shadow rounded-lg {}", if dark_mode { "bg-gray-800" } else { "bg-white" }),
+                        StatsPanel {}
+                    }
+                    div { class: format!("p-4 shadow rounded-lg {}", if dark_mode { "bg-gray-800" } else { "bg-white" }),
+                        UsagePanel {}
+                    }
+                    div { class: format!("p-4 shadow rounded-lg {}", if dark_mode { "bg-gray-800" } else { "bg-white" }),
+                        UsersPanel {}
+                    }
+                    div { class: format!("p-4 shadow rounded-lg {}", if dark_mode { "bg-gray-800" } else { "bg-white" }),
+                        ModerationPanel {}
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+pub fn AdminUser(id: String) -> Element {
+    let theme = use_context::<Signal<Theme>>();
+    let dark_mode = theme() == Theme::Dark;
+    let allowed = use_admin_access();
+
+    rsx! {
+        div { class: format!("min-h-screen p-4 md:p-8 {}", if dark_mode { "bg-gray-900 text-white" } else { "bg-white text-gray-900" }),
+            Navbar {}
+
+            Link {
+                to: Route::AdminConsole {},
+                class: "inline-block mb-4 text-blue-500 hover:underline",
+                "← Back to users"
+            }
+
+            if allowed() {
+                div { class: format!("p-4 shadow rounded-lg {}", if dark_mode { "bg-gray-800" } else { "bg-white" }),
+                    UserPanel { user_id: id }
+                }
+            }
+        }
+    }
+}
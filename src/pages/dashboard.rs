@@ -4,44 +4,34 @@ use crate::components::dashboard::ogs::create::CreateOGPanel;
 use crate::components::dashboard::ogs::list::OGsPanel;
 use crate::components::dashboard::ogs::read::ViewOGPanel;
 use crate::components::dashboard::profile::ProfilePagePanel;
+use crate::components::dashboard::prompts::PromptsPanel;
 use crate::components::dashboard::sidebar::Sidebar;
 use crate::components::dashboard::sidebar::Tab;
+use crate::components::dashboard::voices::VoicesPanel;
 use crate::server::auth::controller::about_me;
 use crate::theme::Theme;
 use dioxus::prelude::*;
-use gloo_storage::SessionStorage;
-use gloo_storage::Storage;
 
 #[component]
 pub fn Dashboard() -> Element {
     let active_tab = use_signal(|| Tab::OGs);
     let theme = use_context::<Signal<Theme>>();
     let dark_mode = theme() == Theme::Dark;
-    let mut user_token = use_signal(|| "".to_string());
     let navigator = use_navigator();
     let current_tab = match active_tab() {
-        Tab::OGs => rsx! { OGsPanel { user_token } },
-        Tab::CreateOG => rsx! { CreateOGPanel { user_token } },
-        Tab::ViewOG => rsx! { ViewOGPanel { og_id: "", user_token } },
+        Tab::OGs => rsx! { OGsPanel { } },
+        Tab::CreateOG => rsx! { CreateOGPanel { } },
+        Tab::ViewOG => rsx! { ViewOGPanel { og_id: "", } },
+        Tab::Prompts => rsx! { PromptsPanel {} },
+        Tab::Voices => rsx! { VoicesPanel {} },
         Tab::EditProfile => rsx! { ProfilePagePanel {} },
-        Tab::Chat => rsx! { ChatPanelPage { user_token, og_id: ""} },
+        Tab::Chat => rsx! { ChatPanelPage { og_id: ""} },
     };
 
     use_effect(move || {
         spawn(async move {
-            let token: String = SessionStorage::get("jwt").unwrap_or_default();
-            if token.is_empty() {
+            if about_me().await.is_err() {
                 navigator.push("/login");
-            } else {
-                match about_me(token.clone()).await {
-                    Ok(data) => {
-                        let _user = data.data.user;
-                        user_token.set(token.clone());
-                    }
-                    Err(_) => {
-                        navigator.push("/login");
-                    }
-                }
             }
         });
     });
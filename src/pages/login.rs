@@ -8,15 +8,8 @@ use crate::server::auth::response::LoginUserSchema;
 use crate::theme::Theme;
 use chrono::Duration;
 use dioxus::prelude::*;
-use gloo_storage::SessionStorage;
-use gloo_storage::Storage;
 use regex::Regex;
 
-fn extract_token(cookie_str: &str) -> Option<String> {
-    let re = Regex::new(r"token=([^;]+)").unwrap();
-    re.captures(cookie_str).map(|caps| caps[1].to_string())
-}
-
 #[component]
 pub fn Login() -> Element {
     let navigator = use_navigator();
@@ -42,17 +35,8 @@ pub fn Login() -> Element {
 
     use_effect(move || {
         spawn(async move {
-            let token: String = SessionStorage::get("jwt").unwrap_or_default();
-            if !token.is_empty() {
-                match about_me(token.clone()).await {
-                    Ok(data) => {
-                        let _user = data.data.user;
-                        navigator.push("/dashboard");
-                    }
-                    Err(e) => {
-                        error_message.set(Some(e.to_string()));
-                    }
-                }
+            if about_me().await.is_ok() {
+                navigator.push("/dashboard");
             }
         });
     });
@@ -76,59 +60,21 @@ pub fn Login() -> Element {
             let password = password_value.clone();
             async move {
                 match login_user(LoginUserSchema { email, password }).await {
-                    Ok(data) => match extract_token(&data.data.token) {
-                        Some(token) => match about_me(token.clone()).await {
-                            Ok(data) => {
-                                let _user = data.data.user;
-                                SessionStorage::set("jwt", token.clone())
-                                    .expect("Failed to store JWT in session storage");
-                                navigator.push("/dashboard");
-                                toasts_manager.set(
-                                    toasts_manager()
-                                        .add_toast(
-                                            "Success".into(),
-                                            "Welcome back!".into(),
-                                            ToastType::Success,
-                                            Some(Duration::seconds(5)),
-                                        )
-                                        .clone(),
-                                );
-                                loading.set(false);
-                            }
-                            Err(e) => {
-                                let msg = e.to_string();
-                                let error_message = msg
-                                    .splitn(2, "error running server function:")
-                                    .nth(1)
-                                    .unwrap_or("")
-                                    .trim();
-                                toasts_manager.set(
-                                    toasts_manager()
-                                        .add_toast(
-                                            "Error".into(),
-                                            error_message.into(),
-                                            ToastType::Error,
-                                            Some(Duration::seconds(5)),
-                                        )
-                                        .clone(),
-                                );
-                                loading.set(false);
-                            }
-                        },
-                        None => {
-                            toasts_manager.set(
-                                toasts_manager()
-                                    .add_toast(
-                                        "Error".into(),
-                                        "Token not found".into(),
-                                        ToastType::Error,
-                                        Some(Duration::seconds(5)),
-                                    )
-                                    .clone(),
-                            );
-                            loading.set(false);
-                        }
-                    },
+                    Ok(data) => {
+                        let _user = data.data.user;
+                        navigator.push("/dashboard");
+                        toasts_manager.set(
+                            toasts_manager()
+                                .add_toast(
+                                    "Success".into(),
+                                    "Welcome back!".into(),
+                                    ToastType::Success,
+                                    Some(Duration::seconds(5)),
+                                )
+                                .clone(),
+                        );
+                        loading.set(false);
+                    }
                     Err(e) => {
                         let msg = e.to_string();
                         let error_message = msg
@@ -9,8 +9,6 @@ use crate::server::auth::response::RegisterUserSchema;
 use crate::theme::Theme;
 use chrono::Duration;
 use dioxus::prelude::*;
-use gloo_storage::SessionStorage;
-use gloo_storage::Storage;
 use regex::Regex;
 
 #[component]
@@ -42,17 +40,8 @@ pub fn Register() -> Element {
 
     use_effect(move || {
         spawn(async move {
-            let token: String = SessionStorage::get("jwt").unwrap_or_default();
-            if !token.is_empty() {
-                match about_me(token.clone()).await {
-                    Ok(data) => {
-                        let _user = data.data.user;
-                        navigator.push("/dashboard");
-                    }
-                    Err(e) => {
-                        error_message.set(Some(e.to_string()));
-                    }
-                }
+            if about_me().await.is_ok() {
+                navigator.push("/dashboard");
             }
         });
     });
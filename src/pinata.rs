@@ -1,6 +1,9 @@
-use pinata_sdk::PinataApi;
+use bson::oid::ObjectId;
+use pinata_sdk::{PinByFile, PinataApi};
 use tokio::sync::{Mutex, OnceCell};
 
+pub const GATEWAY_URL: &str = "https://gateway.pinata.cloud/ipfs/";
+
 static PINATA: OnceCell<Mutex<PinataApi>> = OnceCell::const_new();
 
 async fn init_pinata() -> &'static Mutex<PinataApi> {
@@ -15,6 +18,63 @@ async fn init_pinata() -> &'static Mutex<PinataApi> {
         .await
 }
 
+/// Whether `url` points at a single file on our gateway, the only kind of
+/// asset URL stored on an OG.
+pub fn is_gateway_url(url: &str) -> bool {
+    url.strip_prefix(GATEWAY_URL)
+        .is_some_and(|hash| !hash.is_empty() && hash.chars().all(|c| c.is_ascii_alphanumeric()))
+}
+
+/// The IPFS hash of a gateway URL, or `None` for any other URL.
+pub fn gateway_hash(url: &str) -> Option<&str> {
+    is_gateway_url(url).then(|| &url[GATEWAY_URL.len()..])
+}
+
 pub async fn get_pinata_client() -> &'static Mutex<PinataApi> {
     init_pinata().await
 }
+
+/// Pins `bytes` as a file with the given extension and returns its gateway
+/// URL.
+pub async fn pin_bytes(bytes: &[u8], extension: &str) -> Result<String, String> {
+    let path = std::env::temp_dir().join(format!("{}.{extension}", ObjectId::new().to_hex()));
+    std::fs::write(&path, bytes).map_err(|err| format!("Failed to write asset: {err}"))?;
+
+    let result = get_pinata_client()
+        .await
+        .lock()
+        .await
+        .pin_file(PinByFile::new(path.to_string_lossy()))
+        .await;
+    let _ = std::fs::remove_file(&path);
+
+    let pinned = result.map_err(|err| format!("Pinata Error: {:?}", err))?;
+    Ok(format!("{}{}", GATEWAY_URL, pinned.ipfs_hash))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{gateway_hash, is_gateway_url, GATEWAY_URL};
+
+    #[test]
+    fn accepts_only_files_on_the_gateway() {
+        assert!(is_gateway_url(&format!(
+            "{GATEWAY_URL}QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG"
+        )));
+        assert!(!is_gateway_url(GATEWAY_URL));
+        assert!(!is_gateway_url(&format!("{GATEWAY_URL}Qm1/../../admin")));
+        assert!(!is_gateway_url(&format!("{GATEWAY_URL}Qm1?redirect=x")));
+        assert!(!is_gateway_url("http://169.254.169.254/latest/meta-data"));
+        assert!(!is_gateway_url(
+            "https://gateway.pinata.cloud.evil.com/ipfs/Qm1"
+        ));
+    }
+
+    #[test]
+    fn extracts_the_hash_of_gateway_urls_only() {
+        assert_eq!(gateway_hash(&format!("{GATEWAY_URL}QmAbc")), Some("QmAbc"));
+        assert_eq!(gateway_hash(GATEWAY_URL), None);
+        assert_eq!(gateway_hash(&format!("{GATEWAY_URL}Qm1/../x")), None);
+        assert_eq!(gateway_hash("https://example.com/QmAbc"), None);
+    }
+}
@@ -2,6 +2,8 @@
 
 use crate::components::navbar::HomeNavBar;
 use crate::components::navbar::LoginNavBar;
+use crate::pages::admin::AdminConsole;
+use crate::pages::admin::AdminUser;
 use crate::pages::dashboard::Dashboard;
 use crate::pages::home::Home;
 use crate::pages::login::Login;
@@ -28,4 +30,8 @@ pub enum Route {
     EditOG { id: String },
     #[route("/dashboard")]
     Dashboard {},
+    #[route("/admin")]
+    AdminConsole {},
+    #[route("/admin/users/:id")]
+    AdminUser { id: String },
 }
@@ -1,4 +1,13 @@
+pub(crate) mod account;
+pub(crate) mod admin;
+pub(crate) mod ai;
+pub(crate) mod asset;
 pub(crate) mod auth;
 pub(crate) mod common;
 pub(crate) mod conversation;
+pub(crate) mod moderation;
 pub(crate) mod og;
+pub(crate) mod search;
+pub(crate) mod template;
+pub(crate) mod usage;
+pub(crate) mod voice;
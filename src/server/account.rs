@@ -0,0 +1,3 @@
+pub(crate) mod controller;
+pub(crate) mod request;
+pub(crate) mod response;
@@ -0,0 +1,361 @@
+#![allow(unused)]
+#![allow(dead_code)]
+
+use crate::server::account::request::DeleteAccountRequest;
+use crate::server::account::response::{
+    DeletionStatusResponse, ExportDataResponse, ExportedConversation,
+};
+use crate::server::asset::model::PinnedAsset;
+use crate::server::auth::model::{Permission, User};
+use crate::server::common::response::SuccessResponse;
+use crate::server::conversation::model::{Conversation, Message};
+use crate::server::moderation::model::ModerationCase;
+use crate::server::og::model::OG;
+use crate::server::search::model::OGEmbedding;
+use crate::server::template::model::PromptTemplate;
+use crate::server::usage::model::AIUsage;
+use crate::server::voice::model::BrandVoice;
+use bson::doc;
+use bson::oid::ObjectId;
+use chrono::prelude::*;
+use chrono::Duration;
+use dioxus::prelude::*;
+use dioxus_logger::tracing;
+use futures_util::TryStreamExt;
+
+#[cfg(feature = "server")]
+use {
+    crate::db::get_client,
+    crate::pinata::{gateway_hash, is_gateway_url},
+    crate::server::asset::controller::release_assets,
+    crate::server::auth::guard::authorize,
+    argon2::{Argon2, PasswordHash, PasswordVerifier},
+    base64::{engine::general_purpose::STANDARD, Engine as _},
+    std::collections::BTreeSet,
+    std::io::{Cursor, Write},
+    zip::write::SimpleFileOptions,
+    zip::{CompressionMethod, ZipWriter},
+};
+
+const DEFAULT_GRACE_DAYS: i64 = 30;
+const IMAGE_TIMEOUT_SECS: u64 = 15;
+
+#[server]
+pub async fn export_account_data() -> Result<SuccessResponse<ExportDataResponse>, ServerFnError> {
+    let mut user = authorize(Permission::ManageOwnContent).await?;
+    user.password = String::new();
+
+    let client = get_client().await;
+    let db =
+        client.database(&std::env::var("MONGODB_DB_NAME").expect("MONGODB_DB_NAME must be set."));
+    let og_collection = db.collection::<OG>("ogs");
+    let conversation_collection = db.collection::<Conversation>("conversations");
+    let messages_collection = db.collection::<Message>("messages");
+    let usage_collection = db.collection::<AIUsage>("ai_usage");
+    let template_collection = db.collection::<PromptTemplate>("prompt_templates");
+    let voice_collection = db.collection::<BrandVoice>("brand_voices");
+    let embedding_collection = db.collection::<OGEmbedding>("og_embeddings");
+    let case_collection = db.collection::<ModerationCase>("moderation_cases");
+    let asset_collection = db.collection::<PinnedAsset>("pinned_assets");
+
+    let ogs: Vec<OG> = og_collection
+        .find(doc! { "user": user.id })
+        .await?
+        .try_collect()
+        .await?;
+    let usage: Vec<AIUsage> = usage_collection
+        .find(doc! { "user": user.id })
+        .sort(doc! { "createdAt": 1 })
+        .await?
+        .try_collect()
+        .await?;
+    let templates: Vec<PromptTemplate> = template_collection
+        .find(doc! { "user": user.id })
+        .await?
+        .try_collect()
+        .await?;
+    let voices: Vec<BrandVoice> = voice_collection
+        .find(doc! { "user": user.id })
+        .await?
+        .try_collect()
+        .await?;
+    let embeddings: Vec<OGEmbedding> = embedding_collection
+        .find(doc! { "user": user.id })
+        .await?
+        .try_collect()
+        .await?;
+    let cases: Vec<ModerationCase> = case_collection
+        .find(doc! { "user": user.id })
+        .sort(doc! { "createdAt": 1 })
+        .await?
+        .try_collect()
+        .await?;
+    let assets: Vec<PinnedAsset> = asset_collection
+        .find(doc! { "user": user.id })
+        .sort(doc! { "createdAt": 1 })
+        .await?
+        .try_collect()
+        .await?;
+    let conversations: Vec<Conversation> = conversation_collection
+        .find(doc! { "user": user.id })
+        .await?
+        .try_collect()
+        .await?;
+
+    let mut exported_conversations = Vec::with_capacity(conversations.len());
+    for conversation in conversations {
+        let messages: Vec<Message> = messages_collection
+            .find(doc! { "conversation": conversation.id })
+            .sort(doc! { "timestamp": 1 })
+            .await?
+            .try_collect()
+            .await?;
+        exported_conversations.push(ExportedConversation {
+            conversation,
+            messages,
+        });
+    }
+
+    let http = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(IMAGE_TIMEOUT_SECS))
+        .redirect(reqwest::redirect::Policy::none())
+        .build()?;
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    let mut archive = ZipWriter::new(Cursor::new(Vec::new()));
+
+    archive.start_file("profile.json", options)?;
+    archive.write_all(&serde_json::to_vec_pretty(&user)?)?;
+    archive.start_file("ogs.json", options)?;
+    archive.write_all(&serde_json::to_vec_pretty(&ogs)?)?;
+    archive.start_file("conversations.json", options)?;
+    archive.write_all(&serde_json::to_vec_pretty(&exported_conversations)?)?;
+    archive.start_file("ai_usage.json", options)?;
+    archive.write_all(&serde_json::to_vec_pretty(&usage)?)?;
+    archive.start_file("prompt_templates.json", options)?;
+    archive.write_all(&serde_json::to_vec_pretty(&templates)?)?;
+    archive.start_file("brand_voices.json", options)?;
+    archive.write_all(&serde_json::to_vec_pretty(&voices)?)?;
+    archive.start_file("og_embeddings.json", options)?;
+    archive.write_all(&serde_json::to_vec_pretty(&embeddings)?)?;
+    archive.start_file("moderation_cases.json", options)?;
+    archive.write_all(&serde_json::to_vec_pretty(&cases)?)?;
+    archive.start_file("pinned_assets.json", options)?;
+    archive.write_all(&serde_json::to_vec_pretty(&assets)?)?;
+
+    // Only our own pinned images are downloaded; the URLs are user supplied,
+    // so anything else is listed as missing instead of fetched.
+    let mut missing_images = Vec::new();
+    for og in ogs.iter() {
+        let images = [
+            (&og.image_url, og.id.to_hex()),
+            (&og.background_url, format!("{}-background", og.id.to_hex())),
+        ];
+        for (url, name) in images.into_iter().filter(|(url, _)| !url.is_empty()) {
+            match fetch_pinned_image(&http, url).await {
+                Some((bytes, extension)) => {
+                    archive.start_file(format!("images/{name}.{extension}"), options)?;
+                    archive.write_all(&bytes)?;
+                }
+                None => missing_images.push(url.clone()),
+            }
+        }
+    }
+    if !missing_images.is_empty() {
+        archive.start_file("missing_images.json", options)?;
+        archive.write_all(&serde_json::to_vec_pretty(&missing_images)?)?;
+    }
+
+    let bytes = archive.finish()?.into_inner();
+
+    Ok(SuccessResponse {
+        status: "success".into(),
+        data: ExportDataResponse {
+            file_name: format!("nano-og-export-{}.zip", Utc::now().format("%Y%m%d")),
+            archive: STANDARD.encode(bytes),
+        },
+    })
+}
+
+/// Downloads an image pinned through our Pinata gateway, with the file
+/// extension of its content type. Other URLs are never requested.
+#[cfg(feature = "server")]
+async fn fetch_pinned_image(http: &reqwest::Client, url: &str) -> Option<(Vec<u8>, &'static str)> {
+    if !is_gateway_url(url) {
+        return None;
+    }
+    let response = http.get(url).send().await.ok()?;
+    if !response.status().is_success() {
+        return None;
+    }
+    let extension = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(image_extension)
+        .unwrap_or("bin");
+    Some((response.bytes().await.ok()?.to_vec(), extension))
+}
+
+/// Maps an image content type to the file extension used in the archive.
+fn image_extension(content_type: &str) -> &'static str {
+    let mime = content_type.split(';').next().unwrap_or_default().trim();
+    match mime.to_ascii_lowercase().as_str() {
+        "image/png" => "png",
+        "image/jpeg" | "image/jpg" => "jpg",
+        "image/gif" => "gif",
+        "image/webp" => "webp",
+        "image/svg+xml" => "svg",
+        "image/avif" => "avif",
+        _ => "bin",
+    }
+}
+
+#[server]
+pub async fn request_account_deletion(
+    req: DeleteAccountRequest,
+) -> Result<SuccessResponse<DeletionStatusResponse>, ServerFnError> {
+    let user = authorize(Permission::ManageOwnContent).await?;
+
+    let parsed_hash = PasswordHash::new(&user.password)
+        .map_err(|_| ServerFnError::new("Password verification error"))?;
+    if Argon2::default()
+        .verify_password(req.password.as_bytes(), &parsed_hash)
+        .is_err()
+    {
+        return Err(ServerFnError::new("Password is incorrect"));
+    }
+
+    let grace_days = std::env::var("ACCOUNT_DELETION_GRACE_DAYS")
+        .ok()
+        .and_then(|days| days.parse().ok())
+        .unwrap_or(DEFAULT_GRACE_DAYS);
+    let scheduled_at = Utc::now() + Duration::days(grace_days);
+
+    set_deletion_schedule(user.id, Some(scheduled_at)).await?;
+
+    Ok(SuccessResponse {
+        status: "success".into(),
+        data: DeletionStatusResponse {
+            deletion_scheduled_at: Some(scheduled_at),
+        },
+    })
+}
+
+#[server]
+pub async fn cancel_account_deletion(
+) -> Result<SuccessResponse<DeletionStatusResponse>, ServerFnError> {
+    let user = authorize(Permission::ManageOwnContent).await?;
+
+    set_deletion_schedule(user.id, None).await?;
+
+    Ok(SuccessResponse {
+        status: "success".into(),
+        data: DeletionStatusResponse {
+            deletion_scheduled_at: None,
+        },
+    })
+}
+
+#[cfg(feature = "server")]
+async fn set_deletion_schedule(
+    user_id: ObjectId,
+    scheduled_at: Option<DateTime<Utc>>,
+) -> Result<(), ServerFnError> {
+    let client = get_client().await;
+    let db =
+        client.database(&std::env::var("MONGODB_DB_NAME").expect("MONGODB_DB_NAME must be set."));
+    let user_collection = db.collection::<User>("users");
+
+    let update = match scheduled_at {
+        Some(at) => doc! { "$set": { "deletionScheduledAt": at, "updatedAt": Utc::now() } },
+        None => doc! {
+            "$unset": { "deletionScheduledAt": "" },
+            "$set": { "updatedAt": Utc::now() },
+        },
+    };
+    user_collection
+        .update_one(doc! { "_id": user_id }, update)
+        .await?;
+
+    Ok(())
+}
+
+/// Permanently removes every account whose grace period has ended, together
+/// with its OGs, conversations, messages, pinned images and backgrounds, AI
+/// usage records, personal prompt templates, brand voices, OG embeddings and
+/// moderation cases. Images another user still relies on stay pinned. Returns the number of accounts deleted.
+#[cfg(feature = "server")]
+pub async fn purge_due_accounts() -> Result<u64, ServerFnError> {
+    let client = get_client().await;
+    let db =
+        client.database(&std::env::var("MONGODB_DB_NAME").expect("MONGODB_DB_NAME must be set."));
+    let user_collection = db.collection::<User>("users");
+    let og_collection = db.collection::<OG>("ogs");
+    let conversation_collection = db.collection::<Conversation>("conversations");
+    let messages_collection = db.collection::<Message>("messages");
+    let usage_collection = db.collection::<AIUsage>("ai_usage");
+    let template_collection = db.collection::<PromptTemplate>("prompt_templates");
+    let voice_collection = db.collection::<BrandVoice>("brand_voices");
+    let embedding_collection = db.collection::<OGEmbedding>("og_embeddings");
+    let case_collection = db.collection::<ModerationCase>("moderation_cases");
+
+    let due: Vec<User> = user_collection
+        .find(doc! { "deletionScheduledAt": { "$lte": Utc::now() } })
+        .await?
+        .try_collect()
+        .await?;
+
+    for user in due.iter() {
+        let ogs: Vec<OG> = og_collection
+            .find(doc! { "user": user.id })
+            .await?
+            .try_collect()
+            .await?;
+        // Hashes from before pins were recorded per user are only found
+        // through the OGs.
+        let hashes: BTreeSet<String> = ogs
+            .iter()
+            .flat_map(|og| [&og.image_url, &og.background_url])
+            .filter_map(|url| gateway_hash(url))
+            .map(str::to_string)
+            .collect();
+
+        let conversation_ids: Vec<ObjectId> = conversation_collection
+            .find(doc! { "user": user.id })
+            .await?
+            .try_collect::<Vec<Conversation>>()
+            .await?
+            .into_iter()
+            .map(|conversation| conversation.id)
+            .collect();
+
+        messages_collection
+            .delete_many(doc! { "conversation": { "$in": &conversation_ids } })
+            .await?;
+        conversation_collection
+            .delete_many(doc! { "user": user.id })
+            .await?;
+        og_collection.delete_many(doc! { "user": user.id }).await?;
+        release_assets(user.id, hashes).await?;
+        usage_collection
+            .delete_many(doc! { "user": user.id })
+            .await?;
+        template_collection
+            .delete_many(doc! { "user": user.id })
+            .await?;
+        voice_collection
+            .delete_many(doc! { "user": user.id })
+            .await?;
+        embedding_collection
+            .delete_many(doc! { "user": user.id })
+            .await?;
+        case_collection
+            .delete_many(doc! { "user": user.id })
+            .await?;
+        user_collection.delete_one(doc! { "_id": user.id }).await?;
+
+        tracing::info!("deleted account {}", user.id);
+    }
+
+    Ok(due.len() as u64)
+}
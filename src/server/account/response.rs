@@ -0,0 +1,21 @@
+use crate::server::conversation::model::{Conversation, Message};
+use chrono::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ExportDataResponse {
+    pub file_name: String,
+    /// Base64-encoded zip archive.
+    pub archive: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ExportedConversation {
+    pub conversation: Conversation,
+    pub messages: Vec<Message>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DeletionStatusResponse {
+    pub deletion_scheduled_at: Option<DateTime<Utc>>,
+}
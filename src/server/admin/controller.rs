@@ -0,0 +1,280 @@
+#![allow(unused)]
+#![allow(dead_code)]
+
+use crate::server::admin::model::StatsSnapshot;
+use crate::server::admin::request::{
+    ChangeRoleRequest, ListUsersRequest, ResetVerificationRequest, SetSuspendedRequest,
+    StatsHistoryRequest, UserDetailRequest, UserMessagesRequest,
+};
+use crate::server::admin::response::{StatsHistoryResponse, UserDetailResponse, UsersPageResponse};
+use crate::server::auth::model::{Permission, User};
+use crate::server::common::response::SuccessResponse;
+use crate::server::conversation::model::{Conversation, Message};
+use crate::server::og::model::OG;
+use bson::doc;
+use bson::oid::ObjectId;
+use chrono::prelude::*;
+use chrono::Duration;
+use dioxus::prelude::*;
+use futures_util::TryStreamExt;
+
+#[cfg(feature = "server")]
+use {
+    crate::db::get_client, crate::server::auth::controller::overview_counts,
+    crate::server::auth::guard::authorize,
+};
+
+const MAX_PER_PAGE: u64 = 100;
+const MAX_HISTORY_DAYS: u32 = 365;
+
+fn redact(mut user: User) -> User {
+    user.password = String::new();
+    user
+}
+
+#[server]
+pub async fn list_users(
+    req: ListUsersRequest,
+) -> Result<SuccessResponse<UsersPageResponse>, ServerFnError> {
+    authorize(Permission::ViewUsers).await?;
+
+    let client = get_client().await;
+    let db =
+        client.database(&std::env::var("MONGODB_DB_NAME").expect("MONGODB_DB_NAME must be set."));
+    let user_collection = db.collection::<User>("users");
+
+    let filter = if req.query.trim().is_empty() {
+        doc! {}
+    } else {
+        let pattern = regex::escape(req.query.trim());
+        doc! { "$or": [
+            { "name": { "$regex": &pattern, "$options": "i" } },
+            { "email": { "$regex": &pattern, "$options": "i" } },
+        ]}
+    };
+
+    let per_page = req.per_page.clamp(1, MAX_PER_PAGE);
+    let skip = req
+        .page
+        .checked_mul(per_page)
+        .ok_or(ServerFnError::new("Invalid page"))?;
+    let total = user_collection.count_documents(filter.clone()).await?;
+    let users: Vec<User> = user_collection
+        .find(filter)
+        .sort(doc! { "createdAt": -1 })
+        .skip(skip)
+        .limit(per_page as i64)
+        .await?
+        .try_collect()
+        .await?;
+
+    Ok(SuccessResponse {
+        status: "success".into(),
+        data: UsersPageResponse {
+            users: users.into_iter().map(redact).collect(),
+            total,
+            page: req.page,
+            per_page,
+        },
+    })
+}
+
+#[server]
+pub async fn get_user_detail(
+    req: UserDetailRequest,
+) -> Result<SuccessResponse<UserDetailResponse>, ServerFnError> {
+    authorize(Permission::ViewUsers).await?;
+
+    let client = get_client().await;
+    let db =
+        client.database(&std::env::var("MONGODB_DB_NAME").expect("MONGODB_DB_NAME must be set."));
+    let user_collection = db.collection::<User>("users");
+    let og_collection = db.collection::<OG>("ogs");
+    let conversation_collection = db.collection::<Conversation>("conversations");
+
+    let user_id =
+        ObjectId::parse_str(&req.user_id).map_err(|_| ServerFnError::new("Invalid user ID"))?;
+
+    let user = user_collection
+        .find_one(doc! { "_id": user_id })
+        .await?
+        .ok_or(ServerFnError::new("User not found"))?;
+    let ogs: Vec<OG> = og_collection
+        .find(doc! { "user": user_id })
+        .sort(doc! { "createdAt": -1 })
+        .await?
+        .try_collect()
+        .await?;
+    let conversations: Vec<Conversation> = conversation_collection
+        .find(doc! { "user": user_id })
+        .sort(doc! { "updatedAt": -1 })
+        .await?
+        .try_collect()
+        .await?;
+
+    Ok(SuccessResponse {
+        status: "success".into(),
+        data: UserDetailResponse {
+            user: redact(user),
+            ogs,
+            conversations,
+        },
+    })
+}
+
+#[server]
+pub async fn get_user_messages(
+    req: UserMessagesRequest,
+) -> Result<SuccessResponse<Vec<Message>>, ServerFnError> {
+    authorize(Permission::ViewUsers).await?;
+
+    let client = get_client().await;
+    let db =
+        client.database(&std::env::var("MONGODB_DB_NAME").expect("MONGODB_DB_NAME must be set."));
+    let messages_collection = db.collection::<Message>("messages");
+
+    let conversation_id = ObjectId::parse_str(&req.conversation_id)
+        .map_err(|_| ServerFnError::new("Invalid conversation ID"))?;
+
+    let messages: Vec<Message> = messages_collection
+        .find(doc! { "conversation": conversation_id })
+        .sort(doc! { "timestamp": 1 })
+        .await?
+        .try_collect()
+        .await?;
+
+    Ok(SuccessResponse {
+        status: "success".into(),
+        data: messages,
+    })
+}
+
+#[server]
+pub async fn set_user_suspended(
+    req: SetSuspendedRequest,
+) -> Result<SuccessResponse<User>, ServerFnError> {
+    let admin = authorize(Permission::ManageUsers).await?;
+    let user_id =
+        ObjectId::parse_str(&req.user_id).map_err(|_| ServerFnError::new("Invalid user ID"))?;
+    if user_id == admin.id {
+        return Err(ServerFnError::new("You cannot suspend your own account"));
+    }
+
+    let user = update_user(user_id, doc! { "suspended": req.suspended }).await?;
+
+    Ok(SuccessResponse {
+        status: "success".into(),
+        data: user,
+    })
+}
+
+#[server]
+pub async fn change_user_role(
+    req: ChangeRoleRequest,
+) -> Result<SuccessResponse<User>, ServerFnError> {
+    let admin = authorize(Permission::ManageUsers).await?;
+    let user_id =
+        ObjectId::parse_str(&req.user_id).map_err(|_| ServerFnError::new("Invalid user ID"))?;
+    if user_id == admin.id {
+        return Err(ServerFnError::new("You cannot change your own role"));
+    }
+
+    let role = bson::to_bson(&req.role).map_err(|_| ServerFnError::new("Invalid role"))?;
+    let user = update_user(user_id, doc! { "role": role }).await?;
+
+    Ok(SuccessResponse {
+        status: "success".into(),
+        data: user,
+    })
+}
+
+#[server]
+pub async fn reset_user_verification(
+    req: ResetVerificationRequest,
+) -> Result<SuccessResponse<User>, ServerFnError> {
+    authorize(Permission::ManageUsers).await?;
+    let user_id =
+        ObjectId::parse_str(&req.user_id).map_err(|_| ServerFnError::new("Invalid user ID"))?;
+
+    let user = update_user(user_id, doc! { "verified": false }).await?;
+
+    Ok(SuccessResponse {
+        status: "success".into(),
+        data: user,
+    })
+}
+
+#[cfg(feature = "server")]
+async fn update_user(
+    user_id: ObjectId,
+    mut updates: bson::Document,
+) -> Result<User, ServerFnError> {
+    let client = get_client().await;
+    let db =
+        client.database(&std::env::var("MONGODB_DB_NAME").expect("MONGODB_DB_NAME must be set."));
+    let user_collection = db.collection::<User>("users");
+
+    updates.insert("updatedAt", Utc::now());
+    let user = user_collection
+        .find_one_and_update(doc! { "_id": user_id }, doc! { "$set": updates })
+        .return_document(mongodb::options::ReturnDocument::After)
+        .await?
+        .ok_or(ServerFnError::new("User not found"))?;
+
+    Ok(redact(user))
+}
+
+#[server]
+pub async fn stats_history(
+    req: StatsHistoryRequest,
+) -> Result<SuccessResponse<StatsHistoryResponse>, ServerFnError> {
+    authorize(Permission::ViewStats).await?;
+
+    let client = get_client().await;
+    let db =
+        client.database(&std::env::var("MONGODB_DB_NAME").expect("MONGODB_DB_NAME must be set."));
+    let snapshot_collection = db.collection::<StatsSnapshot>("stats_snapshots");
+
+    let current = overview_counts().await?;
+    let now = Utc::now();
+
+    let since = now - Duration::days(req.days.min(MAX_HISTORY_DAYS) as i64);
+    let history: Vec<StatsSnapshot> = snapshot_collection
+        .find(doc! { "takenAt": { "$gte": since } })
+        .sort(doc! { "_id": 1 })
+        .await?
+        .try_collect()
+        .await?;
+
+    Ok(SuccessResponse {
+        status: "success".into(),
+        data: StatsHistoryResponse { current, history },
+    })
+}
+
+/// Upserts today's copy of the dashboard counters. Run on a schedule by
+/// [`crate::jobs::spawn_stats_snapshots`] so the history has one entry per
+/// day whether or not an admin looked at it.
+#[cfg(feature = "server")]
+pub async fn record_stats_snapshot() -> Result<(), ServerFnError> {
+    let client = get_client().await;
+    let db =
+        client.database(&std::env::var("MONGODB_DB_NAME").expect("MONGODB_DB_NAME must be set."));
+    let snapshot_collection = db.collection::<StatsSnapshot>("stats_snapshots");
+
+    let current = overview_counts().await?;
+    let now = Utc::now();
+    let today = StatsSnapshot {
+        day: now.format("%Y-%m-%d").to_string(),
+        users: current.users,
+        ogs: current.ogs,
+        staff_users: current.staff_users,
+        taken_at: now,
+    };
+    snapshot_collection
+        .replace_one(doc! { "_id": &today.day }, today)
+        .upsert(true)
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,18 @@
+#![allow(non_snake_case)]
+
+use bson::serde_helpers::chrono_datetime_as_bson_datetime;
+use chrono::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Daily copy of the dashboard counters, keyed by `YYYY-MM-DD`.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct StatsSnapshot {
+    #[serde(rename = "_id")]
+    pub day: String,
+    pub users: u64,
+    pub ogs: u64,
+    #[serde(default)]
+    pub staff_users: u64,
+    #[serde(with = "chrono_datetime_as_bson_datetime", rename = "takenAt")]
+    pub taken_at: DateTime<Utc>,
+}
@@ -0,0 +1,41 @@
+use crate::server::auth::model::Role;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ListUsersRequest {
+    pub query: String,
+    pub page: u64,
+    pub per_page: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct UserDetailRequest {
+    pub user_id: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct UserMessagesRequest {
+    pub conversation_id: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SetSuspendedRequest {
+    pub user_id: String,
+    pub suspended: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ChangeRoleRequest {
+    pub user_id: String,
+    pub role: Role,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ResetVerificationRequest {
+    pub user_id: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct StatsHistoryRequest {
+    pub days: u32,
+}
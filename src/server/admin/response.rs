@@ -0,0 +1,27 @@
+use crate::server::admin::model::StatsSnapshot;
+use crate::server::auth::model::User;
+use crate::server::auth::response::DashboardResponse;
+use crate::server::conversation::model::Conversation;
+use crate::server::og::model::OG;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct UsersPageResponse {
+    pub users: Vec<User>,
+    pub total: u64,
+    pub page: u64,
+    pub per_page: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct UserDetailResponse {
+    pub user: User,
+    pub ogs: Vec<OG>,
+    pub conversations: Vec<Conversation>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct StatsHistoryResponse {
+    pub current: DashboardResponse,
+    pub history: Vec<StatsSnapshot>,
+}
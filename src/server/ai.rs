@@ -0,0 +1,11 @@
+pub(crate) mod controller;
+#[cfg(feature = "server")]
+pub(crate) mod page;
+pub(crate) mod prompt;
+pub(crate) mod rank;
+pub(crate) mod request;
+pub(crate) mod response;
+#[cfg(feature = "server")]
+pub(crate) mod tools;
+#[cfg(feature = "server")]
+pub(crate) mod translate;
@@ -0,0 +1,573 @@
+#![allow(unused)]
+#![allow(dead_code)]
+
+use crate::server::ai::prompt::{
+    context_prompt, field_name, keywords, system_prompt, template_prompt, PageSummary,
+    SUGGESTION_COUNT,
+};
+use crate::server::ai::rank::{parse_candidates, rank};
+use crate::server::ai::request::{
+    AltTextRequest, BackgroundRequest, LocalizeOGRequest, SuggestRequest, SuggestionField,
+};
+use crate::server::ai::response::Suggestion;
+use crate::server::auth::model::Permission;
+use crate::server::common::response::SuccessResponse;
+use crate::server::og::model::OG;
+use bson::doc;
+use bson::oid::ObjectId;
+use chrono::prelude::*;
+use dioxus::prelude::*;
+
+#[cfg(feature = "server")]
+use {
+    crate::db::get_client,
+    crate::imagegen::GeneratedImage,
+    crate::imagegen::{get_image_gen, BACKGROUND_SIZE},
+    crate::llm::{estimate_tokens, get_llm, ChatMessage},
+    crate::pinata::{is_gateway_url, pin_bytes},
+    crate::server::ai::page::fetch_page,
+    crate::server::ai::prompt::{
+        alt_text_prompt, background_prompt, card_layout_prompt, translation_prompt,
+        translation_source, voice_prompt,
+    },
+    crate::server::ai::translate::{normalize_locale, parse_translation, MAX_LOCALES},
+    crate::server::asset::controller::record_asset,
+    crate::server::auth::guard::authorize,
+    crate::server::moderation::controller::{classify_for, review_text},
+    crate::server::moderation::model::{
+        ContentKind, ContentSource, ModerationAction, ReviewStatus,
+    },
+    crate::server::og::controller::{og_variants, rescore},
+    crate::server::og::model::{card_alt_text, clean_alt_text},
+    crate::server::search::controller::index_og,
+    crate::server::template::controller::usable_template,
+    crate::server::usage::controller::{check_quota, metered_complete, store_usage},
+    crate::server::usage::model::{AIFeature, AIUsage},
+    crate::server::voice::controller::owned_voice,
+    crate::server::voice::model::VOICE_RETRIES,
+    std::future::Future,
+    std::time::Instant,
+};
+
+const TITLE_MAX_TOKENS: u32 = 160;
+const DESCRIPTION_MAX_TOKENS: u32 = 400;
+const TEMPERATURE: f32 = 0.8;
+const ALT_TEXT_MAX_TOKENS: u32 = 200;
+const ALT_TEXT_TEMPERATURE: f32 = 0.3;
+const TRANSLATION_MAX_TOKENS: u32 = 800;
+const TRANSLATION_TEMPERATURE: f32 = 0.3;
+const BACKGROUND_PROMPT_MAX_CHARS: usize = 500;
+/// Largest card screenshot forwarded to the vision model.
+const MAX_CARD_IMAGE_BYTES: usize = 4 * 1024 * 1024;
+
+#[server]
+pub async fn generate_suggestions(
+    req: SuggestRequest,
+) -> Result<SuccessResponse<Vec<Suggestion>>, ServerFnError> {
+    let user = authorize(Permission::ManageOwnContent).await?;
+    check_quota(&user).await?;
+
+    let voice = match req.voice_id {
+        Some(voice_id) => Some(owned_voice(&user, voice_id).await?),
+        None => None,
+    };
+    let mut req = req;
+    if let Some(voice) = voice.as_ref().filter(|_| req.tone.trim().is_empty()) {
+        req.tone = voice.tone.clone();
+    }
+
+    let page = if req.site_url.trim().is_empty() {
+        None
+    } else {
+        fetch_page(&req.site_url).await
+    };
+    let mut system = match req.template_id {
+        Some(template_id) => {
+            let template = usable_template(&user, template_id).await?;
+            if template.field != req.field {
+                return Err(ServerFnError::new("This template is for a different field"));
+            }
+            template_prompt(&template.body, &req)
+        }
+        None => system_prompt(req.field),
+    };
+    if let Some(voice) = &voice {
+        system.push_str(&voice_prompt(voice));
+    }
+    let max_tokens = suggestion_max_tokens(req.field);
+
+    let mut messages = suggestion_messages(system, &req, page.as_ref());
+    let mut candidates: Vec<String> = Vec::new();
+    let mut rejected_any = false;
+    for attempt in 0..=VOICE_RETRIES {
+        let reply = metered_complete(
+            &user,
+            AIFeature::Suggestions,
+            req.model.as_deref(),
+            messages.clone(),
+            TEMPERATURE,
+            max_tokens,
+        )
+        .await?;
+
+        // Candidates breaking the voice's hard rules are dropped and the
+        // model is asked for replacements, explaining what went wrong.
+        // Candidates blocked by moderation are dropped silently.
+        let mut rejected = Vec::new();
+        let mut fresh = Vec::new();
+        for candidate in parse_candidates(&reply) {
+            let violations = voice
+                .as_ref()
+                .map(|voice| voice.violations(field_name(req.field), &candidate))
+                .unwrap_or_default();
+            if !violations.is_empty() {
+                rejected.push(format!("\"{candidate}\": {}", violations.join(", ")));
+            } else if !candidates.contains(&candidate) && !fresh.contains(&candidate) {
+                fresh.push(candidate);
+            }
+        }
+        let texts: Vec<&str> = fresh.iter().map(String::as_str).collect();
+        let verdicts = classify_for(&user, &texts).await;
+        candidates.extend(
+            fresh
+                .into_iter()
+                .zip(verdicts)
+                .filter(|(_, verdict)| verdict.action != ModerationAction::Block)
+                .map(|(candidate, _)| candidate),
+        );
+        rejected_any |= !rejected.is_empty();
+        if rejected.is_empty() || candidates.len() >= SUGGESTION_COUNT || attempt == VOICE_RETRIES {
+            break;
+        }
+        messages.push(ChatMessage::assistant(reply));
+        messages.push(ChatMessage::user(format!(
+            "These candidates break the brand voice rules:\n{}\nWrite {} new candidates that follow every rule.",
+            rejected.join("\n"),
+            SUGGESTION_COUNT - candidates.len()
+        )));
+    }
+
+    let suggestions = rank(req.field, candidates, &keywords(&req.keywords));
+    if suggestions.is_empty() {
+        return Err(ServerFnError::new(if rejected_any {
+            "No suggestion followed the brand voice rules"
+        } else {
+            "The AI returned no suggestions"
+        }));
+    }
+
+    Ok(SuccessResponse {
+        status: "success".into(),
+        data: suggestions,
+    })
+}
+
+/// Alt text for the card being edited. With `LLM_VISION_MODEL` set, the model
+/// also looks at the rendered card; otherwise it works from the text and
+/// layout only.
+#[server]
+pub async fn generate_alt_text(
+    req: AltTextRequest,
+) -> Result<SuccessResponse<String>, ServerFnError> {
+    let user = authorize(Permission::ManageOwnContent).await?;
+    if req.title.trim().is_empty() {
+        return Err(ServerFnError::new("Add a title before generating alt text"));
+    }
+    check_quota(&user).await?;
+
+    let mut message = ChatMessage::user(card_layout_prompt(&req));
+    let mut model = req.model.clone();
+    let vision_model = get_llm().await.config.vision_model.clone();
+    if let (Some(vision_model), Some(image)) = (vision_model, &req.image) {
+        if !image.is_empty() && image.len() <= MAX_CARD_IMAGE_BYTES {
+            message = message.with_image(image.clone());
+            model = Some(vision_model);
+        }
+    }
+
+    let reply = metered_complete(
+        &user,
+        AIFeature::AltText,
+        model.as_deref(),
+        vec![ChatMessage::system(alt_text_prompt()), message],
+        ALT_TEXT_TEMPERATURE,
+        ALT_TEXT_MAX_TOKENS,
+    )
+    .await?;
+
+    Ok(SuccessResponse {
+        status: "success".into(),
+        data: alt_text_from_reply(&reply, &req),
+    })
+}
+
+fn suggestion_max_tokens(field: SuggestionField) -> u32 {
+    match field {
+        SuggestionField::Title => TITLE_MAX_TOKENS,
+        SuggestionField::Description => DESCRIPTION_MAX_TOKENS,
+    }
+}
+
+/// The transcript asking for suggestions: the system prompt, then the site
+/// as described by the form and its page.
+#[cfg(feature = "server")]
+fn suggestion_messages(
+    system: String,
+    req: &SuggestRequest,
+    page: Option<&PageSummary>,
+) -> Vec<ChatMessage> {
+    vec![
+        ChatMessage::system(system),
+        ChatMessage::user(context_prompt(req, page)),
+    ]
+}
+
+/// The model's alt text, or the card's own text when the reply is empty.
+#[cfg(feature = "server")]
+fn alt_text_from_reply(reply: &str, req: &AltTextRequest) -> String {
+    match clean_alt_text(reply) {
+        alt if alt.is_empty() => {
+            card_alt_text(&req.title, &req.description, &req.author, &req.site_name)
+        }
+        alt => alt,
+    }
+}
+
+/// Translates an OG's title, description and alt text into each of
+/// `req.locales`, creating a variant per locale or refreshing the existing
+/// one. Returns the whole variant group.
+#[server]
+pub async fn localize_og(
+    req: LocalizeOGRequest,
+) -> Result<SuccessResponse<Vec<OG>>, ServerFnError> {
+    let user = authorize(Permission::ManageOwnContent).await?;
+
+    let client = get_client().await;
+    let db =
+        client.database(&std::env::var("MONGODB_DB_NAME").expect("MONGODB_DB_NAME must be set."));
+    let og_collection = db.collection::<OG>("ogs");
+
+    let og_id = ObjectId::parse_str(&req.og_id).map_err(|_| ServerFnError::new("Invalid og ID"))?;
+    let source = og_collection
+        .find_one(doc! { "_id": og_id, "user": user.id })
+        .await?
+        .ok_or(ServerFnError::new("OG not found"))?;
+    let group = og_variants(&og_collection, user.id, source.group_id()).await?;
+
+    // Translating a variant back into the original's locale would overwrite
+    // the original, so that locale is skipped like the source's own.
+    let original_locale = group
+        .iter()
+        .find(|og| og.variant_of.is_none())
+        .map(|og| og.locale.as_str());
+
+    let mut locales: Vec<String> = Vec::new();
+    for raw in &req.locales {
+        let locale = normalize_locale(raw)
+            .ok_or_else(|| ServerFnError::new(format!("Invalid locale \"{}\"", raw.trim())))?;
+        if locale != source.locale
+            && Some(locale.as_str()) != original_locale
+            && !locales.contains(&locale)
+        {
+            locales.push(locale);
+        }
+    }
+    if locales.is_empty() {
+        return Err(ServerFnError::new(
+            "Pick at least one locale other than the OG's own or its original's",
+        ));
+    }
+    if locales.len() > MAX_LOCALES {
+        return Err(ServerFnError::new(format!(
+            "Translate into at most {MAX_LOCALES} locales at once"
+        )));
+    }
+
+    for locale in locales {
+        let reply = metered_complete(
+            &user,
+            AIFeature::Translation,
+            req.model.as_deref(),
+            vec![
+                ChatMessage::system(translation_prompt(&locale)),
+                ChatMessage::user(translation_source(&source)),
+            ],
+            TRANSLATION_TEMPERATURE,
+            TRANSLATION_MAX_TOKENS,
+        )
+        .await?;
+        let translation = parse_translation(&reply).ok_or_else(|| {
+            ServerFnError::new(format!("The AI returned no usable {locale} translation"))
+        })?;
+
+        let now = Utc::now();
+        let mut variant = group
+            .iter()
+            .find(|og| og.variant_of.is_some() && og.locale == locale)
+            .cloned()
+            .unwrap_or_else(|| OG {
+                id: ObjectId::new(),
+                variant_of: Some(source.group_id()),
+                created_at: now,
+                ..source.clone()
+            });
+        variant.title = translation.title;
+        variant.description = translation.description;
+        variant.image_alt = match translation.image_alt {
+            alt if alt.is_empty() => card_alt_text(
+                &variant.title,
+                &variant.description,
+                &variant.author,
+                &variant.site_name,
+            ),
+            alt => alt,
+        };
+        // A blocked translation is left out rather than failing the others.
+        let review = review_text(
+            &user,
+            ContentKind::Og,
+            variant.id,
+            ContentSource::Ai,
+            &[
+                ("title", &variant.title),
+                ("description", &variant.description),
+            ],
+        )
+        .await;
+        if review.verdict.action == ModerationAction::Block {
+            review.record().await;
+            continue;
+        }
+        variant.moderation = review.status();
+        variant.locale = locale;
+        variant.updated_at = now;
+        og_collection
+            .replace_one(doc! { "_id": variant.id }, &variant)
+            .upsert(true)
+            .await?;
+        review.record().await;
+        index_og(&variant);
+    }
+
+    rescore(user.id);
+
+    Ok(SuccessResponse {
+        status: "success".into(),
+        data: og_variants(&og_collection, user.id, source.group_id()).await?,
+    })
+}
+
+/// Generates a card background from `req.prompt` with the configured image
+/// provider and stores it as an asset, returning its URL.
+#[server]
+pub async fn generate_background(
+    req: BackgroundRequest,
+) -> Result<SuccessResponse<String>, ServerFnError> {
+    let user = authorize(Permission::ManageOwnContent).await?;
+    let idea = req.prompt.trim();
+    if idea.is_empty() || idea.chars().count() > BACKGROUND_PROMPT_MAX_CHARS {
+        return Err(ServerFnError::new(format!(
+            "Describe the background in 1 to {BACKGROUND_PROMPT_MAX_CHARS} characters"
+        )));
+    }
+    check_quota(&user).await?;
+
+    let image_gen = get_image_gen().await;
+    let prompt = background_prompt(idea);
+    let started = Instant::now();
+    let result = image_gen.generate(&prompt, BACKGROUND_SIZE).await;
+    store_usage(AIUsage {
+        id: ObjectId::new(),
+        user: user.id,
+        feature: AIFeature::BackgroundImage,
+        provider: image_gen.config.provider.clone(),
+        model: image_gen.model().to_string(),
+        prompt_tokens: estimate_tokens(&prompt),
+        completion_tokens: 0,
+        latency_ms: started.elapsed().as_millis() as u64,
+        success: result.is_ok(),
+        error: result.as_ref().err().map(|err| err.to_string()),
+        created_at: Utc::now(),
+    })
+    .await;
+
+    let url = pin_background(result?, |bytes, extension| async move {
+        pin_bytes(&bytes, extension).await
+    })
+    .await?;
+    record_asset(user.id, &url).await?;
+
+    Ok(SuccessResponse {
+        status: "success".into(),
+        data: url,
+    })
+}
+
+/// Stores a generated background through `pin`, making sure the URL it comes
+/// back under is one `store_og` accepts.
+#[cfg(feature = "server")]
+async fn pin_background<P, F>(image: GeneratedImage, pin: P) -> Result<String, ServerFnError>
+where
+    P: FnOnce(Vec<u8>, &'static str) -> F,
+    F: Future<Output = Result<String, String>>,
+{
+    let url = pin(image.bytes, image.extension)
+        .await
+        .map_err(ServerFnError::new)?;
+    if !is_gateway_url(&url) {
+        return Err(ServerFnError::new(
+            "The background was stored at an unexpected URL",
+        ));
+    }
+    Ok(url)
+}
+
+#[server]
+pub async fn get_ai_models() -> Result<SuccessResponse<Vec<String>>, ServerFnError> {
+    authorize(Permission::ManageOwnContent).await?;
+
+    Ok(SuccessResponse {
+        status: "success".into(),
+        data: get_llm().await.config.models.clone(),
+    })
+}
+
+#[cfg(all(test, feature = "server"))]
+mod tests {
+    use super::{alt_text_from_reply, pin_background, suggestion_messages};
+    use crate::imagegen::{ImageGen, ImageGenConfig, BACKGROUND_SIZE};
+    use crate::llm::mock_llm;
+    use crate::pinata::GATEWAY_URL;
+    use crate::server::ai::prompt::background_prompt;
+    use crate::server::ai::prompt::{keywords, system_prompt, PageSummary};
+    use crate::server::ai::rank::{parse_candidates, rank};
+    use crate::server::ai::request::{AltTextRequest, SuggestRequest, SuggestionField};
+    use std::time::Duration;
+
+    fn suggest_request() -> SuggestRequest {
+        SuggestRequest {
+            field: SuggestionField::Title,
+            title: "Fresh roasted beans".into(),
+            description: String::new(),
+            site_name: "Bean There".into(),
+            site_url: String::new(),
+            author: String::new(),
+            locale: "en_US".into(),
+            keywords: "coffee, roastery".into(),
+            model: None,
+            template_id: None,
+            tone: String::new(),
+            voice_id: None,
+        }
+    }
+
+    fn alt_request() -> AltTextRequest {
+        AltTextRequest {
+            title: "Fresh roasted beans".into(),
+            description: "Small batch coffee".into(),
+            site_name: "Bean There".into(),
+            author: String::new(),
+            from_color: "purple-300".into(),
+            to_color: "pink-300".into(),
+            image: None,
+            model: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn suggestions_round_trip_through_the_mock() {
+        let req = suggest_request();
+        let page = PageSummary {
+            title: "Bean There roastery".into(),
+            ..PageSummary::default()
+        };
+        let llm = mock_llm();
+
+        let messages = suggestion_messages(system_prompt(req.field), &req, Some(&page));
+        assert_eq!(messages.len(), 2);
+        let reply = llm
+            .complete(None, messages.clone(), 0.8, 160)
+            .await
+            .unwrap();
+        let again = llm.complete(None, messages, 0.8, 160).await.unwrap();
+        assert_eq!(reply, again);
+
+        let suggestions = rank(
+            req.field,
+            parse_candidates(&reply),
+            &keywords(&req.keywords),
+        );
+        assert_eq!(suggestions.len(), 1);
+        assert!(suggestions[0]
+            .text
+            .starts_with("[mock-model] Write 5 candidate OG titles"));
+    }
+
+    #[tokio::test]
+    async fn alt_text_comes_from_the_reply() {
+        let req = alt_request();
+        let reply = mock_llm()
+            .complete(
+                None,
+                vec![crate::llm::ChatMessage::user("a card")],
+                0.3,
+                200,
+            )
+            .await
+            .unwrap();
+        assert_eq!(alt_text_from_reply(&reply, &req), "[mock-model] a card");
+    }
+
+    #[test]
+    fn empty_alt_text_falls_back_to_the_card() {
+        let req = alt_request();
+        let alt = alt_text_from_reply("  \"\"  ", &req);
+        assert!(alt.contains("Fresh roasted beans"));
+        assert!(alt.contains("Small batch coffee"));
+    }
+
+    fn procedural_image_gen() -> ImageGen {
+        ImageGen::new(ImageGenConfig {
+            provider: "procedural".into(),
+            base_url: String::new(),
+            api_key: None,
+            model: String::new(),
+            timeout: Duration::from_secs(1),
+        })
+    }
+
+    #[tokio::test]
+    async fn pins_the_generated_background() {
+        let image_gen = procedural_image_gen();
+        let prompt = background_prompt("misty pine forest");
+        let image = image_gen.generate(&prompt, BACKGROUND_SIZE).await.unwrap();
+        let expected = image.bytes.clone();
+
+        let url = pin_background(image, |bytes, extension| async move {
+            assert_eq!(bytes, expected);
+            assert_eq!(extension, "svg");
+            Ok(format!("{GATEWAY_URL}QmBackground"))
+        })
+        .await
+        .unwrap();
+        assert_eq!(url, format!("{GATEWAY_URL}QmBackground"));
+        assert_eq!(image_gen.model(), "procedural");
+    }
+
+    #[tokio::test]
+    async fn rejects_failed_or_foreign_pins() {
+        let image_gen = procedural_image_gen();
+        let image = image_gen.generate("dunes", (64, 64)).await.unwrap();
+        let failed = pin_background(image.clone(), |_, _| async {
+            Err("Pinata Error: quota".to_string())
+        })
+        .await;
+        assert!(failed.is_err());
+
+        let foreign = pin_background(image, |_, _| async {
+            Ok("https://example.com/background.svg".to_string())
+        })
+        .await;
+        assert!(foreign.is_err());
+    }
+}
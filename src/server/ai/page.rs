@@ -0,0 +1,139 @@
+use dioxus_logger::tracing;
+use regex::Regex;
+use reqwest::header::LOCATION;
+use reqwest::redirect::Policy;
+use reqwest::Url;
+use std::net::{IpAddr, SocketAddr};
+use std::time::Duration;
+
+use crate::server::ai::prompt::PageSummary;
+
+const FETCH_TIMEOUT_SECS: u64 = 5;
+const MAX_REDIRECTS: usize = 3;
+const MAX_BODY_BYTES: usize = 512 * 1024;
+const MAX_TEXT_CHARS: usize = 1500;
+
+/// Fetches `url` and extracts its title, meta description and visible text.
+/// Only public http(s) hosts are contacted, so the server cannot be pointed
+/// at internal services. Any failure yields `None`; suggestions then fall
+/// back to the form fields alone.
+pub async fn fetch_page(url: &str) -> Option<PageSummary> {
+    let mut url = Url::parse(url.trim()).ok()?;
+
+    for _ in 0..=MAX_REDIRECTS {
+        let addr = public_addr(&url).await?;
+        let host = url.host_str()?.to_string();
+        let http = reqwest::Client::builder()
+            .timeout(Duration::from_secs(FETCH_TIMEOUT_SECS))
+            .redirect(Policy::none())
+            .resolve(&host, addr)
+            .build()
+            .ok()?;
+
+        let mut response = http.get(url.clone()).send().await.ok()?;
+        if response.status().is_redirection() {
+            let location = response.headers().get(LOCATION)?.to_str().ok()?;
+            url = url.join(location).ok()?;
+            continue;
+        }
+        if !response.status().is_success() {
+            tracing::debug!("page fetch for {url} returned {}", response.status());
+            return None;
+        }
+
+        let mut body = Vec::new();
+        while let Some(chunk) = response.chunk().await.ok()? {
+            body.extend_from_slice(&chunk);
+            if body.len() >= MAX_BODY_BYTES {
+                body.truncate(MAX_BODY_BYTES);
+                break;
+            }
+        }
+        return Some(summarize(&String::from_utf8_lossy(&body)));
+    }
+
+    None
+}
+
+/// Resolves the URL's host and returns an address only if every address it
+/// resolves to is publicly routable.
+async fn public_addr(url: &Url) -> Option<SocketAddr> {
+    if !matches!(url.scheme(), "http" | "https") {
+        return None;
+    }
+    let host = url.host_str()?;
+    let port = url.port_or_known_default()?;
+
+    let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host, port)).await.ok()?.collect();
+    if addrs.is_empty() || !addrs.iter().all(|addr| is_public(addr.ip())) {
+        return None;
+    }
+    addrs.first().copied()
+}
+
+fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            !(ip.is_private()
+                || ip.is_loopback()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast()
+                || ip.is_multicast()
+                || ip.is_documentation()
+                || ip.octets()[0] == 0
+                || (ip.octets()[0] == 100 && (ip.octets()[1] & 0xc0) == 64))
+        }
+        IpAddr::V6(ip) => {
+            if let Some(v4) = ip.to_ipv4_mapped() {
+                return is_public(IpAddr::V4(v4));
+            }
+            let first = ip.segments()[0];
+            !(ip.is_loopback()
+                || ip.is_unspecified()
+                || ip.is_multicast()
+                || (first & 0xfe00) == 0xfc00
+                || (first & 0xffc0) == 0xfe80)
+        }
+    }
+}
+
+fn summarize(html: &str) -> PageSummary {
+    let title = Regex::new(r"(?is)<title[^>]*>(.*?)</title>")
+        .unwrap()
+        .captures(html)
+        .map(|caps| clean_text(&caps[1]))
+        .unwrap_or_default();
+    let description = Regex::new(
+        r#"(?is)<meta[^>]+(?:name|property)=["'](?:og:)?description["'][^>]*content=["']([^"']*)["']"#,
+    )
+    .unwrap()
+    .captures(html)
+    .map(|caps| clean_text(&caps[1]))
+    .unwrap_or_default();
+
+    let body = Regex::new(
+        r"(?is)<(script|style|noscript|svg|head)[^>]*>.*?</(script|style|noscript|svg|head)>",
+    )
+    .unwrap()
+    .replace_all(html, " ");
+    let text: String = clean_text(&body).chars().take(MAX_TEXT_CHARS).collect();
+
+    PageSummary {
+        title,
+        description,
+        text,
+    }
+}
+
+fn clean_text(html: &str) -> String {
+    let text = Regex::new(r"(?s)<[^>]*>").unwrap().replace_all(html, " ");
+    let text = text
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'");
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
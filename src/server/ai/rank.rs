@@ -0,0 +1,93 @@
+use regex::Regex;
+use std::cmp::Reverse;
+
+use crate::server::ai::request::SuggestionField;
+use crate::server::ai::response::Suggestion;
+
+const LENGTH_WEIGHT: f32 = 40.0;
+const KEYWORD_WEIGHT: f32 = 40.0;
+const ORDER_WEIGHT: f32 = 20.0;
+
+/// Ideal character range for each field.
+fn target_length(field: SuggestionField) -> (usize, usize) {
+    match field {
+        SuggestionField::Title => (15, 60),
+        SuggestionField::Description => (50, 160),
+    }
+}
+
+/// Splits a model reply into candidates, dropping list markers, labels,
+/// quotes and duplicates.
+pub fn parse_candidates(reply: &str) -> Vec<String> {
+    let marker = Regex::new(r"^\s*(\d+[.)]|[-*•])\s+").unwrap();
+    let mut candidates: Vec<String> = Vec::new();
+    for line in reply.lines() {
+        let line = marker.replace(line, "");
+        let line = line.trim();
+        let line = ["Title:", "Description:"]
+            .iter()
+            .find_map(|label| line.strip_prefix(label))
+            .unwrap_or(line)
+            .trim()
+            .trim_matches(|c| c == '"' || c == '\'' || c == '*')
+            .trim();
+
+        if !line.is_empty()
+            && !candidates
+                .iter()
+                .any(|candidate| candidate.eq_ignore_ascii_case(line))
+        {
+            candidates.push(line.to_string());
+        }
+    }
+    candidates
+}
+
+/// Scores candidates on length fit, keyword coverage and the model's own
+/// ordering, best first.
+pub fn rank(
+    field: SuggestionField,
+    candidates: Vec<String>,
+    keywords: &[String],
+) -> Vec<Suggestion> {
+    let (min, max) = target_length(field);
+    let total = candidates.len().max(1) as f32;
+
+    let mut ranked: Vec<Suggestion> = candidates
+        .into_iter()
+        .enumerate()
+        .map(|(index, text)| {
+            let length = text.chars().count();
+            let length_fit = if length < min {
+                length as f32 / min as f32
+            } else if length > max {
+                (max as f32 / length as f32).powi(2)
+            } else {
+                1.0
+            };
+
+            let lower = text.to_lowercase();
+            let coverage = if keywords.is_empty() {
+                1.0
+            } else {
+                keywords
+                    .iter()
+                    .filter(|keyword| lower.contains(&keyword.to_lowercase()))
+                    .count() as f32
+                    / keywords.len() as f32
+            };
+
+            let order = 1.0 - index as f32 / total;
+            let score =
+                LENGTH_WEIGHT * length_fit + KEYWORD_WEIGHT * coverage + ORDER_WEIGHT * order;
+
+            Suggestion {
+                text,
+                score: score.round().clamp(0.0, 100.0) as u8,
+            }
+        })
+        .collect();
+
+    ranked.sort_by_key(|suggestion| Reverse(suggestion.score));
+    ranked
+}
@@ -0,0 +1,61 @@
+use bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum SuggestionField {
+    Title,
+    Description,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SuggestRequest {
+    pub field: SuggestionField,
+    pub title: String,
+    pub description: String,
+    pub site_name: String,
+    pub site_url: String,
+    pub author: String,
+    pub locale: String,
+    /// Comma separated target keywords.
+    pub keywords: String,
+    pub model: Option<String>,
+    /// A prompt template to use instead of the built-in system prompt.
+    #[serde(default)]
+    pub template_id: Option<ObjectId>,
+    /// Free-form voice for the copy, e.g. "playful".
+    #[serde(default)]
+    pub tone: String,
+    /// Brand voice whose rules the suggestions must follow.
+    #[serde(default)]
+    pub voice_id: Option<ObjectId>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AltTextRequest {
+    pub title: String,
+    pub description: String,
+    pub site_name: String,
+    pub author: String,
+    /// Tailwind colors of the card's background gradient.
+    pub from_color: String,
+    pub to_color: String,
+    /// Base64 encoded PNG of the rendered card, read when a vision model is
+    /// configured.
+    #[serde(default)]
+    pub image: Option<String>,
+    pub model: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LocalizeOGRequest {
+    pub og_id: String,
+    /// Target locales such as `fr_FR`.
+    pub locales: Vec<String>,
+    pub model: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BackgroundRequest {
+    /// What the background should show, e.g. "misty pine forest at dawn".
+    pub prompt: String,
+}
@@ -0,0 +1,8 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Suggestion {
+    pub text: String,
+    /// 0-100, higher is better.
+    pub score: u8,
+}
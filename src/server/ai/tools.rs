@@ -0,0 +1,301 @@
+use regex::Regex;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+use crate::pinata::is_gateway_url;
+use crate::server::conversation::model::{EditStatus, FieldChange, OGEdit};
+use crate::server::og::model::{IMAGE_ALT_MAX_CHARS, OG};
+use crate::server::og::request::UpdateOGContentRequest;
+use crate::server::voice::model::BrandVoice;
+
+pub const EDIT_TOOL: &str = "update_og";
+
+/// Fields the assistant may change, in display order.
+pub const EDITABLE_FIELDS: [&str; 9] = [
+    "title",
+    "description",
+    "site_name",
+    "image_url",
+    "image_alt",
+    "author",
+    "locale",
+    "twitter_card",
+    "twitter_site",
+];
+
+const TWITTER_CARDS: [&str; 4] = ["summary", "summary_large_image", "app", "player"];
+
+pub const EDIT_TOOL_PROMPT: &str = r#"
+**Tools:** When the user asks you to change the OG (for example "shorten the title", "switch to summary card" or "translate to French"), call the `update_og` tool by ending your reply with exactly one block:
+<tool_call>{"name": "update_og", "arguments": {"title": "..."}}</tool_call>
+- `arguments` holds only the fields to change, with their complete new values.
+- Allowed fields: title, description, site_name, image_url, image_alt, author, locale, twitter_card, twitter_site.
+- twitter_card is one of summary, summary_large_image, app or player.
+- Before the block, say in one sentence what the edit does. The user reviews the change before it is applied, so never claim it is already done.
+- Do not call the tool when the user only asks a question.
+"#;
+
+#[derive(Deserialize)]
+struct ToolCall {
+    name: String,
+    #[serde(default)]
+    arguments: HashMap<String, serde_json::Value>,
+}
+
+/// Separates an assistant reply into its visible text and the edit proposed
+/// by an `update_og` tool call, if any. Unknown fields, invalid values (such
+/// as an image outside our gateway) and values equal to the current ones are
+/// dropped.
+pub fn extract_edit(reply: &str, og: &OG) -> (String, Option<OGEdit>) {
+    let block = Regex::new(r"(?s)<tool_call>(.*?)(?:</tool_call>|$)").unwrap();
+    let call = block
+        .captures(reply)
+        .and_then(|caps| serde_json::from_str::<ToolCall>(caps[1].trim()).ok())
+        .filter(|call| call.name == EDIT_TOOL);
+    let text = block.replace_all(reply, "").trim().to_string();
+
+    let changes: Vec<FieldChange> = call
+        .map(|call| {
+            EDITABLE_FIELDS
+                .iter()
+                .filter_map(|field| {
+                    let after = call.arguments.get(*field)?.as_str()?.trim().to_string();
+                    let before = field_value(og, field)?.to_string();
+                    let valid = match *field {
+                        "title" => !after.is_empty(),
+                        "twitter_card" => TWITTER_CARDS.contains(&after.as_str()),
+                        "image_alt" => after.chars().count() <= IMAGE_ALT_MAX_CHARS,
+                        "image_url" => is_gateway_url(&after),
+                        _ => true,
+                    };
+                    (valid && after != before).then(|| FieldChange {
+                        field: field.to_string(),
+                        before,
+                        after,
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    if changes.is_empty() {
+        return (text, None);
+    }
+    let text = if text.is_empty() {
+        "Here is the edit I suggest.".to_string()
+    } else {
+        text
+    };
+    let edit = OGEdit {
+        og: og.id,
+        changes,
+        status: EditStatus::Pending,
+    };
+    (text, Some(edit))
+}
+
+pub fn field_value<'a>(og: &'a OG, field: &str) -> Option<&'a str> {
+    let value = match field {
+        "title" => &og.title,
+        "description" => &og.description,
+        "site_name" => &og.site_name,
+        "image_url" => &og.image_url,
+        "image_alt" => &og.image_alt,
+        "author" => &og.author,
+        "locale" => &og.locale,
+        "twitter_card" => &og.twitter_card,
+        "twitter_site" => &og.twitter_site,
+        _ => return None,
+    };
+    Some(value)
+}
+
+/// The `update_og` request applying every change of `edit`.
+pub fn update_request(edit: &OGEdit) -> UpdateOGContentRequest {
+    let mut req = UpdateOGContentRequest {
+        og_id: edit.og.to_hex(),
+        title: None,
+        description: None,
+        site_name: None,
+        image_url: None,
+        image_alt: None,
+        brand_url: None,
+        author: None,
+        locale: None,
+        twitter_card: None,
+        twitter_site: None,
+    };
+    for change in &edit.changes {
+        let value = Some(change.after.clone());
+        match change.field.as_str() {
+            "title" => req.title = value,
+            "description" => req.description = value,
+            "site_name" => req.site_name = value,
+            "image_url" => req.image_url = value,
+            "image_alt" => req.image_alt = value,
+            "author" => req.author = value,
+            "locale" => req.locale = value,
+            "twitter_card" => req.twitter_card = value,
+            "twitter_site" => req.twitter_site = value,
+            _ => {}
+        }
+    }
+    req
+}
+
+/// Ways `reply` breaks the hard rules of `voice`: banned words in the text
+/// and proposed field values that are too long or use banned words.
+pub fn voice_violations(reply: &str, og: &OG, voice: &BrandVoice) -> Vec<String> {
+    let (text, edit) = extract_edit(reply, og);
+    let mut violations: Vec<String> = voice
+        .banned_in(&text)
+        .into_iter()
+        .map(|word| format!("the reply uses the banned word \"{word}\""))
+        .collect();
+    for change in edit.iter().flat_map(|edit| &edit.changes) {
+        for violation in voice.violations(&change.field, &change.after) {
+            violations.push(format!("the proposed {} {violation}", change.field));
+        }
+    }
+    violations
+}
+
+/// A note for the user when the visible text of a reply still uses words
+/// banned by `voice`, e.g. after a streamed reply that could not be retried.
+pub fn banned_words_note(text: &str, voice: &BrandVoice) -> Option<String> {
+    let banned = voice.banned_in(text);
+    (!banned.is_empty()).then(|| {
+        format!(
+            "_This reply uses {} banned by the \"{}\" brand voice._",
+            banned
+                .iter()
+                .map(|word| format!("\"{word}\""))
+                .collect::<Vec<_>>()
+                .join(", "),
+            voice.name
+        )
+    })
+}
+
+/// Drops the changes of `edit` that break `voice`, returning a note for the
+/// user about what was dropped.
+pub fn enforce_voice(edit: &mut Option<OGEdit>, voice: &BrandVoice) -> Option<String> {
+    let changes = &mut edit.as_mut()?.changes;
+    let mut dropped = Vec::new();
+    changes.retain(|change| {
+        let violations = voice.violations(&change.field, &change.after);
+        if !violations.is_empty() {
+            dropped.push(format!("{} ({})", change.field, violations.join(", ")));
+        }
+        violations.is_empty()
+    });
+    if changes.is_empty() {
+        *edit = None;
+    }
+    (!dropped.is_empty()).then(|| {
+        format!(
+            "_The proposed {} broke the \"{}\" brand voice and was left out._",
+            dropped.join("; "),
+            voice.name
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{extract_edit, update_request};
+    use crate::pinata::GATEWAY_URL;
+    use crate::server::conversation::model::FieldChange;
+    use crate::server::og::model::OG;
+
+    fn og() -> OG {
+        OG {
+            title: "Old title".into(),
+            twitter_card: "summary".into(),
+            image_url: format!("{GATEWAY_URL}QmOld"),
+            ..OG::default()
+        }
+    }
+
+    fn fields(changes: &[FieldChange]) -> Vec<(&str, &str)> {
+        changes
+            .iter()
+            .map(|change| (change.field.as_str(), change.after.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn splits_text_from_the_proposed_edit() {
+        let reply = r#"Shorter title. <tool_call>{"name": "update_og", "arguments": {"title": "New", "twitter_card": "summary_large_image"}}</tool_call>"#;
+        let (text, edit) = extract_edit(reply, &og());
+        assert_eq!(text, "Shorter title.");
+        let edit = edit.unwrap();
+        assert_eq!(
+            fields(&edit.changes),
+            [("title", "New"), ("twitter_card", "summary_large_image")]
+        );
+        assert_eq!(edit.changes[0].before, "Old title");
+    }
+
+    #[test]
+    fn ignores_malformed_calls() {
+        let og = og();
+        for reply in [
+            r#"Sure. <tool_call>{"name": "update_og", "arguments": {"title": </tool_call>"#,
+            r#"Sure. <tool_call>{"name": "delete_og", "arguments": {"title": "New"}}</tool_call>"#,
+            r#"Sure. <tool_call>not json at all"#,
+        ] {
+            let (text, edit) = extract_edit(reply, &og);
+            assert_eq!(text, "Sure.");
+            assert!(edit.is_none(), "{reply}");
+        }
+    }
+
+    #[test]
+    fn drops_unknown_non_editable_and_invalid_fields() {
+        let reply = r#"<tool_call>{"name": "update_og", "arguments": {"user": "someone", "brand_url": "https://evil.test", "moderation": "approved", "twitter_card": "poster", "title": "", "image_url": "https://evil.test/x.png", "author": 7, "locale": "fr_FR"}}</tool_call>"#;
+        let (text, edit) = extract_edit(reply, &og());
+        assert_eq!(text, "Here is the edit I suggest.");
+        assert_eq!(fields(&edit.unwrap().changes), [("locale", "fr_FR")]);
+    }
+
+    #[test]
+    fn accepts_images_on_our_gateway_only() {
+        let og = og();
+        let reply = |url: &str| {
+            format!(
+                r#"<tool_call>{{"name": "update_og", "arguments": {{"image_url": "{url}"}}}}</tool_call>"#
+            )
+        };
+        let pinned = format!("{GATEWAY_URL}QmNew");
+        let (_, edit) = extract_edit(&reply(&pinned), &og);
+        assert_eq!(
+            fields(&edit.unwrap().changes),
+            [("image_url", pinned.as_str())]
+        );
+        let (_, edit) = extract_edit(&reply(&format!("{GATEWAY_URL}Qm1/../x")), &og);
+        assert!(edit.is_none());
+        let (_, edit) = extract_edit(&reply(&og.image_url), &og);
+        assert!(edit.is_none());
+    }
+
+    #[test]
+    fn builds_the_update_request_from_every_change() {
+        let og = og();
+        let reply = r#"<tool_call>{"name": "update_og", "arguments": {"title": "New", "locale": "de_DE"}}</tool_call>"#;
+        let (_, edit) = extract_edit(reply, &og);
+        let mut edit = edit.unwrap();
+        edit.changes.push(FieldChange {
+            field: "brand_url".into(),
+            before: String::new(),
+            after: "https://evil.test".into(),
+        });
+        let req = update_request(&edit);
+        assert_eq!(req.og_id, og.id.to_hex());
+        assert_eq!(req.title.as_deref(), Some("New"));
+        assert_eq!(req.locale.as_deref(), Some("de_DE"));
+        assert_eq!(req.brand_url, None);
+        assert_eq!(req.image_url, None);
+        assert_eq!(req.description, None);
+    }
+}
@@ -0,0 +1,97 @@
+use regex::Regex;
+use serde::Deserialize;
+
+use crate::server::og::model::clean_alt_text;
+
+/// Most locales a single request may translate into.
+pub const MAX_LOCALES: usize = 10;
+
+/// The translated copy of an OG as returned by the model.
+#[derive(Debug, Deserialize, Clone)]
+pub struct Translation {
+    pub title: String,
+    pub description: String,
+    #[serde(default)]
+    pub image_alt: String,
+}
+
+/// `fr-fr`, `fr_FR` and ` fr_fr ` all become `fr_FR`, the form `og:locale`
+/// uses. `None` when `raw` is not a language with an optional region.
+pub fn normalize_locale(raw: &str) -> Option<String> {
+    let pattern = Regex::new(r"^([a-zA-Z]{2,3})(?:[-_]([a-zA-Z]{2}))?$").unwrap();
+    let caps = pattern.captures(raw.trim())?;
+    let language = caps[1].to_lowercase();
+    Some(match caps.get(2) {
+        Some(region) => format!("{language}_{}", region.as_str().to_uppercase()),
+        None => language,
+    })
+}
+
+/// Reads the JSON object in `reply`, tolerating code fences and surrounding
+/// text. Empty titles are rejected.
+pub fn parse_translation(reply: &str) -> Option<Translation> {
+    let start = reply.find('{')?;
+    let end = reply.rfind('}')?;
+    let mut translation: Translation = serde_json::from_str(reply.get(start..=end)?).ok()?;
+    translation.title = translation.title.trim().to_string();
+    translation.description = translation.description.trim().to_string();
+    translation.image_alt = clean_alt_text(&translation.image_alt);
+    (!translation.title.is_empty()).then_some(translation)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{normalize_locale, parse_translation};
+
+    #[test]
+    fn normalizes_locale_casing_and_separators() {
+        assert_eq!(normalize_locale("fr-fr").as_deref(), Some("fr_FR"));
+        assert_eq!(normalize_locale(" FR_fr ").as_deref(), Some("fr_FR"));
+        assert_eq!(normalize_locale("pt-BR").as_deref(), Some("pt_BR"));
+        assert_eq!(normalize_locale("DE").as_deref(), Some("de"));
+        assert_eq!(normalize_locale("fil").as_deref(), Some("fil"));
+    }
+
+    #[test]
+    fn rejects_other_locale_shapes() {
+        for raw in [
+            "",
+            "f",
+            "french",
+            "fr-",
+            "fr FR",
+            "fr_FRA",
+            "zh-Hant-TW",
+            "fr.FR",
+            "1a",
+        ] {
+            assert_eq!(normalize_locale(raw), None, "{raw}");
+        }
+    }
+
+    #[test]
+    fn reads_fenced_json_with_surrounding_text() {
+        let reply = "Here you go:\n```json\n{\"title\": \" Bonjour \", \"description\": \"Salut\", \"image_alt\": \"Une carte\"}\n```\nEnjoy!";
+        let translation = parse_translation(reply).unwrap();
+        assert_eq!(translation.title, "Bonjour");
+        assert_eq!(translation.description, "Salut");
+        assert_eq!(translation.image_alt, "Une carte");
+    }
+
+    #[test]
+    fn rejects_partial_or_incomplete_replies() {
+        assert!(parse_translation("Sorry, I can't help with that.").is_none());
+        assert!(parse_translation(r#"{"title": "Bonjour", "description": "Sal"#).is_none());
+        assert!(parse_translation(r#"} nope {"#).is_none());
+        assert!(parse_translation(r#"{"description": "Salut"}"#).is_none());
+        assert!(parse_translation(r#"{"title": "Bonjour"}"#).is_none());
+        assert!(parse_translation(r#"{"title": "  ", "description": "Salut"}"#).is_none());
+    }
+
+    #[test]
+    fn defaults_a_missing_alt_text() {
+        let translation =
+            parse_translation(r#"{"title": "Hallo", "description": "Welt"}"#).unwrap();
+        assert_eq!(translation.image_alt, "");
+    }
+}
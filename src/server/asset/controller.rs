@@ -0,0 +1,120 @@
+#![allow(unused)]
+#![allow(dead_code)]
+
+use crate::server::asset::model::PinnedAsset;
+use crate::server::og::model::OG;
+use bson::doc;
+use bson::oid::ObjectId;
+use chrono::prelude::*;
+use dioxus::prelude::*;
+use dioxus_logger::tracing;
+use futures_util::TryStreamExt;
+
+#[cfg(feature = "server")]
+use {
+    crate::db::get_client,
+    crate::pinata::{gateway_hash, get_pinata_client, GATEWAY_URL},
+    mongodb::Collection,
+    std::collections::BTreeSet,
+};
+
+/// Records that `user` pinned the file at `url`.
+#[cfg(feature = "server")]
+pub async fn record_asset(user: ObjectId, url: &str) -> Result<(), ServerFnError> {
+    let hash = gateway_hash(url).ok_or(ServerFnError::new("Not a pinned asset"))?;
+    asset_collection()
+        .await
+        .update_one(
+            doc! { "user": user, "hash": hash },
+            doc! { "$setOnInsert": { "_id": ObjectId::new(), "createdAt": Utc::now() } },
+        )
+        .upsert(true)
+        .await?;
+    Ok(())
+}
+
+/// Whether `url` is a file `user` pinned through us.
+#[cfg(feature = "server")]
+pub async fn owns_asset(user: ObjectId, url: &str) -> Result<bool, ServerFnError> {
+    let Some(hash) = gateway_hash(url) else {
+        return Ok(false);
+    };
+    Ok(asset_collection()
+        .await
+        .find_one(doc! { "user": user, "hash": hash })
+        .await?
+        .is_some())
+}
+
+/// Drops `user`'s asset records and unpins the hashes they held, together
+/// with `extra` hashes from the user's deleted OGs. A hash still used by a
+/// remaining OG or pinned by another user stays pinned. Call this after the
+/// user's OGs are deleted.
+#[cfg(feature = "server")]
+pub async fn release_assets(user: ObjectId, extra: BTreeSet<String>) -> Result<(), ServerFnError> {
+    let collection = asset_collection().await;
+    let mut hashes = extra;
+    hashes.extend(
+        collection
+            .find(doc! { "user": user })
+            .await?
+            .try_collect::<Vec<PinnedAsset>>()
+            .await?
+            .into_iter()
+            .map(|asset| asset.hash),
+    );
+    collection.delete_many(doc! { "user": user }).await?;
+    if hashes.is_empty() {
+        return Ok(());
+    }
+
+    let hash_list: Vec<&String> = hashes.iter().collect();
+    for asset in collection
+        .find(doc! { "hash": { "$in": &hash_list } })
+        .await?
+        .try_collect::<Vec<PinnedAsset>>()
+        .await?
+    {
+        hashes.remove(&asset.hash);
+    }
+
+    let urls: Vec<String> = hashes
+        .iter()
+        .map(|hash| format!("{GATEWAY_URL}{hash}"))
+        .collect();
+    let client = get_client().await;
+    let og_collection = client
+        .database(&std::env::var("MONGODB_DB_NAME").expect("MONGODB_DB_NAME must be set."))
+        .collection::<OG>("ogs");
+    for og in og_collection
+        .find(doc! { "$or": [
+            { "imageUrl": { "$in": &urls } },
+            { "backgroundUrl": { "$in": &urls } },
+        ] })
+        .await?
+        .try_collect::<Vec<OG>>()
+        .await?
+    {
+        for url in [&og.image_url, &og.background_url] {
+            if let Some(hash) = gateway_hash(url) {
+                hashes.remove(hash);
+            }
+        }
+    }
+
+    let pinata = get_pinata_client().await.lock().await;
+    for hash in hashes {
+        if let Err(err) = pinata.unpin(&hash).await {
+            tracing::warn!("failed to unpin {hash}: {err:?}");
+        }
+    }
+    Ok(())
+}
+
+#[cfg(feature = "server")]
+async fn asset_collection() -> Collection<PinnedAsset> {
+    let client = get_client().await;
+    client
+        .database(&std::env::var("MONGODB_DB_NAME").expect("MONGODB_DB_NAME must be set."))
+        .collection::<PinnedAsset>("pinned_assets")
+}
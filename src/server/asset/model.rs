@@ -0,0 +1,17 @@
+#![allow(non_snake_case)]
+
+use bson::{oid::ObjectId, serde_helpers::chrono_datetime_as_bson_datetime};
+use chrono::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// A file pinned on a user's behalf. Pinata stores files by content, so two
+/// users pinning the same bytes each get a record for the same hash.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct PinnedAsset {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    pub user: ObjectId,
+    pub hash: String,
+    #[serde(with = "chrono_datetime_as_bson_datetime", rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+}
@@ -1,4 +1,5 @@
 pub(crate) mod controller;
+pub(crate) mod guard;
 pub(crate) mod model;
 pub(crate) mod request;
 pub(crate) mod response;
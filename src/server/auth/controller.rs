@@ -8,10 +8,10 @@ use chrono::prelude::*;
 use chrono::Duration;
 use dioxus::prelude::*;
 
-use crate::server::auth::model::{TokenClaims, User};
+use crate::server::auth::model::{Permission, Role, TokenClaims, User};
 use crate::server::auth::request::EditUserSchema;
 use crate::server::auth::response::{
-    AuthResponse, DashboardResponse, LoginUserSchema, RegisterUserSchema, UserResponse,
+    DashboardResponse, LoginUserSchema, RegisterUserSchema, UserResponse,
 };
 use crate::server::common::response::SuccessResponse;
 use crate::server::og::model::OG;
@@ -19,9 +19,10 @@ use crate::server::og::model::OG;
 #[cfg(feature = "server")]
 use {
     crate::db::get_client,
+    crate::server::auth::guard::{authenticate, authorize, session_cookie},
     argon2::{password_hash::SaltString, Argon2, PasswordHash, PasswordHasher, PasswordVerifier},
-    axum_extra::extract::cookie::{Cookie, SameSite},
-    jsonwebtoken::{encode, DecodingKey, EncodingKey, Header, Validation},
+    axum::http::header::{HeaderValue, SET_COOKIE},
+    jsonwebtoken::{encode, EncodingKey, Header},
     rand_core::OsRng,
 };
 
@@ -51,14 +52,18 @@ pub async fn register_user(
         .map_err(|_| ServerFnError::new("Error while hashing password"))?;
 
     // Insert new user into MongoDB
+    let email = body.email.to_lowercase();
     let new_user = User {
         id: ObjectId::new(),
         name: body.name,
-        email: body.email.to_lowercase(),
+        signup_email: email.clone(),
+        email,
         password: hashed_password,
-        role: "user".into(),
+        role: Role::User,
         photo: "".into(),
         verified: false,
+        suspended: false,
+        deletion_scheduled_at: None,
         created_at: Utc::now(),
         updated_at: Utc::now(),
     };
@@ -73,14 +78,14 @@ pub async fn register_user(
 #[server]
 pub async fn login_user(
     body: LoginUserSchema,
-) -> Result<SuccessResponse<AuthResponse>, ServerFnError> {
+) -> Result<SuccessResponse<UserResponse>, ServerFnError> {
     let client = get_client().await;
     let db =
         client.database(&std::env::var("MONGODB_DB_NAME").expect("MONGODB_DB_NAME must be set."));
     let user_collection = db.collection::<User>("users");
 
     // Find the user by email
-    let user = user_collection
+    let mut user = user_collection
         .find_one(doc! { "email": &body.email })
         .await?
         .ok_or(ServerFnError::new("Invalid email or password"))?;
@@ -95,6 +100,21 @@ pub async fn login_user(
         return Err(ServerFnError::new("Invalid email or password"));
     }
 
+    if user.suspended {
+        return Err(ServerFnError::new("Account suspended"));
+    }
+
+    if user.role != Role::Admin && user.verified && is_bootstrap_admin(&user.signup_email) {
+        let role = bson::to_bson(&Role::Admin)?;
+        user_collection
+            .update_one(
+                doc! { "_id": user.id },
+                doc! { "$set": { "role": role, "updatedAt": Utc::now() } },
+            )
+            .await?;
+        user.role = Role::Admin;
+    }
+
     // Generate a JWT token
     let now = Utc::now();
     let claims = TokenClaims {
@@ -110,60 +130,53 @@ pub async fn login_user(
         &EncodingKey::from_secret(jwt_secret.as_ref()),
     )?;
 
-    let cookie = Cookie::build(("token", token.to_owned()))
-        .path("/")
-        .max_age(time::Duration::hours(1).into())
-        .same_site(SameSite::Lax)
-        .http_only(true);
+    let cookie = session_cookie(token, time::Duration::hours(1));
+    user.password = String::new();
+    server_context()
+        .response_parts_mut()
+        .headers
+        .append(SET_COOKIE, HeaderValue::from_str(&cookie.to_string())?);
 
     Ok(SuccessResponse {
         status: "success".into(),
-        data: AuthResponse {
-            token: cookie.to_string().parse().unwrap(),
-        },
+        data: UserResponse { user },
     })
 }
 
+/// Whether `email` is listed in `ADMIN_EMAILS`. Verified accounts that
+/// signed up with one of those addresses are made admins when they log in,
+/// which is how the first admin is created before anyone can use the admin
+/// console. The signup address is checked so that changing an account's
+/// email to a listed one does not promote it.
+#[cfg(feature = "server")]
+fn is_bootstrap_admin(email: &str) -> bool {
+    env::var("ADMIN_EMAILS")
+        .map(|emails| {
+            emails
+                .split(',')
+                .any(|admin| !admin.trim().is_empty() && admin.trim().eq_ignore_ascii_case(email))
+        })
+        .unwrap_or(false)
+}
+
 #[server]
-async fn logout() -> Result<SuccessResponse<AuthResponse>, ServerFnError> {
-    let cookie = Cookie::build(("token", ""))
-        .path("/")
-        .max_age(time::Duration::hours(-1))
-        .same_site(SameSite::Lax)
-        .http_only(true);
+pub async fn logout() -> Result<SuccessResponse<String>, ServerFnError> {
+    let cookie = session_cookie(String::new(), time::Duration::hours(-1));
+    server_context()
+        .response_parts_mut()
+        .headers
+        .append(SET_COOKIE, HeaderValue::from_str(&cookie.to_string())?);
 
     Ok(SuccessResponse {
         status: "success".into(),
-        data: AuthResponse {
-            token: cookie.to_string().parse().unwrap(),
-        },
+        data: "Logged out".into(),
     })
 }
 
 #[server]
-pub async fn about_me(token: String) -> Result<SuccessResponse<UserResponse>, ServerFnError> {
-    let client = get_client().await;
-    let db =
-        client.database(&std::env::var("MONGODB_DB_NAME").expect("MONGODB_DB_NAME must be set."));
-    let user_collection = db.collection::<User>("users");
-
-    let claims = jsonwebtoken::decode::<TokenClaims>(
-        &token,
-        &DecodingKey::from_secret(
-            env::var("JWT_SECRET")
-                .expect("JWT_SECRET must be set")
-                .as_ref(),
-        ),
-        &Validation::default(),
-    )
-    .map_err(|_| ServerFnError::new("Invalid token"))?;
-
-    let user_id = ObjectId::from_str(&claims.claims.sub)
-        .map_err(|_| ServerFnError::new("Invalid user ID"))?;
-    let user = user_collection
-        .find_one(doc! { "_id": user_id })
-        .await?
-        .ok_or(ServerFnError::new("User not found"))?;
+pub async fn about_me() -> Result<SuccessResponse<UserResponse>, ServerFnError> {
+    let mut user = authenticate().await?;
+    user.password = String::new();
 
     Ok(SuccessResponse {
         status: "success".into(),
@@ -171,36 +184,10 @@ pub async fn about_me(token: String) -> Result<SuccessResponse<UserResponse>, Se
     })
 }
 
-#[server]
-pub async fn auth(token: String) -> Result<User, ServerFnError> {
-    let client = get_client().await;
-    let db =
-        client.database(&std::env::var("MONGODB_DB_NAME").expect("MONGODB_DB_NAME must be set."));
-    let user_collection = db.collection::<User>("users");
-
-    let claims = jsonwebtoken::decode::<TokenClaims>(
-        &token,
-        &DecodingKey::from_secret(
-            env::var("JWT_SECRET")
-                .expect("JWT_SECRET must be set")
-                .as_ref(),
-        ),
-        &Validation::default(),
-    )
-    .map_err(|_| ServerFnError::new("Invalid token"))?;
-
-    let user_id = ObjectId::from_str(&claims.claims.sub)
-        .map_err(|_| ServerFnError::new("Invalid user ID"))?;
-    let user = user_collection
-        .find_one(doc! { "_id": user_id })
-        .await?
-        .ok_or(ServerFnError::new("User not found"))?;
-
-    Ok(user)
-}
-
 #[server]
 pub async fn get_user_info(user_id: ObjectId) -> Result<SuccessResponse<User>, ServerFnError> {
+    authorize(Permission::ViewUsers).await?;
+
     let client = get_client().await;
     let db =
         client.database(&std::env::var("MONGODB_DB_NAME").expect("MONGODB_DB_NAME must be set"));
@@ -221,6 +208,16 @@ pub async fn get_user_info(user_id: ObjectId) -> Result<SuccessResponse<User>, S
 
 #[server]
 pub async fn dashboard_overview() -> Result<SuccessResponse<DashboardResponse>, ServerFnError> {
+    authorize(Permission::ViewStats).await?;
+
+    Ok(SuccessResponse {
+        status: "success".into(),
+        data: overview_counts().await?,
+    })
+}
+
+#[cfg(feature = "server")]
+pub(crate) async fn overview_counts() -> Result<DashboardResponse, ServerFnError> {
     let client = get_client().await;
     let db =
         client.database(&std::env::var("MONGODB_DB_NAME").expect("MONGODB_DB_NAME must be set."));
@@ -229,17 +226,14 @@ pub async fn dashboard_overview() -> Result<SuccessResponse<DashboardResponse>,
 
     let users = user_collection.estimated_document_count().await?;
     let ogs = og_collection.estimated_document_count().await?;
-    let paid_users = user_collection
-        .count_documents(doc! { "role": { "$ne": "free" } })
+    let staff_users = user_collection
+        .count_documents(doc! { "role": { "$in": ["support", "admin"] } })
         .await?;
 
-    Ok(SuccessResponse {
-        status: "success".into(),
-        data: DashboardResponse {
-            users,
-            ogs,
-            paid_users,
-        },
+    Ok(DashboardResponse {
+        users,
+        ogs,
+        staff_users,
     })
 }
 
@@ -251,23 +245,8 @@ pub async fn edit_profile(
     let db = client.database(&env::var("MONGODB_DB_NAME").expect("MONGODB_DB_NAME must be set."));
     let user_collection = db.collection::<User>("users");
 
-    let claims = jsonwebtoken::decode::<TokenClaims>(
-        &body.token,
-        &DecodingKey::from_secret(
-            env::var("JWT_SECRET")
-                .expect("JWT_SECRET must be set")
-                .as_ref(),
-        ),
-        &Validation::default(),
-    )
-    .map_err(|_| ServerFnError::new("Invalid token"))?;
-    let user_id = ObjectId::from_str(&claims.claims.sub)
-        .map_err(|_| ServerFnError::new("Invalid user ID"))?;
-
-    let mut user = user_collection
-        .find_one(doc! { "_id": user_id })
-        .await?
-        .ok_or(ServerFnError::new("User not found"))?;
+    let mut user = authenticate().await?;
+    let user_id = user.id;
 
     if let Some(new_name) = body.name {
         if new_name.is_empty() {
@@ -280,7 +259,7 @@ pub async fn edit_profile(
         user.photo = new_photo;
     }
 
-    if let Some(new_email) = body.email {
+    if let Some(new_email) = body.email.map(|email| email.to_lowercase()) {
         if !new_email.contains("@") || !new_email.contains(".") {
             return Err(ServerFnError::new("Invalid email format"));
         }
@@ -293,7 +272,7 @@ pub async fn edit_profile(
         {
             return Err(ServerFnError::new("Email already in use"));
         }
-        user.email = new_email.to_lowercase();
+        user.email = new_email;
     }
 
     if let Some(old_pass) = body.old_password {
@@ -348,11 +327,12 @@ pub async fn edit_profile(
                 "name": &user.name,
                 "email": &user.email,
                 "password": &user.password,
-                "updated_at": &user.updated_at,
+                "updatedAt": &user.updated_at,
             }},
         )
         .await
         .map_err(|_| ServerFnError::new("Failed to update user data"))?;
+    user.password = String::new();
 
     Ok(SuccessResponse {
         status: "success".into(),
@@ -0,0 +1,95 @@
+#![allow(unused_imports)]
+
+use dioxus::prelude::*;
+
+use crate::server::auth::model::{Permission, TokenClaims, User};
+
+#[cfg(feature = "server")]
+use {
+    crate::db::get_client,
+    axum::http::HeaderMap,
+    axum_extra::extract::cookie::{Cookie, CookieJar, SameSite},
+    bson::{doc, oid::ObjectId},
+    jsonwebtoken::{DecodingKey, Validation},
+    std::env,
+};
+
+#[cfg(feature = "server")]
+pub const SESSION_COOKIE: &str = "token";
+
+/// Builds the HttpOnly session cookie. `Secure` is on unless `COOKIE_SECURE`
+/// is set to `false` for plain-http development.
+#[cfg(feature = "server")]
+pub fn session_cookie(value: String, max_age: time::Duration) -> Cookie<'static> {
+    let secure = env::var("COOKIE_SECURE")
+        .map(|value| value != "false")
+        .unwrap_or(true);
+
+    Cookie::build((SESSION_COOKIE, value))
+        .path("/")
+        .max_age(max_age)
+        .same_site(SameSite::Strict)
+        .secure(secure)
+        .http_only(true)
+        .build()
+}
+
+/// Resolves the user behind the session cookie of the current request.
+#[cfg(feature = "server")]
+pub async fn authenticate() -> Result<User, ServerFnError> {
+    let headers = server_context().request_parts().headers.clone();
+    authenticate_headers(&headers).await
+}
+
+/// Same as [`authenticate`] for plain axum handlers, which have no server
+/// function context.
+#[cfg(feature = "server")]
+pub async fn authenticate_headers(headers: &HeaderMap) -> Result<User, ServerFnError> {
+    let jar = CookieJar::from_headers(headers);
+    let token = jar
+        .get(SESSION_COOKIE)
+        .map(|cookie| cookie.value().to_string())
+        .filter(|token| !token.is_empty())
+        .ok_or(ServerFnError::new("Not Authenticated"))?;
+
+    let claims = jsonwebtoken::decode::<TokenClaims>(
+        &token,
+        &DecodingKey::from_secret(
+            env::var("JWT_SECRET")
+                .expect("JWT_SECRET must be set")
+                .as_ref(),
+        ),
+        &Validation::default(),
+    )
+    .map_err(|_| ServerFnError::new("Not Authenticated"))?;
+
+    let user_id = ObjectId::parse_str(&claims.claims.sub)
+        .map_err(|_| ServerFnError::new("Invalid user ID"))?;
+
+    let client = get_client().await;
+    let db = client.database(&env::var("MONGODB_DB_NAME").expect("MONGODB_DB_NAME must be set."));
+    let user = db
+        .collection::<User>("users")
+        .find_one(doc! { "_id": user_id })
+        .await?
+        .ok_or(ServerFnError::new("Not Authenticated"))?;
+
+    if user.suspended {
+        return Err(ServerFnError::new("Account suspended"));
+    }
+
+    Ok(user)
+}
+
+/// Authenticates the current request and checks that the user's role grants
+/// `permission`.
+#[cfg(feature = "server")]
+pub async fn authorize(permission: Permission) -> Result<User, ServerFnError> {
+    let user = authenticate().await?;
+
+    if !user.role.can(permission) {
+        return Err(ServerFnError::new("Not Authorized"));
+    }
+
+    Ok(user)
+}
@@ -1,6 +1,10 @@
-use bson::{oid::ObjectId, serde_helpers::chrono_datetime_as_bson_datetime};
+use bson::{
+    oid::ObjectId,
+    serde_helpers::{chrono_datetime_as_bson_datetime, chrono_datetime_as_bson_datetime_optional},
+};
 use chrono::prelude::*;
 use serde::{Deserialize, Serialize};
+use std::fmt;
 
 #[allow(non_snake_case)]
 #[derive(Debug, PartialEq, Deserialize, Serialize, Clone)]
@@ -9,16 +13,81 @@ pub struct User {
     pub id: ObjectId,
     pub name: String,
     pub email: String,
+    /// The address the account signed up with, kept when `email` changes.
+    #[serde(default, rename = "signupEmail")]
+    pub signup_email: String,
     pub password: String,
-    pub role: String,
+    pub role: Role,
     pub photo: String,
     pub verified: bool,
+    #[serde(default)]
+    pub suspended: bool,
+    #[serde(
+        default,
+        with = "chrono_datetime_as_bson_datetime_optional",
+        rename = "deletionScheduledAt"
+    )]
+    pub deletion_scheduled_at: Option<DateTime<Utc>>,
     #[serde(with = "chrono_datetime_as_bson_datetime", rename = "createdAt")]
     pub created_at: DateTime<Utc>,
     #[serde(with = "chrono_datetime_as_bson_datetime", rename = "updatedAt")]
     pub updated_at: DateTime<Utc>,
 }
 
+#[derive(Debug, Default, PartialEq, Eq, Deserialize, Serialize, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    #[default]
+    User,
+    Support,
+    Admin,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Permission {
+    ManageOwnContent,
+    ReviewContent,
+    ManageTemplates,
+    ViewUsers,
+    ManageUsers,
+    ViewStats,
+}
+
+impl Role {
+    pub fn permissions(&self) -> &'static [Permission] {
+        match self {
+            Role::User => &[Permission::ManageOwnContent],
+            Role::Support => &[
+                Permission::ManageOwnContent,
+                Permission::ReviewContent,
+                Permission::ManageTemplates,
+            ],
+            Role::Admin => &[
+                Permission::ManageOwnContent,
+                Permission::ReviewContent,
+                Permission::ManageTemplates,
+                Permission::ViewUsers,
+                Permission::ManageUsers,
+                Permission::ViewStats,
+            ],
+        }
+    }
+
+    pub fn can(&self, permission: Permission) -> bool {
+        self.permissions().contains(&permission)
+    }
+}
+
+impl fmt::Display for Role {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Role::User => write!(f, "user"),
+            Role::Support => write!(f, "support"),
+            Role::Admin => write!(f, "admin"),
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct TokenClaims {
     pub sub: String,
@@ -2,7 +2,6 @@ use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct EditUserSchema {
-    pub token: String,
     pub name: Option<String>,
     pub email: Option<String>,
     pub photo: Option<String>,
@@ -14,11 +14,6 @@ pub struct LoginUserSchema {
     pub password: String,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
-pub struct AuthResponse {
-    pub token: String,
-}
-
 #[derive(Deserialize, Serialize, Debug)]
 pub struct UserResponse {
     pub user: User,
@@ -28,5 +23,5 @@ pub struct UserResponse {
 pub struct DashboardResponse {
     pub users: u64,
     pub ogs: u64,
-    pub paid_users: u64,
+    pub staff_users: u64,
 }
@@ -7,31 +7,88 @@ use dioxus_logger::tracing;
 
 #[cfg(feature = "server")]
 use crate::db::get_client;
-use crate::server::auth::controller::auth;
+#[cfg(feature = "server")]
+use crate::llm::{get_llm, ChatMessage};
+#[cfg(feature = "server")]
+use crate::server::ai::prompt::{chat_prompt, voice_prompt, CONVERSATION_TITLE_PROMPT};
+#[cfg(feature = "server")]
+use crate::server::ai::rank::parse_candidates;
+#[cfg(feature = "server")]
+use crate::server::ai::tools::{
+    banned_words_note, enforce_voice, extract_edit, field_value, update_request, voice_violations,
+};
+#[cfg(feature = "server")]
+use crate::server::auth::guard::authorize;
+use crate::server::auth::model::{Permission, User};
 use crate::server::common::response::SuccessResponse;
+#[cfg(feature = "server")]
+use crate::server::conversation::export::{
+    to_markdown, ConversationArchive, ConversationExport, OGMetadata, ARCHIVE_VERSION,
+};
 use crate::server::conversation::model::Conversation;
+use crate::server::conversation::model::EditStatus;
 use crate::server::conversation::model::Message;
+use crate::server::conversation::request::ArchiveConversationRequest;
 use crate::server::conversation::request::CreateConversationRequest;
+use crate::server::conversation::request::DeleteConversationRequest;
+use crate::server::conversation::request::ExportConversationsRequest;
+use crate::server::conversation::request::ExportFormat;
 use crate::server::conversation::request::GetConversationsRequest;
 use crate::server::conversation::request::GetMessagesRequest;
+use crate::server::conversation::request::ImportConversationsRequest;
+use crate::server::conversation::request::RenameConversationRequest;
+use crate::server::conversation::request::ReviewEditRequest;
+use crate::server::conversation::request::SearchConversationsRequest;
 use crate::server::conversation::request::SendQueryRequest;
+use crate::server::conversation::request::TitleConversationRequest;
+use crate::server::conversation::response::ConversationFile;
+use crate::server::conversation::response::ConversationMatch;
 use crate::server::conversation::response::ConversationResponse;
 use crate::server::conversation::response::ConversationsListResponse;
 use crate::server::conversation::response::MessageResponse;
 use crate::server::conversation::response::MessagesListResponse;
+use crate::server::conversation::tree::{link_legacy, path_to};
+#[cfg(feature = "server")]
+use crate::server::moderation::controller::{
+    moderate, record_all, refuse_blocked, review_many, review_text, Review,
+};
+#[cfg(feature = "server")]
+use crate::server::moderation::model::{
+    ContentKind, ContentSource, ModerationAction, ReviewStatus, REMOVED_MESSAGE,
+};
+use crate::server::og::controller::update_og;
 use crate::server::og::model::OG;
+#[cfg(feature = "server")]
+use crate::server::usage::controller::{check_quota, metered_complete};
+#[cfg(feature = "server")]
+use crate::server::usage::model::AIFeature;
+#[cfg(feature = "server")]
+use crate::server::voice::controller::owned_voice;
+#[cfg(feature = "server")]
+use crate::server::voice::model::{BrandVoice, VOICE_RETRIES};
 use bson::oid::ObjectId;
 use chrono::prelude::*;
 use futures_util::TryStreamExt;
+#[cfg(feature = "server")]
+use mongodb::options::ReturnDocument;
+#[cfg(feature = "server")]
+use std::collections::HashMap;
 use std::env;
 
+const HISTORY_LIMIT: usize = 20;
+const DEFAULT_TITLE: &str = "New conversation";
+const TITLE_MAX_CHARS: usize = 60;
+const SEARCH_LIMIT: i64 = 200;
+const SNIPPET_CONTEXT: usize = 40;
+const MAX_IMPORT_BYTES: usize = 5 * 1024 * 1024;
+const MAX_IMPORT_MESSAGES: usize = 5_000;
+pub(crate) const CHAT_TEMPERATURE: f32 = 0.7;
+
 #[server]
 pub async fn create_conversation(
     req: CreateConversationRequest,
 ) -> Result<ConversationResponse, ServerFnError> {
-    let user = auth(req.token)
-        .await
-        .map_err(|_| ServerFnError::new("Not Authenticated"))?;
+    let user = authorize(Permission::ManageOwnContent).await?;
     let db_client = get_client().await;
     let db = db_client
         .database(&std::env::var("MONGODB_DB_NAME").expect("MONGODB_DB_NAME must be set."));
@@ -39,11 +96,18 @@ pub async fn create_conversation(
 
     let og_id = ObjectId::parse_str(&req.og_id).map_err(|_| ServerFnError::new("Invalid og ID"))?;
 
+    let title = req.title.trim();
     let conversation = Conversation {
         id: ObjectId::new(),
         user: user.id,
         og: og_id,
-        title: req.title,
+        title: if title.is_empty() {
+            DEFAULT_TITLE.to_string()
+        } else {
+            title.to_string()
+        },
+        custom_title: false,
+        archived: false,
         created_at: Utc::now(),
         updated_at: Utc::now(),
     };
@@ -62,18 +126,26 @@ pub async fn create_conversation(
 pub async fn get_conversations(
     req: GetConversationsRequest,
 ) -> Result<ConversationsListResponse, ServerFnError> {
-    let user = auth(req.token)
-        .await
-        .map_err(|_| ServerFnError::new("Not Authenticated"))?;
+    let user = authorize(Permission::ManageOwnContent).await?;
 
     let db_client = get_client().await;
     let db = db_client
         .database(&std::env::var("MONGODB_DB_NAME").expect("MONGODB_DB_NAME must be set."));
     let conversation_collection = db.collection::<Conversation>("conversations");
 
-    let filter = doc! {"user": user.id, "og": req.og_id};
+    let mut filter = if req.archived {
+        doc! { "user": user.id, "archived": true }
+    } else {
+        doc! { "user": user.id, "archived": { "$ne": true } }
+    };
+    if !req.og_id.is_empty() {
+        let og_id =
+            ObjectId::parse_str(&req.og_id).map_err(|_| ServerFnError::new("Invalid og ID"))?;
+        filter.insert("og", og_id);
+    }
     let cursor = conversation_collection
         .find(filter)
+        .sort(doc! { "updatedAt": -1 })
         .await
         .map_err(|e| ServerFnError::new(&e.to_string()))?;
     let conversations: Vec<Conversation> = cursor
@@ -89,41 +161,1085 @@ pub async fn get_conversations(
 
 #[server]
 pub async fn save_message_to_db(message: Message) -> Result<(), ServerFnError> {
+    let user = authorize(Permission::ManageOwnContent).await?;
+
     let db_client = get_client().await;
     let db = db_client
         .database(&std::env::var("MONGODB_DB_NAME").expect("MONGODB_DB_NAME must be set."));
+    let conversation_collection = db.collection::<Conversation>("conversations");
     let messages_collection = db.collection::<Message>("messages");
 
+    conversation_collection
+        .find_one(doc! { "_id": message.conversation, "user": user.id })
+        .await
+        .map_err(|e| ServerFnError::new(e.to_string()))?
+        .ok_or(ServerFnError::new("Conversation not found"))?;
+
+    let mut message = message;
+    let review = moderate(
+        &user,
+        ContentKind::Message,
+        message.id,
+        ContentSource::User,
+        &[("message", &message.content)],
+    )
+    .await?;
+    message.moderation = review.status();
+    let conversation = message.conversation;
     messages_collection
         .insert_one(message)
         .await
         .map_err(|e| ServerFnError::new(&e.to_string()))?;
+    review.record().await;
+    conversation_collection
+        .update_one(
+            doc! { "_id": conversation },
+            doc! { "$set": { "updatedAt": Utc::now() } },
+        )
+        .await?;
     Ok(())
 }
 
 #[server]
 pub async fn get_messages(req: GetMessagesRequest) -> Result<MessagesListResponse, ServerFnError> {
-    let user = auth(req.token)
-        .await
-        .map_err(|_| ServerFnError::new("Not Authenticated"))?;
+    let user = authorize(Permission::ManageOwnContent).await?;
 
     let db_client = get_client().await;
     let db = db_client
         .database(&std::env::var("MONGODB_DB_NAME").expect("MONGODB_DB_NAME must be set."));
+    let conversation_collection = db.collection::<Conversation>("conversations");
     let messages_collection = db.collection::<Message>("messages");
 
+    conversation_collection
+        .find_one(doc! { "_id": req.conversation_id, "user": user.id })
+        .await?
+        .ok_or(ServerFnError::new("Conversation not found"))?;
+
     let filter = doc! {"conversation": req.conversation_id};
     let cursor = messages_collection
         .find(filter)
+        .sort(doc! { "timestamp": 1 })
         .await
         .map_err(|e| ServerFnError::new(&e.to_string()))?;
-    let messages: Vec<Message> = cursor
+    let mut messages: Vec<Message> = cursor
         .try_collect()
         .await
         .map_err(|e| ServerFnError::new(&e.to_string()))?;
+    link_legacy(&mut messages);
 
     Ok(MessagesListResponse {
         status: "success".to_string(),
         data: messages,
     })
 }
+
+#[server]
+pub async fn send_query(req: SendQueryRequest) -> Result<MessageResponse, ServerFnError> {
+    let user = authorize(Permission::ManageOwnContent).await?;
+    check_quota(&user).await?;
+    let chat = prepare_chat(&user, &req).await?;
+
+    let mut prompt = chat.prompt;
+    let mut reply = String::new();
+    for attempt in 0..=VOICE_RETRIES {
+        reply = metered_complete(
+            &user,
+            AIFeature::Chat,
+            Some(&req.model),
+            prompt.clone(),
+            CHAT_TEMPERATURE,
+            chat.max_tokens,
+        )
+        .await?;
+        let violations = chat
+            .voice
+            .as_ref()
+            .map(|voice| voice_violations(&reply, &chat.og, voice))
+            .unwrap_or_default();
+        if violations.is_empty() || attempt == VOICE_RETRIES {
+            break;
+        }
+        prompt.push(ChatMessage::assistant(reply.clone()));
+        prompt.push(ChatMessage::user(format!(
+            "Your last reply broke the brand voice rules: {}. Answer my previous message again, following every rule.",
+            violations.join("; ")
+        )));
+    }
+    let assistant_message = save_reply(
+        &user,
+        chat.conversation,
+        chat.parent,
+        &chat.og,
+        chat.voice.as_ref(),
+        &reply,
+    )
+    .await?;
+
+    Ok(MessageResponse {
+        status: "success".to_string(),
+        data: assistant_message,
+    })
+}
+
+#[cfg(feature = "server")]
+pub(crate) struct PreparedChat {
+    pub conversation: ObjectId,
+    /// The user message the reply answers.
+    pub parent: ObjectId,
+    /// The query stored for this turn; `None` when regenerating.
+    pub query: Option<Message>,
+    pub og: OG,
+    pub prompt: Vec<ChatMessage>,
+    pub max_tokens: u32,
+    pub voice: Option<BrandVoice>,
+}
+
+/// Checks that the conversation and OG belong to `user`, stores the user's
+/// query under `req.parent` and builds the prompt from the OG and the branch
+/// leading to it.
+#[cfg(feature = "server")]
+pub(crate) async fn prepare_chat(
+    user: &User,
+    req: &SendQueryRequest,
+) -> Result<PreparedChat, ServerFnError> {
+    let db_client = get_client().await;
+    let db = db_client
+        .database(&std::env::var("MONGODB_DB_NAME").expect("MONGODB_DB_NAME must be set."));
+    let conversation_collection = db.collection::<Conversation>("conversations");
+    let messages_collection = db.collection::<Message>("messages");
+    let og_collection = db.collection::<OG>("ogs");
+
+    let conversation = conversation_collection
+        .find_one(doc! { "_id": req.conversation_id, "user": user.id })
+        .await?
+        .ok_or(ServerFnError::new("Conversation not found"))?;
+    let voice = match req.voice_id {
+        Some(voice_id) => Some(owned_voice(user, voice_id).await?),
+        None => None,
+    };
+    let og_id = if req.og.is_empty() {
+        conversation.og
+    } else {
+        ObjectId::parse_str(&req.og).map_err(|_| ServerFnError::new("Invalid og ID"))?
+    };
+    let og = og_collection
+        .find_one(doc! { "_id": og_id, "user": user.id })
+        .await?
+        .ok_or(ServerFnError::new("OG not found"))?;
+
+    let mut messages: Vec<Message> = messages_collection
+        .find(doc! { "conversation": conversation.id })
+        .await?
+        .try_collect()
+        .await?;
+    link_legacy(&mut messages);
+    let parent = match req.parent {
+        Some(id) => Some(
+            messages
+                .iter()
+                .find(|message| message.id == id)
+                .ok_or(ServerFnError::new("Message not found"))?,
+        ),
+        None => None,
+    };
+
+    let (history, query, reply_parent, query_message) =
+        if let Some(target) = regenerated_query(req, parent)? {
+            (
+                path_to(&messages, target.parent),
+                target.content.clone(),
+                target.id,
+                None,
+            )
+        } else {
+            let id = ObjectId::new();
+            let content = req.query.trim().to_string();
+            let review = moderate(
+                user,
+                ContentKind::Message,
+                id,
+                ContentSource::User,
+                &[("message", &content)],
+            )
+            .await?;
+            let user_message = Message {
+                id,
+                conversation: conversation.id,
+                parent: parent.map(|message| message.id),
+                root: parent.is_none(),
+                sender: "user".into(),
+                content,
+                timestamp: Utc::now(),
+                edit: None,
+                moderation: review.status(),
+            };
+            messages_collection.insert_one(user_message.clone()).await?;
+            review.record().await;
+            (
+                path_to(&messages, user_message.parent),
+                user_message.content.clone(),
+                user_message.id,
+                Some(user_message),
+            )
+        };
+    let history = &history[history.len().saturating_sub(HISTORY_LIMIT)..];
+
+    conversation_collection
+        .update_one(
+            doc! { "_id": conversation.id },
+            doc! { "$set": { "updatedAt": Utc::now() } },
+        )
+        .await?;
+
+    let mut system = chat_prompt(&og, &req.detail);
+    if let Some(voice) = &voice {
+        system.push_str(&voice_prompt(voice));
+    }
+    let mut prompt = vec![ChatMessage::system(system)];
+    prompt.extend(history.iter().map(|message| {
+        if message.sender == "user" {
+            return ChatMessage::user(message.content.clone());
+        }
+        let mut content = message.content.clone();
+        if let Some(edit) = &message.edit {
+            let status = match edit.status {
+                EditStatus::Pending => "awaiting review",
+                EditStatus::Accepted => "accepted",
+                EditStatus::Rejected => "rejected",
+            };
+            let changes: Vec<String> = edit
+                .changes
+                .iter()
+                .map(|change| format!("{} = {:?}", change.field, change.after))
+                .collect();
+            content.push_str(&format!(
+                "\n[update_og edit {status}: {}]",
+                changes.join(", ")
+            ));
+        }
+        ChatMessage::assistant(content)
+    }));
+    prompt.push(ChatMessage::user(query));
+
+    let max_tokens = match req.detail.as_str() {
+        "brief" => 256,
+        "detailed" => 1024,
+        _ => 512,
+    };
+
+    Ok(PreparedChat {
+        conversation: conversation.id,
+        parent: reply_parent,
+        query: query_message,
+        og,
+        prompt,
+        max_tokens,
+        voice,
+    })
+}
+
+/// The user message a regenerate request answers again, or `None` for a new
+/// query, which must not be empty. Regenerating ignores `req.query`.
+#[cfg(feature = "server")]
+fn regenerated_query<'a>(
+    req: &SendQueryRequest,
+    parent: Option<&'a Message>,
+) -> Result<Option<&'a Message>, ServerFnError> {
+    if !req.regenerate {
+        if req.query.trim().is_empty() {
+            return Err(ServerFnError::new("Query cannot be empty"));
+        }
+        return Ok(None);
+    }
+    parent
+        .filter(|message| message.sender == "user")
+        .map(Some)
+        .ok_or(ServerFnError::new(
+            "Only answers to a query can be regenerated",
+        ))
+}
+
+/// Stores an assistant reply, with any edit it proposes for `og`, and bumps
+/// the conversation's `updatedAt`. Proposed changes breaking `voice` are
+/// left out of the edit, banned words left in the text get a note, and a
+/// reply blocked by moderation is replaced with a notice.
+#[cfg(feature = "server")]
+pub(crate) async fn save_reply(
+    user: &User,
+    conversation: ObjectId,
+    parent: ObjectId,
+    og: &OG,
+    voice: Option<&BrandVoice>,
+    reply: &str,
+) -> Result<Message, ServerFnError> {
+    let (mut content, mut edit) = extract_edit(reply, og);
+    if let Some(voice) = voice {
+        let notes = [
+            banned_words_note(&content, voice),
+            enforce_voice(&mut edit, voice),
+        ];
+        for note in notes.into_iter().flatten() {
+            content.push_str("\n\n");
+            content.push_str(&note);
+        }
+    }
+    let id = ObjectId::new();
+    let mut fields = vec![("reply", content.as_str())];
+    if let Some(edit) = &edit {
+        fields.extend(
+            edit.changes
+                .iter()
+                .map(|change| (change.field.as_str(), change.after.as_str())),
+        );
+    }
+    let review = review_text(user, ContentKind::Message, id, ContentSource::Ai, &fields).await;
+    if review.verdict.action == ModerationAction::Block {
+        content = REMOVED_MESSAGE.to_string();
+        edit = None;
+    }
+
+    let db_client = get_client().await;
+    let db = db_client
+        .database(&std::env::var("MONGODB_DB_NAME").expect("MONGODB_DB_NAME must be set."));
+    let conversation_collection = db.collection::<Conversation>("conversations");
+    let messages_collection = db.collection::<Message>("messages");
+
+    let message = Message {
+        id,
+        conversation,
+        parent: Some(parent),
+        root: false,
+        sender: "assistant".into(),
+        content,
+        timestamp: Utc::now(),
+        edit,
+        moderation: review.status(),
+    };
+    messages_collection.insert_one(message.clone()).await?;
+    review.record().await;
+    conversation_collection
+        .update_one(
+            doc! { "_id": conversation },
+            doc! { "$set": { "updatedAt": Utc::now() } },
+        )
+        .await?;
+
+    Ok(message)
+}
+
+#[server]
+pub async fn review_edit(req: ReviewEditRequest) -> Result<SuccessResponse<OG>, ServerFnError> {
+    let user = authorize(Permission::ManageOwnContent).await?;
+
+    let db_client = get_client().await;
+    let db = db_client
+        .database(&std::env::var("MONGODB_DB_NAME").expect("MONGODB_DB_NAME must be set."));
+    let conversation_collection = db.collection::<Conversation>("conversations");
+    let messages_collection = db.collection::<Message>("messages");
+    let og_collection = db.collection::<OG>("ogs");
+
+    let message = messages_collection
+        .find_one(doc! { "_id": req.message_id })
+        .await?
+        .ok_or(ServerFnError::new("Message not found"))?;
+    conversation_collection
+        .find_one(doc! { "_id": message.conversation, "user": user.id })
+        .await?
+        .ok_or(ServerFnError::new("Conversation not found"))?;
+    let edit = message
+        .edit
+        .ok_or(ServerFnError::new("This message proposes no edit"))?;
+    let og = og_collection
+        .find_one(doc! { "_id": edit.og, "user": user.id })
+        .await?
+        .ok_or(ServerFnError::new("OG not found"))?;
+
+    if req.accept
+        && edit
+            .changes
+            .iter()
+            .any(|change| field_value(&og, &change.field) != Some(change.before.as_str()))
+    {
+        return Err(ServerFnError::new(
+            "The OG has changed since this edit was proposed",
+        ));
+    }
+
+    // Claim the edit first so it cannot be applied twice.
+    let status = if req.accept {
+        EditStatus::Accepted
+    } else {
+        EditStatus::Rejected
+    };
+    let claimed = messages_collection
+        .update_one(
+            doc! { "_id": message.id, "edit.status": "pending" },
+            doc! { "$set": { "edit.status": bson::to_bson(&status)? } },
+        )
+        .await?;
+    if claimed.matched_count == 0 {
+        return Err(ServerFnError::new("This edit has already been reviewed"));
+    }
+    if !req.accept {
+        return Ok(SuccessResponse {
+            status: "success".into(),
+            data: og,
+        });
+    }
+
+    if let Err(err) = update_og(update_request(&edit)).await {
+        messages_collection
+            .update_one(
+                doc! { "_id": message.id },
+                doc! { "$set": { "edit.status": "pending" } },
+            )
+            .await?;
+        return Err(err);
+    }
+
+    let og = og_collection
+        .find_one(doc! { "_id": edit.og, "user": user.id })
+        .await?
+        .ok_or(ServerFnError::new("OG not found"))?;
+
+    Ok(SuccessResponse {
+        status: "success".into(),
+        data: og,
+    })
+}
+
+#[server]
+pub async fn rename_conversation(
+    req: RenameConversationRequest,
+) -> Result<ConversationResponse, ServerFnError> {
+    let user = authorize(Permission::ManageOwnContent).await?;
+
+    let title = req.title.trim();
+    if title.is_empty() {
+        return Err(ServerFnError::new("Title cannot be empty"));
+    }
+    let title: String = title.chars().take(TITLE_MAX_CHARS).collect();
+
+    let conversation = update_conversation(
+        user.id,
+        req.conversation_id,
+        doc! { "title": title, "customTitle": true },
+    )
+    .await?;
+
+    Ok(ConversationResponse {
+        status: "success".to_string(),
+        data: conversation,
+    })
+}
+
+#[server]
+pub async fn archive_conversation(
+    req: ArchiveConversationRequest,
+) -> Result<ConversationResponse, ServerFnError> {
+    let user = authorize(Permission::ManageOwnContent).await?;
+
+    let conversation = update_conversation(
+        user.id,
+        req.conversation_id,
+        doc! { "archived": req.archived },
+    )
+    .await?;
+
+    Ok(ConversationResponse {
+        status: "success".to_string(),
+        data: conversation,
+    })
+}
+
+#[server]
+pub async fn delete_conversation(
+    req: DeleteConversationRequest,
+) -> Result<SuccessResponse<String>, ServerFnError> {
+    let user = authorize(Permission::ManageOwnContent).await?;
+
+    let db_client = get_client().await;
+    let db = db_client
+        .database(&std::env::var("MONGODB_DB_NAME").expect("MONGODB_DB_NAME must be set."));
+    let conversation_collection = db.collection::<Conversation>("conversations");
+    let messages_collection = db.collection::<Message>("messages");
+
+    let result = conversation_collection
+        .delete_one(doc! { "_id": req.conversation_id, "user": user.id })
+        .await?;
+    if result.deleted_count == 0 {
+        return Err(ServerFnError::new("Conversation not found"));
+    }
+    messages_collection
+        .delete_many(doc! { "conversation": req.conversation_id })
+        .await?;
+
+    Ok(SuccessResponse {
+        status: "success".into(),
+        data: "Conversation deleted".into(),
+    })
+}
+
+/// Names the conversation after its first exchange. Conversations the user
+/// renamed keep their title.
+#[server]
+pub async fn auto_title_conversation(
+    req: TitleConversationRequest,
+) -> Result<ConversationResponse, ServerFnError> {
+    let user = authorize(Permission::ManageOwnContent).await?;
+
+    let db_client = get_client().await;
+    let db = db_client
+        .database(&std::env::var("MONGODB_DB_NAME").expect("MONGODB_DB_NAME must be set."));
+    let conversation_collection = db.collection::<Conversation>("conversations");
+    let messages_collection = db.collection::<Message>("messages");
+
+    let conversation = conversation_collection
+        .find_one(doc! { "_id": req.conversation_id, "user": user.id })
+        .await?
+        .ok_or(ServerFnError::new("Conversation not found"))?;
+    if conversation.custom_title {
+        return Ok(ConversationResponse {
+            status: "success".to_string(),
+            data: conversation,
+        });
+    }
+
+    let exchange: Vec<Message> = messages_collection
+        .find(doc! { "conversation": conversation.id })
+        .sort(doc! { "timestamp": 1 })
+        .limit(2)
+        .await?
+        .try_collect()
+        .await?;
+    let transcript = exchange
+        .iter()
+        .map(|message| format!("{}: {}", message.sender, message.content))
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    if transcript.is_empty() {
+        return Err(ServerFnError::new("The conversation has no messages yet"));
+    }
+
+    let reply = metered_complete(
+        &user,
+        AIFeature::ConversationTitle,
+        None,
+        vec![
+            ChatMessage::system(CONVERSATION_TITLE_PROMPT),
+            ChatMessage::user(transcript),
+        ],
+        0.3,
+        24,
+    )
+    .await?;
+    let title = parse_candidates(&reply)
+        .into_iter()
+        .next()
+        .map(|title| {
+            title
+                .trim_end_matches('.')
+                .chars()
+                .take(TITLE_MAX_CHARS)
+                .collect::<String>()
+        })
+        .filter(|title| !title.is_empty())
+        .ok_or(ServerFnError::new("The AI returned no title"))?;
+
+    let conversation =
+        update_conversation(user.id, conversation.id, doc! { "title": title }).await?;
+
+    Ok(ConversationResponse {
+        status: "success".to_string(),
+        data: conversation,
+    })
+}
+
+/// Finds the user's conversations whose title or messages contain `query`,
+/// most recent first, each with an excerpt around the match.
+#[server]
+pub async fn search_conversations(
+    req: SearchConversationsRequest,
+) -> Result<SuccessResponse<Vec<ConversationMatch>>, ServerFnError> {
+    let user = authorize(Permission::ManageOwnContent).await?;
+
+    let query = req.query.trim();
+    if query.is_empty() {
+        return Ok(SuccessResponse {
+            status: "success".into(),
+            data: Vec::new(),
+        });
+    }
+    let pattern = regex::escape(query);
+
+    let db_client = get_client().await;
+    let db = db_client
+        .database(&std::env::var("MONGODB_DB_NAME").expect("MONGODB_DB_NAME must be set."));
+    let conversation_collection = db.collection::<Conversation>("conversations");
+    let messages_collection = db.collection::<Message>("messages");
+
+    let mut filter = doc! { "user": user.id };
+    if !req.og_id.is_empty() {
+        let og_id =
+            ObjectId::parse_str(&req.og_id).map_err(|_| ServerFnError::new("Invalid og ID"))?;
+        filter.insert("og", og_id);
+    }
+    let conversations: Vec<Conversation> = conversation_collection
+        .find(filter)
+        .sort(doc! { "updatedAt": -1 })
+        .await?
+        .try_collect()
+        .await?;
+    let ids: Vec<ObjectId> = conversations.iter().map(|c| c.id).collect();
+
+    let messages: Vec<Message> = messages_collection
+        .find(doc! {
+            "conversation": { "$in": ids },
+            "content": { "$regex": &pattern, "$options": "i" },
+        })
+        .sort(doc! { "timestamp": -1 })
+        .limit(SEARCH_LIMIT)
+        .await?
+        .try_collect()
+        .await?;
+
+    let matcher = regex::RegexBuilder::new(&pattern)
+        .case_insensitive(true)
+        .build()?;
+    let matches = conversations
+        .into_iter()
+        .filter_map(|conversation| {
+            let snippet = messages
+                .iter()
+                .find(|message| message.conversation == conversation.id)
+                .map(|message| snippet(&message.content, &matcher))
+                .or_else(|| {
+                    matcher
+                        .is_match(&conversation.title)
+                        .then(|| conversation.title.clone())
+                })?;
+            Some(ConversationMatch {
+                conversation,
+                snippet,
+            })
+        })
+        .collect();
+
+    Ok(SuccessResponse {
+        status: "success".into(),
+        data: matches,
+    })
+}
+
+#[server]
+pub async fn export_conversations(
+    req: ExportConversationsRequest,
+) -> Result<SuccessResponse<ConversationFile>, ServerFnError> {
+    let user = authorize(Permission::ManageOwnContent).await?;
+
+    let db_client = get_client().await;
+    let db = db_client
+        .database(&std::env::var("MONGODB_DB_NAME").expect("MONGODB_DB_NAME must be set."));
+    let conversation_collection = db.collection::<Conversation>("conversations");
+    let messages_collection = db.collection::<Message>("messages");
+    let og_collection = db.collection::<OG>("ogs");
+
+    let mut filter = doc! { "user": user.id };
+    if let Some(conversation_id) = req.conversation_id {
+        filter.insert("_id", conversation_id);
+    } else if !req.og_id.is_empty() {
+        let og_id =
+            ObjectId::parse_str(&req.og_id).map_err(|_| ServerFnError::new("Invalid og ID"))?;
+        filter.insert("og", og_id);
+    }
+    let conversations: Vec<Conversation> = conversation_collection
+        .find(filter)
+        .sort(doc! { "createdAt": 1 })
+        .await?
+        .try_collect()
+        .await?;
+    if conversations.is_empty() {
+        return Err(ServerFnError::new("No conversations to export"));
+    }
+
+    let og_ids: Vec<ObjectId> = conversations.iter().map(|c| c.og).collect();
+    let ogs: Vec<OG> = og_collection
+        .find(doc! { "_id": { "$in": og_ids }, "user": user.id })
+        .await?
+        .try_collect()
+        .await?;
+
+    let mut exports = Vec::new();
+    for conversation in &conversations {
+        let mut messages: Vec<Message> = messages_collection
+            .find(doc! { "conversation": conversation.id })
+            .await?
+            .try_collect()
+            .await?;
+        link_legacy(&mut messages);
+        let og = ogs
+            .iter()
+            .find(|og| og.id == conversation.og)
+            .map(OGMetadata::from)
+            .unwrap_or_default();
+        exports.push(ConversationExport::new(conversation, og, &messages));
+    }
+    let archive = ConversationArchive {
+        version: ARCHIVE_VERSION,
+        exported_at: Utc::now(),
+        conversations: exports,
+    };
+
+    let stem = match (req.conversation_id, conversations.first()) {
+        (Some(_), Some(conversation)) => slug(&conversation.title),
+        _ => "conversations".to_string(),
+    };
+    let (extension, mime, content) = match req.format {
+        ExportFormat::Markdown => ("md", "text/markdown", to_markdown(&archive)),
+        ExportFormat::Json => (
+            "json",
+            "application/json",
+            serde_json::to_string_pretty(&archive)?,
+        ),
+    };
+
+    Ok(SuccessResponse {
+        status: "success".into(),
+        data: ConversationFile {
+            file_name: format!("nano-og-{stem}-{}.{extension}", Utc::now().format("%Y%m%d")),
+            mime: mime.into(),
+            content,
+        },
+    })
+}
+
+/// Restores conversations from a JSON export as new conversations owned by
+/// the current user, keeping titles, timestamps and message branches. Every
+/// message is moderated; a blocked one refuses the whole import.
+#[server]
+pub async fn import_conversations(
+    req: ImportConversationsRequest,
+) -> Result<ConversationsListResponse, ServerFnError> {
+    let user = authorize(Permission::ManageOwnContent).await?;
+
+    if req.data.len() > MAX_IMPORT_BYTES {
+        return Err(ServerFnError::new("The import file is too large"));
+    }
+    let archive: ConversationArchive = serde_json::from_str(&req.data)
+        .map_err(|_| ServerFnError::new("Not a valid conversation export"))?;
+    if archive.version != ARCHIVE_VERSION {
+        return Err(ServerFnError::new("Unsupported export version"));
+    }
+    let message_count: usize = archive.conversations.iter().map(|c| c.messages.len()).sum();
+    if message_count > MAX_IMPORT_MESSAGES {
+        return Err(ServerFnError::new("The import has too many messages"));
+    }
+
+    let db_client = get_client().await;
+    let db = db_client
+        .database(&std::env::var("MONGODB_DB_NAME").expect("MONGODB_DB_NAME must be set."));
+    let conversation_collection = db.collection::<Conversation>("conversations");
+    let messages_collection = db.collection::<Message>("messages");
+    let og_collection = db.collection::<OG>("ogs");
+
+    // Validate everything before writing so a bad file imports nothing.
+    let mut imports = Vec::new();
+    for export in archive.conversations {
+        let og_id = if req.og_id.is_empty() {
+            &export.og.id
+        } else {
+            &req.og_id
+        };
+        let og_id = ObjectId::parse_str(og_id)
+            .map_err(|_| ServerFnError::new("Choose an OG to import into"))?;
+        og_collection
+            .find_one(doc! { "_id": og_id, "user": user.id })
+            .await?
+            .ok_or(ServerFnError::new("OG not found"))?;
+
+        let title = export.title.trim();
+        let conversation = Conversation {
+            id: ObjectId::new(),
+            user: user.id,
+            og: og_id,
+            title: if title.is_empty() {
+                DEFAULT_TITLE.to_string()
+            } else {
+                title.chars().take(TITLE_MAX_CHARS).collect()
+            },
+            custom_title: true,
+            archived: export.archived,
+            created_at: export.created_at,
+            updated_at: export.updated_at,
+        };
+
+        let ids: HashMap<&str, ObjectId> = export
+            .messages
+            .iter()
+            .map(|message| (message.id.as_str(), ObjectId::new()))
+            .collect();
+        if ids.len() != export.messages.len() {
+            return Err(ServerFnError::new(
+                "The import contains duplicate message IDs",
+            ));
+        }
+        let mut messages = Vec::new();
+        for message in &export.messages {
+            if message.sender != "user" && message.sender != "assistant" {
+                return Err(ServerFnError::new("The import contains an unknown sender"));
+            }
+            let parent = message
+                .parent
+                .as_deref()
+                .and_then(|parent| ids.get(parent))
+                .copied();
+            messages.push(Message {
+                id: ids[message.id.as_str()],
+                conversation: conversation.id,
+                parent,
+                root: parent.is_none(),
+                sender: message.sender.clone(),
+                content: message.content.clone(),
+                timestamp: message.timestamp,
+                edit: None,
+                moderation: None,
+            });
+        }
+        imports.push((conversation, messages));
+    }
+
+    // Imported text comes from the user's file, whoever sent it.
+    let fields: Vec<[(&str, &str); 1]> = imports
+        .iter()
+        .flat_map(|(_, messages)| messages)
+        .map(|message| [("message", message.content.as_str())])
+        .collect();
+    let targets: Vec<(ObjectId, &[(&str, &str)])> = imports
+        .iter()
+        .flat_map(|(_, messages)| messages)
+        .zip(&fields)
+        .map(|(message, fields)| (message.id, fields.as_slice()))
+        .collect();
+    let reviews = review_many(&user, ContentKind::Message, ContentSource::User, &targets).await;
+    let reviews = refuse_blocked(reviews).await?;
+    let statuses: Vec<Option<ReviewStatus>> = reviews.iter().map(Review::status).collect();
+    for (message, status) in imports
+        .iter_mut()
+        .flat_map(|(_, messages)| messages)
+        .zip(statuses)
+    {
+        message.moderation = status;
+    }
+
+    // A failed write removes whatever the import already inserted.
+    let written = async {
+        for (conversation, messages) in &imports {
+            conversation_collection.insert_one(conversation).await?;
+            if !messages.is_empty() {
+                messages_collection.insert_many(messages).await?;
+            }
+        }
+        Ok::<_, mongodb::error::Error>(())
+    }
+    .await;
+    let conversations: Vec<Conversation> = imports
+        .into_iter()
+        .map(|(conversation, _)| conversation)
+        .collect();
+    if let Err(err) = written {
+        let ids: Vec<ObjectId> = conversations.iter().map(|c| c.id).collect();
+        if let Err(cleanup) = messages_collection
+            .delete_many(doc! { "conversation": { "$in": &ids } })
+            .await
+        {
+            tracing::error!("failed to undo message import: {cleanup}");
+        }
+        if let Err(cleanup) = conversation_collection
+            .delete_many(doc! { "_id": { "$in": &ids } })
+            .await
+        {
+            tracing::error!("failed to undo conversation import: {cleanup}");
+        }
+        return Err(err.into());
+    }
+    record_all(reviews).await;
+
+    Ok(ConversationsListResponse {
+        status: "success".to_string(),
+        data: conversations,
+    })
+}
+
+#[cfg(feature = "server")]
+async fn update_conversation(
+    user: ObjectId,
+    conversation: ObjectId,
+    mut updates: bson::Document,
+) -> Result<Conversation, ServerFnError> {
+    let db_client = get_client().await;
+    let db = db_client
+        .database(&std::env::var("MONGODB_DB_NAME").expect("MONGODB_DB_NAME must be set."));
+    let conversation_collection = db.collection::<Conversation>("conversations");
+
+    updates.insert("updatedAt", Utc::now());
+    conversation_collection
+        .find_one_and_update(
+            doc! { "_id": conversation, "user": user },
+            doc! { "$set": updates },
+        )
+        .return_document(ReturnDocument::After)
+        .await?
+        .ok_or(ServerFnError::new("Conversation not found"))
+}
+
+/// A file-name friendly version of `title`.
+#[cfg(feature = "server")]
+fn slug(title: &str) -> String {
+    let slug = title
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+    let slug: String = slug.chars().take(40).collect();
+    if slug.is_empty() {
+        "conversation".into()
+    } else {
+        slug.trim_end_matches('-').to_string()
+    }
+}
+
+/// The text around the first match of `matcher`, or the start of `text`
+/// when nothing matches, with `…` where it was cut.
+#[cfg(feature = "server")]
+fn snippet(text: &str, matcher: &regex::Regex) -> String {
+    let Some(found) = matcher.find(text) else {
+        let mut snippet: String = text.chars().take(SNIPPET_CONTEXT * 2).collect();
+        if snippet.len() < text.len() {
+            snippet.push('…');
+        }
+        return snippet;
+    };
+    let before: String = text[..found.start()]
+        .chars()
+        .rev()
+        .take(SNIPPET_CONTEXT)
+        .collect::<Vec<_>>()
+        .into_iter()
+        .rev()
+        .collect();
+    let after: String = text[found.end()..].chars().take(SNIPPET_CONTEXT).collect();
+
+    let mut snippet = format!("{}{}{}", before, found.as_str(), after);
+    snippet = snippet.split_whitespace().collect::<Vec<_>>().join(" ");
+    if found.start() > before.len() {
+        snippet.insert(0, '…');
+    }
+    if found.end() + after.len() < text.len() {
+        snippet.push('…');
+    }
+    snippet
+}
+
+#[cfg(all(test, feature = "server"))]
+mod tests {
+    use super::{regenerated_query, slug, snippet, SNIPPET_CONTEXT};
+    use crate::server::conversation::model::Message;
+    use crate::server::conversation::request::SendQueryRequest;
+    use bson::oid::ObjectId;
+    use chrono::Utc;
+
+    fn request(query: &str, regenerate: bool) -> SendQueryRequest {
+        SendQueryRequest {
+            conversation_id: ObjectId::new(),
+            query: query.into(),
+            og: String::new(),
+            detail: String::new(),
+            model: String::new(),
+            parent: None,
+            regenerate,
+            voice_id: None,
+        }
+    }
+
+    fn message(sender: &str) -> Message {
+        Message {
+            id: ObjectId::new(),
+            conversation: ObjectId::new(),
+            parent: None,
+            root: true,
+            sender: sender.into(),
+            content: "Shorter title please".into(),
+            timestamp: Utc::now(),
+            edit: None,
+            moderation: None,
+        }
+    }
+
+    #[test]
+    fn regenerates_with_an_empty_query() {
+        let query = message("user");
+        let target = regenerated_query(&request("", true), Some(&query)).unwrap();
+        assert_eq!(target.map(|message| message.id), Some(query.id));
+    }
+
+    #[test]
+    fn only_user_messages_are_regenerated() {
+        let reply = message("assistant");
+        assert!(regenerated_query(&request("", true), Some(&reply)).is_err());
+        assert!(regenerated_query(&request("", true), None).is_err());
+    }
+
+    #[test]
+    fn new_queries_must_not_be_empty() {
+        assert!(regenerated_query(&request("  ", false), None).is_err());
+        assert!(regenerated_query(&request("Hi", false), None)
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn slugs_titles_for_file_names() {
+        assert_eq!(slug("Launch plan: Q3 / Q4!"), "launch-plan-q3-q4");
+        assert_eq!(slug("Café Crème"), "café-crème");
+        assert_eq!(slug("東京 タワー"), "東京-タワー");
+        assert_eq!(slug(""), "conversation");
+        assert_eq!(slug(" -- !! "), "conversation");
+    }
+
+    #[test]
+    fn cuts_long_slugs_on_a_char_boundary() {
+        assert_eq!(slug(&"é".repeat(50)), "é".repeat(40));
+        // A cut right after a separator drops the dangling dash.
+        let title = format!("{} {}", "a".repeat(39), "b".repeat(10));
+        assert_eq!(slug(&title), "a".repeat(39));
+    }
+
+    #[test]
+    fn snippets_the_text_around_the_match() {
+        let matcher = regex::Regex::new("needle").unwrap();
+        assert_eq!(snippet("a needle  in\nhay", &matcher), "a needle in hay");
+
+        let before = "é".repeat(SNIPPET_CONTEXT + 3);
+        let after = "ü".repeat(SNIPPET_CONTEXT + 3);
+        assert_eq!(
+            snippet(&format!("{before}needle{after}"), &matcher),
+            format!(
+                "…{}needle{}…",
+                "é".repeat(SNIPPET_CONTEXT),
+                "ü".repeat(SNIPPET_CONTEXT)
+            )
+        );
+    }
+
+    #[test]
+    fn snippets_the_start_without_a_match() {
+        let matcher = regex::Regex::new("needle").unwrap();
+        assert_eq!(snippet("short text", &matcher), "short text");
+        assert_eq!(
+            snippet(&"ß".repeat(SNIPPET_CONTEXT * 3), &matcher),
+            format!("{}…", "ß".repeat(SNIPPET_CONTEXT * 2))
+        );
+        assert_eq!(snippet("", &matcher), "");
+    }
+}
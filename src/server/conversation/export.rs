@@ -0,0 +1,258 @@
+use chrono::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::server::conversation::model::{Conversation, Message};
+use crate::server::og::model::OG;
+
+pub const ARCHIVE_VERSION: u32 = 1;
+
+/// The JSON export format, also accepted by the import.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ConversationArchive {
+    pub version: u32,
+    pub exported_at: DateTime<Utc>,
+    pub conversations: Vec<ConversationExport>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ConversationExport {
+    pub title: String,
+    #[serde(default)]
+    pub archived: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub og: OGMetadata,
+    pub messages: Vec<MessageExport>,
+}
+
+/// The OG a conversation was about, as it was when exported.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct OGMetadata {
+    pub id: String,
+    pub title: String,
+    pub description: String,
+    pub site_name: String,
+    pub image_url: String,
+    #[serde(default)]
+    pub image_alt: String,
+    pub author: String,
+    pub locale: String,
+    pub twitter_card: String,
+    pub twitter_site: String,
+}
+
+/// A message with its id and parent kept as hex strings so alternate
+/// branches survive a round trip.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MessageExport {
+    pub id: String,
+    #[serde(default)]
+    pub parent: Option<String>,
+    pub sender: String,
+    pub content: String,
+    pub timestamp: DateTime<Utc>,
+}
+
+impl From<&OG> for OGMetadata {
+    fn from(og: &OG) -> Self {
+        Self {
+            id: og.id.to_hex(),
+            title: og.title.clone(),
+            description: og.description.clone(),
+            site_name: og.site_name.clone(),
+            image_url: og.image_url.clone(),
+            image_alt: og.image_alt.clone(),
+            author: og.author.clone(),
+            locale: og.locale.clone(),
+            twitter_card: og.twitter_card.clone(),
+            twitter_site: og.twitter_site.clone(),
+        }
+    }
+}
+
+impl ConversationExport {
+    pub fn new(conversation: &Conversation, og: OGMetadata, messages: &[Message]) -> Self {
+        Self {
+            title: conversation.title.clone(),
+            archived: conversation.archived,
+            created_at: conversation.created_at,
+            updated_at: conversation.updated_at,
+            og,
+            messages: messages
+                .iter()
+                .map(|message| MessageExport {
+                    id: message.id.to_hex(),
+                    parent: message.parent.map(|parent| parent.to_hex()),
+                    sender: message.sender.clone(),
+                    content: message.content.clone(),
+                    timestamp: message.timestamp,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Renders the archive as Markdown. Only the latest branch of each
+/// conversation is written out; the JSON export keeps every branch.
+pub fn to_markdown(archive: &ConversationArchive) -> String {
+    let mut markdown = String::new();
+    for conversation in &archive.conversations {
+        markdown.push_str(&format!("# {}\n\n", one_line(&conversation.title)));
+        markdown.push_str(&format!(
+            "- Created: {}\n- Updated: {}\n\n",
+            format_time(conversation.created_at),
+            format_time(conversation.updated_at)
+        ));
+
+        markdown.push_str("## OG\n\n| Field | Value |\n| --- | --- |\n");
+        let og = &conversation.og;
+        for (label, value) in [
+            ("og:title", &og.title),
+            ("og:description", &og.description),
+            ("og:site_name", &og.site_name),
+            ("og:image", &og.image_url),
+            ("og:image:alt", &og.image_alt),
+            ("og:locale", &og.locale),
+            ("author", &og.author),
+            ("twitter:card", &og.twitter_card),
+            ("twitter:site", &og.twitter_site),
+        ] {
+            if !value.is_empty() {
+                markdown.push_str(&format!(
+                    "| {label} | {} |\n",
+                    one_line(value).replace('|', "\\|")
+                ));
+            }
+        }
+
+        markdown.push_str("\n## Messages\n\n");
+        for message in latest_branch(&conversation.messages) {
+            let sender = if message.sender == "user" {
+                "User"
+            } else {
+                "Assistant"
+            };
+            markdown.push_str(&format!(
+                "### {sender} · {}\n\n{}\n\n",
+                format_time(message.timestamp),
+                message.content.trim()
+            ));
+        }
+        markdown.push_str("---\n\n");
+    }
+    markdown
+}
+
+fn latest_branch(messages: &[MessageExport]) -> Vec<&MessageExport> {
+    let mut branch = Vec::new();
+    let mut parent: Option<&str> = None;
+    while branch.len() < messages.len() {
+        let Some(next) = messages
+            .iter()
+            .filter(|message| message.parent.as_deref() == parent)
+            .max_by_key(|message| message.timestamp)
+        else {
+            break;
+        };
+        branch.push(next);
+        parent = Some(&next.id);
+    }
+    branch
+}
+
+/// `text` with its whitespace runs collapsed, so it fits a heading or a
+/// table cell.
+fn one_line(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn format_time(time: DateTime<Utc>) -> String {
+    time.format("%Y-%m-%d %H:%M UTC").to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{to_markdown, ConversationArchive, ConversationExport, MessageExport, OGMetadata};
+    use chrono::{Duration, TimeZone, Utc};
+
+    fn message(id: &str, parent: Option<&str>, sender: &str, minute: i64) -> MessageExport {
+        MessageExport {
+            id: id.into(),
+            parent: parent.map(Into::into),
+            sender: sender.into(),
+            content: format!("message {id}"),
+            timestamp: Utc.with_ymd_and_hms(2026, 3, 1, 9, 0, 0).unwrap()
+                + Duration::minutes(minute),
+        }
+    }
+
+    fn archive(title: &str, og: OGMetadata, messages: Vec<MessageExport>) -> ConversationArchive {
+        let created_at = Utc.with_ymd_and_hms(2026, 3, 1, 9, 0, 0).unwrap();
+        ConversationArchive {
+            version: 1,
+            exported_at: created_at,
+            conversations: vec![ConversationExport {
+                title: title.into(),
+                archived: false,
+                created_at,
+                updated_at: created_at,
+                og,
+                messages,
+            }],
+        }
+    }
+
+    #[test]
+    fn writes_role_headings_in_order() {
+        let markdown = to_markdown(&archive(
+            "Launch",
+            OGMetadata::default(),
+            vec![
+                message("b", Some("a"), "assistant", 1),
+                message("a", None, "user", 0),
+            ],
+        ));
+        let user = markdown
+            .find("### User · 2026-03-01 09:00 UTC\n\nmessage a")
+            .unwrap();
+        let assistant = markdown
+            .find("### Assistant · 2026-03-01 09:01 UTC\n\nmessage b")
+            .unwrap();
+        assert!(markdown.starts_with("# Launch\n\n- Created: 2026-03-01 09:00 UTC"));
+        assert!(user < assistant);
+    }
+
+    #[test]
+    fn follows_the_latest_branch() {
+        let markdown = to_markdown(&archive(
+            "Branches",
+            OGMetadata::default(),
+            vec![
+                message("q", None, "user", 0),
+                message("old", Some("q"), "assistant", 1),
+                message("old-follow-up", Some("old"), "user", 2),
+                message("new", Some("q"), "assistant", 3),
+                message("new-follow-up", Some("new"), "user", 4),
+            ],
+        ));
+        let order: Vec<&str> = markdown
+            .lines()
+            .filter_map(|line| line.strip_prefix("message "))
+            .collect();
+        assert_eq!(order, ["q", "new", "new-follow-up"]);
+    }
+
+    #[test]
+    fn escapes_table_cells_and_skips_empty_fields() {
+        let og = OGMetadata {
+            title: "Fast | cheap".into(),
+            description: "Two\nlines".into(),
+            ..OGMetadata::default()
+        };
+        let markdown = to_markdown(&archive("Multi\nline title", og, Vec::new()));
+        assert!(markdown.starts_with("# Multi line title\n"));
+        assert!(markdown.contains("| og:title | Fast \\| cheap |\n"));
+        assert!(markdown.contains("| og:description | Two lines |\n"));
+        assert!(!markdown.contains("og:site_name"));
+    }
+}
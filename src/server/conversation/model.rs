@@ -4,6 +4,8 @@ use bson::{oid::ObjectId, serde_helpers::chrono_datetime_as_bson_datetime};
 use chrono::prelude::*;
 use serde::{Deserialize, Serialize};
 
+use crate::server::moderation::model::ReviewStatus;
+
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct Conversation {
     #[serde(rename = "_id")]
@@ -11,6 +13,11 @@ pub struct Conversation {
     pub user: ObjectId,
     pub og: ObjectId,
     pub title: String,
+    /// Set once the user renames the conversation, so it is not auto-titled.
+    #[serde(default, rename = "customTitle")]
+    pub custom_title: bool,
+    #[serde(default)]
+    pub archived: bool,
     #[serde(with = "chrono_datetime_as_bson_datetime", rename = "createdAt")]
     pub created_at: DateTime<Utc>,
     #[serde(with = "chrono_datetime_as_bson_datetime", rename = "updatedAt")]
@@ -22,8 +29,46 @@ pub struct Message {
     #[serde(rename = "_id")]
     pub id: ObjectId,
     pub conversation: ObjectId,
+    /// The message this one follows. Editing a message or regenerating a
+    /// reply adds a sibling, so a conversation is a tree of branches.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<ObjectId>,
+    /// Marks a message that starts a thread on purpose, such as the first
+    /// query or an edit of it. Messages stored before branching existed have
+    /// neither a parent nor this flag.
+    #[serde(default)]
+    pub root: bool,
     pub sender: String,
     pub content: String,
     #[serde(with = "chrono_datetime_as_bson_datetime")]
     pub timestamp: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub edit: Option<OGEdit>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub moderation: Option<ReviewStatus>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum EditStatus {
+    Pending,
+    Accepted,
+    Rejected,
+}
+
+/// One OG field the assistant proposes to change.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct FieldChange {
+    pub field: String,
+    pub before: String,
+    pub after: String,
+}
+
+/// An edit proposed by the assistant through its `update_og` tool, waiting
+/// for the user to accept or reject it.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct OGEdit {
+    pub og: ObjectId,
+    pub changes: Vec<FieldChange>,
+    pub status: EditStatus,
 }
@@ -3,20 +3,19 @@ use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct CreateConversationRequest {
-    pub token: String,
     pub og_id: String,
     pub title: String,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct GetConversationsRequest {
-    pub token: String,
     pub og_id: String,
+    #[serde(default)]
+    pub archived: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct GetMessagesRequest {
-    pub token: String,
     pub conversation_id: ObjectId,
 }
 
@@ -27,5 +26,73 @@ pub struct SendQueryRequest {
     pub og: String,
     pub detail: String,
     pub model: String,
-    pub token: String,
+    /// Message to reply under, normally the last one shown; `None` starts at
+    /// the root. Sending an edited query under the original's parent starts a
+    /// new branch.
+    #[serde(default)]
+    pub parent: Option<ObjectId>,
+    /// Answer `parent`, an existing user message, again instead of adding
+    /// `query`.
+    #[serde(default)]
+    pub regenerate: bool,
+    /// Brand voice the reply and any proposed edit must follow.
+    #[serde(default)]
+    pub voice_id: Option<ObjectId>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ReviewEditRequest {
+    pub message_id: ObjectId,
+    pub accept: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RenameConversationRequest {
+    pub conversation_id: ObjectId,
+    pub title: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ArchiveConversationRequest {
+    pub conversation_id: ObjectId,
+    pub archived: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DeleteConversationRequest {
+    pub conversation_id: ObjectId,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TitleConversationRequest {
+    pub conversation_id: ObjectId,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SearchConversationsRequest {
+    pub og_id: String,
+    pub query: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum ExportFormat {
+    Markdown,
+    Json,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ExportConversationsRequest {
+    /// A single conversation, or every conversation of `og_id` when unset.
+    pub conversation_id: Option<ObjectId>,
+    pub og_id: String,
+    pub format: ExportFormat,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ImportConversationsRequest {
+    /// OG to attach the imported conversations to; when empty, the OG named
+    /// in the export is used if it belongs to the user.
+    pub og_id: String,
+    /// A JSON export.
+    pub data: String,
 }
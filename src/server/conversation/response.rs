@@ -25,3 +25,17 @@ pub struct MessageResponse {
     pub status: String,
     pub data: Message,
 }
+
+/// A conversation matching a search, with the best matching excerpt.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ConversationMatch {
+    pub conversation: Conversation,
+    pub snippet: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ConversationFile {
+    pub file_name: String,
+    pub mime: String,
+    pub content: String,
+}
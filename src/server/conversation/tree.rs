@@ -0,0 +1,164 @@
+use bson::oid::ObjectId;
+use std::collections::HashMap;
+
+use crate::server::conversation::model::Message;
+
+/// Messages stored before branching existed have no parent and are not
+/// marked as roots; chain them to the message before them so every
+/// conversation forms a tree.
+pub fn link_legacy(messages: &mut [Message]) {
+    messages.sort_by_key(|message| message.timestamp);
+    let mut previous = None;
+    for message in messages.iter_mut() {
+        if message.parent.is_none() && !message.root {
+            message.parent = previous;
+        }
+        previous = Some(message.id);
+    }
+}
+
+/// Replies to `parent`, oldest first.
+pub fn children(messages: &[Message], parent: Option<ObjectId>) -> Vec<&Message> {
+    let mut children: Vec<&Message> = messages
+        .iter()
+        .filter(|message| message.parent == parent)
+        .collect();
+    children.sort_by_key(|message| message.timestamp);
+    children
+}
+
+/// The thread from the root down to `leaf`, inclusive.
+pub fn path_to(messages: &[Message], leaf: Option<ObjectId>) -> Vec<&Message> {
+    let mut path = Vec::new();
+    let mut current = leaf;
+    while let Some(id) = current {
+        let Some(message) = messages.iter().find(|message| message.id == id) else {
+            break;
+        };
+        // Guards against a corrupted parent chain looping forever.
+        if path.len() >= messages.len() {
+            break;
+        }
+        path.push(message);
+        current = message.parent;
+    }
+    path.reverse();
+    path
+}
+
+/// The thread shown to the user: at each fork the branch picked in `choices`
+/// (keyed by parent), otherwise the newest one.
+pub fn active_path<'a>(
+    messages: &'a [Message],
+    choices: &HashMap<Option<ObjectId>, ObjectId>,
+) -> Vec<&'a Message> {
+    let mut path = Vec::new();
+    let mut parent = None;
+    while path.len() < messages.len() {
+        let branches = children(messages, parent);
+        let Some(next) = choices
+            .get(&parent)
+            .and_then(|id| branches.iter().find(|message| message.id == *id))
+            .or(branches.last())
+            .copied()
+        else {
+            break;
+        };
+        path.push(next);
+        parent = Some(next.id);
+    }
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{active_path, children, link_legacy, path_to};
+    use crate::server::conversation::model::Message;
+    use bson::oid::ObjectId;
+    use chrono::{Duration, TimeZone, Utc};
+    use std::collections::HashMap;
+
+    fn message(minute: i64, parent: Option<ObjectId>, root: bool) -> Message {
+        Message {
+            id: ObjectId::new(),
+            conversation: ObjectId::new(),
+            parent,
+            root,
+            sender: "user".into(),
+            content: format!("message {minute}"),
+            timestamp: Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap()
+                + Duration::minutes(minute),
+            edit: None,
+            moderation: None,
+        }
+    }
+
+    fn ids(path: &[&Message]) -> Vec<ObjectId> {
+        path.iter().map(|message| message.id).collect()
+    }
+
+    #[test]
+    fn chains_legacy_messages_by_time() {
+        let mut messages = vec![
+            message(2, None, false),
+            message(0, None, false),
+            message(1, None, false),
+        ];
+        link_legacy(&mut messages);
+
+        assert_eq!(messages[0].parent, None);
+        assert_eq!(messages[1].parent, Some(messages[0].id));
+        assert_eq!(messages[2].parent, Some(messages[1].id));
+    }
+
+    #[test]
+    fn edited_first_message_stays_a_sibling_root() {
+        let first = message(0, None, true);
+        let reply = message(1, Some(first.id), false);
+        let edit = message(2, None, true);
+        let edit_id = edit.id;
+        let mut messages = vec![first.clone(), reply.clone(), edit];
+        link_legacy(&mut messages);
+
+        let roots = children(&messages, None);
+        assert_eq!(ids(&roots), vec![first.id, edit_id]);
+        assert_eq!(
+            messages.iter().find(|m| m.id == edit_id).unwrap().parent,
+            None
+        );
+        assert_eq!(ids(&active_path(&messages, &HashMap::new())), vec![edit_id]);
+
+        let choices = HashMap::from([(None, first.id)]);
+        assert_eq!(
+            ids(&active_path(&messages, &choices)),
+            vec![first.id, reply.id]
+        );
+    }
+
+    #[test]
+    fn new_replies_keep_their_parent_after_legacy_ones() {
+        let legacy = message(0, None, false);
+        let legacy_reply = message(1, None, false);
+        let reply = message(2, Some(legacy.id), false);
+        let mut messages = vec![legacy.clone(), legacy_reply.clone(), reply.clone()];
+        link_legacy(&mut messages);
+
+        assert_eq!(messages[1].parent, Some(legacy.id));
+        assert_eq!(messages[2].parent, Some(legacy.id));
+        assert_eq!(
+            ids(&children(&messages, Some(legacy.id))),
+            vec![legacy_reply.id, reply.id]
+        );
+    }
+
+    #[test]
+    fn path_stops_on_a_parent_cycle() {
+        let mut a = message(0, None, true);
+        let b = message(1, Some(a.id), false);
+        a.parent = Some(b.id);
+        let messages = vec![a, b.clone()];
+
+        assert_eq!(path_to(&messages, Some(b.id)).len(), messages.len());
+        assert!(path_to(&messages, None).is_empty());
+    }
+}
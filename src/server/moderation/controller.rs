@@ -0,0 +1,295 @@
+#![allow(unused)]
+#![allow(dead_code)]
+
+use crate::server::auth::model::{Permission, User};
+use crate::server::common::response::SuccessResponse;
+use crate::server::conversation::model::Message;
+use crate::server::moderation::model::{
+    ContentKind, ContentSource, ModerationAction, ModerationCase, ReviewStatus, Verdict,
+    REMOVED_MESSAGE,
+};
+use crate::server::moderation::request::{ListCasesRequest, ResolveCaseRequest};
+use crate::server::og::model::OG;
+use bson::doc;
+use bson::oid::ObjectId;
+use chrono::prelude::*;
+use dioxus::prelude::*;
+
+#[cfg(feature = "server")]
+use {
+    crate::db::get_client,
+    crate::llm::estimate_tokens,
+    crate::moderation::get_moderator,
+    crate::server::auth::guard::authorize,
+    crate::server::usage::controller::store_usage,
+    crate::server::usage::model::{AIFeature, AIUsage},
+    dioxus_logger::tracing,
+    futures_util::TryStreamExt,
+    mongodb::Collection,
+    std::time::Instant,
+};
+
+const QUEUE_LIMIT: i64 = 200;
+
+#[server]
+pub async fn list_moderation_cases(
+    req: ListCasesRequest,
+) -> Result<SuccessResponse<Vec<ModerationCase>>, ServerFnError> {
+    authorize(Permission::ReviewContent).await?;
+
+    let cases: Vec<ModerationCase> = case_collection()
+        .await
+        .find(doc! { "status": bson::to_bson(&req.status)? })
+        .sort(doc! { "createdAt": -1 })
+        .limit(QUEUE_LIMIT)
+        .await?
+        .try_collect()
+        .await?;
+
+    Ok(SuccessResponse {
+        status: "success".into(),
+        data: cases,
+    })
+}
+
+/// Approves or rejects a pending case. Content is cleared for public pages
+/// once none of its cases are pending; rejecting removes a chat message's
+/// text and keeps an OG off public pages until its copy is edited.
+#[server]
+pub async fn resolve_moderation_case(
+    req: ResolveCaseRequest,
+) -> Result<SuccessResponse<ModerationCase>, ServerFnError> {
+    let reviewer = authorize(Permission::ReviewContent).await?;
+    let case_id =
+        ObjectId::parse_str(&req.case_id).map_err(|_| ServerFnError::new("Invalid case ID"))?;
+
+    let cases = case_collection().await;
+    let mut case = cases
+        .find_one(doc! { "_id": case_id })
+        .await?
+        .ok_or(ServerFnError::new("Case not found"))?;
+    if case.status != ReviewStatus::Pending {
+        return Err(ServerFnError::new("This case was already reviewed"));
+    }
+    case.status = if req.approve {
+        ReviewStatus::Approved
+    } else {
+        ReviewStatus::Rejected
+    };
+    case.reviewed_by = Some(reviewer.id);
+    case.reviewed_at = Some(Utc::now());
+    cases.replace_one(doc! { "_id": case.id }, &case).await?;
+
+    let still_pending = cases
+        .count_documents(doc! { "target": case.target, "status": "pending" })
+        .await?
+        > 0;
+    let client = get_client().await;
+    let db =
+        client.database(&std::env::var("MONGODB_DB_NAME").expect("MONGODB_DB_NAME must be set."));
+    let update = match (req.approve, case.kind) {
+        (true, _) if still_pending => None,
+        (true, _) => Some(doc! { "moderation": "approved" }),
+        (false, ContentKind::Og) => Some(doc! { "moderation": "rejected" }),
+        (false, ContentKind::Message) => {
+            Some(doc! { "moderation": "rejected", "content": REMOVED_MESSAGE })
+        }
+    };
+    if let Some(update) = update {
+        // An OG only counts as rejected while it still has the rejected copy.
+        let (collection, filter) = match case.kind {
+            ContentKind::Og => (
+                "ogs",
+                doc! { "_id": case.target, case.field.as_str(): &case.text },
+            ),
+            ContentKind::Message => ("messages", doc! { "_id": case.target }),
+        };
+        db.collection::<bson::Document>(collection)
+            .update_one(filter, doc! { "$set": update })
+            .await?;
+    }
+
+    Ok(SuccessResponse {
+        status: "success".into(),
+        data: case,
+    })
+}
+
+/// The strictest verdict on a piece of content and a case for each field
+/// that was flagged or blocked. Nothing is stored until [`Review::record`],
+/// so the cases of content that never gets saved are not left behind.
+#[cfg(feature = "server")]
+#[derive(Debug, Default)]
+pub(crate) struct Review {
+    pub verdict: Verdict,
+    cases: Vec<ModerationCase>,
+}
+
+#[cfg(feature = "server")]
+impl Review {
+    /// The moderation status to save the content with.
+    pub fn status(&self) -> Option<ReviewStatus> {
+        match self.verdict.action {
+            ModerationAction::Flag => Some(ReviewStatus::Pending),
+            _ => None,
+        }
+    }
+
+    /// Records the cases; call it once the content is saved, or when it was
+    /// blocked and will not be.
+    pub async fn record(self) {
+        record_all(vec![self]).await;
+    }
+}
+
+/// Records the cases of several reviews at once.
+#[cfg(feature = "server")]
+pub(crate) async fn record_all(reviews: Vec<Review>) {
+    let cases: Vec<ModerationCase> = reviews
+        .into_iter()
+        .flat_map(|review| review.cases)
+        .collect();
+    if cases.is_empty() {
+        return;
+    }
+    if let Err(err) = case_collection().await.insert_many(cases).await {
+        tracing::error!("failed to record moderation cases: {err}");
+    }
+}
+
+/// Classifies each `(field, text)` pair of every target in one batch and
+/// returns a review per target, in order.
+#[cfg(feature = "server")]
+pub(crate) async fn review_many(
+    user: &User,
+    kind: ContentKind,
+    source: ContentSource,
+    targets: &[(ObjectId, &[(&str, &str)])],
+) -> Vec<Review> {
+    let texts: Vec<&str> = targets
+        .iter()
+        .flat_map(|(_, fields)| fields.iter().map(|(_, text)| *text))
+        .filter(|text| !text.trim().is_empty())
+        .collect();
+    let mut verdicts = classify_for(user, &texts).await.into_iter();
+
+    let mut reviews = Vec::with_capacity(targets.len());
+    for (target, fields) in targets {
+        let mut review = Review::default();
+        for (field, text) in fields.iter() {
+            if text.trim().is_empty() {
+                continue;
+            }
+            let field_verdict = verdicts.next().unwrap_or_default();
+            if field_verdict.action == ModerationAction::Allow {
+                continue;
+            }
+            review.cases.push(ModerationCase {
+                id: ObjectId::new(),
+                user: user.id,
+                kind,
+                target: *target,
+                field: field.to_string(),
+                text: text.to_string(),
+                source,
+                action: field_verdict.action,
+                reasons: field_verdict.reasons.clone(),
+                status: match field_verdict.action {
+                    ModerationAction::Block => ReviewStatus::Rejected,
+                    _ => ReviewStatus::Pending,
+                },
+                reviewed_by: None,
+                reviewed_at: None,
+                created_at: Utc::now(),
+            });
+            review.verdict.merge(field_verdict);
+        }
+        reviews.push(review);
+    }
+    reviews
+}
+
+/// Classifies `texts` on behalf of `user`, recording the provider call, if
+/// one was made, as AI usage that counts against the user's quota.
+#[cfg(feature = "server")]
+pub(crate) async fn classify_for(user: &User, texts: &[&str]) -> Vec<Verdict> {
+    let moderator = get_moderator().await;
+    let started = Instant::now();
+    let classification = moderator.classify_many(texts).await;
+    if let Some(result) = classification.provider_result {
+        store_usage(AIUsage {
+            id: ObjectId::new(),
+            user: user.id,
+            feature: AIFeature::Moderation,
+            provider: moderator.config.provider.clone(),
+            model: moderator.config.model.clone(),
+            prompt_tokens: texts.iter().map(|text| estimate_tokens(text)).sum(),
+            completion_tokens: 0,
+            latency_ms: started.elapsed().as_millis() as u64,
+            success: result.is_ok(),
+            error: result.err(),
+            created_at: Utc::now(),
+        })
+        .await;
+    }
+    classification.verdicts
+}
+
+/// [`review_many`] for a single target.
+#[cfg(feature = "server")]
+pub(crate) async fn review_text(
+    user: &User,
+    kind: ContentKind,
+    target: ObjectId,
+    source: ContentSource,
+    fields: &[(&str, &str)],
+) -> Review {
+    review_many(user, kind, source, &[(target, fields)])
+        .await
+        .pop()
+        .unwrap_or_default()
+}
+
+/// Refuses the whole batch with the reasons if any review blocked its
+/// content, recording the blocked cases since nothing will be saved.
+#[cfg(feature = "server")]
+pub(crate) async fn refuse_blocked(reviews: Vec<Review>) -> Result<Vec<Review>, ServerFnError> {
+    let (blocked, allowed): (Vec<Review>, Vec<Review>) = reviews
+        .into_iter()
+        .partition(|review| review.verdict.action == ModerationAction::Block);
+    let Some(first) = blocked.first() else {
+        return Ok(allowed);
+    };
+    let message = format!(
+        "Blocked by moderation: {}",
+        first.verdict.reasons.join("; ")
+    );
+    record_all(blocked).await;
+    Err(ServerFnError::new(message))
+}
+
+/// [`review_text`] for text about to be saved: blocked text is refused with
+/// the reasons, and otherwise the review comes back for the caller to save
+/// the text with its [`Review::status`] and then [`Review::record`] it.
+#[cfg(feature = "server")]
+pub(crate) async fn moderate(
+    user: &User,
+    kind: ContentKind,
+    target: ObjectId,
+    source: ContentSource,
+    fields: &[(&str, &str)],
+) -> Result<Review, ServerFnError> {
+    let review = review_text(user, kind, target, source, fields).await;
+    Ok(refuse_blocked(vec![review])
+        .await?
+        .pop()
+        .unwrap_or_default())
+}
+
+#[cfg(feature = "server")]
+async fn case_collection() -> Collection<ModerationCase> {
+    let client = get_client().await;
+    client
+        .database(&std::env::var("MONGODB_DB_NAME").expect("MONGODB_DB_NAME must be set."))
+        .collection::<ModerationCase>("moderation_cases")
+}
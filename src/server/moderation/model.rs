@@ -0,0 +1,113 @@
+#![allow(non_snake_case)]
+
+use bson::{
+    oid::ObjectId,
+    serde_helpers::{chrono_datetime_as_bson_datetime, chrono_datetime_as_bson_datetime_optional},
+};
+use chrono::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Shown in place of a chat message blocked or removed by moderation.
+pub const REMOVED_MESSAGE: &str = "This message was removed by moderation.";
+
+/// Ordered from most to least permissive, so the stricter of two actions is
+/// the greater one.
+#[derive(Debug, Default, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum ModerationAction {
+    #[default]
+    Allow,
+    /// Saved and shown to its author, but held back from public pages until
+    /// reviewed.
+    Flag,
+    /// Not saved at all.
+    Block,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq)]
+pub struct Verdict {
+    pub action: ModerationAction,
+    pub reasons: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ReviewStatus {
+    Pending,
+    Approved,
+    Rejected,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ContentKind {
+    Og,
+    Message,
+}
+
+/// Who wrote the moderated text.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ContentSource {
+    User,
+    Ai,
+}
+
+/// Text that was flagged or blocked, kept for review in the
+/// `moderation_cases` collection. Blocked text is never saved, so its case
+/// starts out rejected.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct ModerationCase {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    pub user: ObjectId,
+    pub kind: ContentKind,
+    pub target: ObjectId,
+    pub field: String,
+    pub text: String,
+    pub source: ContentSource,
+    pub action: ModerationAction,
+    pub reasons: Vec<String>,
+    pub status: ReviewStatus,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "reviewedBy"
+    )]
+    pub reviewed_by: Option<ObjectId>,
+    #[serde(
+        default,
+        with = "chrono_datetime_as_bson_datetime_optional",
+        rename = "reviewedAt"
+    )]
+    pub reviewed_at: Option<DateTime<Utc>>,
+    #[serde(with = "chrono_datetime_as_bson_datetime", rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+}
+
+#[cfg(feature = "server")]
+impl Verdict {
+    pub fn flag(reason: String) -> Self {
+        Self {
+            action: ModerationAction::Flag,
+            reasons: vec![reason],
+        }
+    }
+
+    pub fn block(reason: String) -> Self {
+        Self {
+            action: ModerationAction::Block,
+            reasons: vec![reason],
+        }
+    }
+
+    /// Keeps the stricter action and every reason.
+    pub fn merge(&mut self, other: Verdict) {
+        self.action = self.action.max(other.action);
+        for reason in other.reasons {
+            if !self.reasons.contains(&reason) {
+                self.reasons.push(reason);
+            }
+        }
+    }
+}
@@ -0,0 +1,13 @@
+use crate::server::moderation::model::ReviewStatus;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ListCasesRequest {
+    pub status: ReviewStatus,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ResolveCaseRequest {
+    pub case_id: String,
+    pub approve: bool,
+}
@@ -1,4 +1,7 @@
 pub(crate) mod controller;
+#[cfg(feature = "server")]
+pub(crate) mod listing;
 pub(crate) mod model;
 pub(crate) mod request;
 pub(crate) mod response;
+pub(crate) mod score;
@@ -1,17 +1,18 @@
 #![allow(unused)]
 #![allow(dead_code)]
 
-use crate::server::auth::controller::auth;
+use crate::server::auth::model::Permission;
 use crate::server::common::response::SuccessResponse;
-use crate::server::og::model::OG;
-use crate::server::og::request::AIRequest;
+use crate::server::og::model::{card_alt_text, clean_alt_text, meta_tags, OG};
 use crate::server::og::request::GetOGForUserRequest;
-use crate::server::og::request::GetOGsForUserRequest;
+use crate::server::og::request::ListOGsRequest;
 use crate::server::og::request::StoreOGRequest;
 use crate::server::og::request::UpdateOGContentRequest;
 use crate::server::og::response::GenerateOGResponse;
 use crate::server::og::response::OGResponse;
 use crate::server::og::response::{AIUsageStats, AnalyticsData, EngagementStats, PredictiveStats};
+use crate::server::og::response::{OGListItem, OGPageResponse};
+use crate::server::og::score::{score_og, SeoScore};
 use bson::doc;
 use dioxus::prelude::*;
 use serde::{Deserialize, Serialize};
@@ -25,14 +26,29 @@ use regex::Regex;
 #[cfg(feature = "server")]
 use {
     crate::db::get_client,
-    crate::pinata::get_pinata_client,
+    crate::pinata::{is_gateway_url, pin_bytes},
+    crate::server::asset::controller::{owns_asset, record_asset},
+    crate::server::auth::guard::authorize,
+    crate::server::moderation::controller::moderate,
+    crate::server::moderation::model::{ContentKind, ContentSource},
+    crate::server::og::listing::{filter_doc, refresh_scores},
+    crate::server::search::controller::index_og,
     base64::{engine::general_purpose::STANDARD, Engine as _},
-    pinata_sdk::PinByFile,
+    dioxus_logger::tracing,
     rand::thread_rng,
     rand::Rng,
-    std::fs::write,
+    std::collections::BTreeSet,
+    std::sync::Mutex,
 };
 
+const MAX_OGS_PER_PAGE: u64 = 100;
+/// How long a rescore waits for more writes from the same user.
+const RESCORE_DELAY_SECS: u64 = 5;
+
+/// Users with a rescore waiting to run.
+#[cfg(feature = "server")]
+static PENDING_RESCORES: Mutex<BTreeSet<ObjectId>> = Mutex::new(BTreeSet::new());
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct UploadOGRequest {
     pub image_url: String,
@@ -43,9 +59,7 @@ pub async fn store_og(req: StoreOGRequest) -> Result<SuccessResponse<OGResponse>
     // HACK: Wait for image to upload
     tokio::time::sleep(tokio::time::Duration::from_secs(7)).await;
 
-    let user = auth(req.token)
-        .await
-        .map_err(|_| ServerFnError::new("Not Authenticated"))?;
+    let user = authorize(Permission::ManageOwnContent).await?;
 
     let client = get_client().await;
     let db =
@@ -53,7 +67,7 @@ pub async fn store_og(req: StoreOGRequest) -> Result<SuccessResponse<OGResponse>
     let og_collection = db.collection::<OG>("ogs");
 
     let mut cursor = og_collection
-        .find(doc! {})
+        .find(doc! { "user": user.id, "variantOf": { "$exists": false } })
         .sort(doc! { "createdAt": -1 })
         .await
         .map_err(|_| ServerFnError::new("Failed to query OG collection"))?;
@@ -61,6 +75,15 @@ pub async fn store_og(req: StoreOGRequest) -> Result<SuccessResponse<OGResponse>
     if let Some(result) = cursor.next().await {
         match result {
             Ok(mut latest_og) => {
+                // Backgrounds are fetched again on export and shown on public
+                // cards, so a new one must be an asset this user pinned
+                // through `generate_background`.
+                if !req.background_url.is_empty()
+                    && req.background_url != latest_og.background_url
+                    && !owns_asset(user.id, &req.background_url).await?
+                {
+                    return Err(ServerFnError::new("Invalid background URL"));
+                }
                 latest_og.user = user.id;
                 latest_og.title = req.title;
                 latest_og.description = req.description;
@@ -68,14 +91,43 @@ pub async fn store_og(req: StoreOGRequest) -> Result<SuccessResponse<OGResponse>
                 latest_og.author = req.author;
                 latest_og.locale = req.locale;
                 latest_og.brand_url = req.brand_url;
+                latest_og.from_color = req.from_color;
+                latest_og.to_color = req.to_color;
+                latest_og.background_url = req.background_url;
+                latest_og.keywords = req.keywords;
                 latest_og.twitter_card = req.twitter_card;
                 latest_og.twitter_site = req.twitter_site;
+                latest_og.image_alt = match clean_alt_text(&req.image_alt) {
+                    alt if alt.is_empty() => card_alt_text(
+                        &latest_og.title,
+                        &latest_og.description,
+                        &latest_og.author,
+                        &latest_og.site_name,
+                    ),
+                    alt => alt,
+                };
+                let review = moderate(
+                    &user,
+                    ContentKind::Og,
+                    latest_og.id,
+                    ContentSource::User,
+                    &[
+                        ("title", &latest_og.title),
+                        ("description", &latest_og.description),
+                    ],
+                )
+                .await?;
+                latest_og.moderation = review.status();
                 latest_og.updated_at = Utc::now();
 
                 og_collection
                     .replace_one(doc! { "_id": latest_og.id }, latest_og.clone())
                     .await
                     .map_err(|_| ServerFnError::new("Failed to update OG document"))?;
+                review.record().await;
+                index_og(&latest_og);
+                rescore(user.id);
+                let variants = og_variants(&og_collection, user.id, latest_og.group_id()).await?;
 
                 Ok(SuccessResponse {
                     status: "success".into(),
@@ -86,10 +138,12 @@ pub async fn store_og(req: StoreOGRequest) -> Result<SuccessResponse<OGResponse>
                         description: latest_og.description.clone(),
                         site_name: latest_og.site_name.clone(),
                         image_url: latest_og.image_url.clone(),
+                        image_alt: latest_og.image_alt.clone(),
                         author: latest_og.author.clone(),
                         locale: latest_og.locale.clone(),
                         twitter_card: latest_og.twitter_card.clone(),
                         twitter_site: latest_og.twitter_site.clone(),
+                        meta_tags: meta_tags(&latest_og, &variants),
                         created_at: latest_og.created_at,
                         updated_at: latest_og.updated_at,
                     },
@@ -106,14 +160,35 @@ pub async fn store_og(req: StoreOGRequest) -> Result<SuccessResponse<OGResponse>
 pub async fn update_og(
     req: UpdateOGContentRequest,
 ) -> Result<SuccessResponse<String>, ServerFnError> {
+    let user = authorize(Permission::ManageOwnContent).await?;
+
     let client = get_client().await;
     let db =
         client.database(&std::env::var("MONGODB_DB_NAME").expect("MONGODB_DB_NAME must be set."));
     let og_collection = db.collection::<OG>("ogs");
 
     let og_id = ObjectId::parse_str(&req.og_id).map_err(|_| ServerFnError::new("Invalid og ID"))?;
+    if og_collection
+        .count_documents(doc! { "_id": og_id, "user": user.id })
+        .await?
+        == 0
+    {
+        return Err(ServerFnError::new("OG not found"));
+    }
+    let fields: Vec<(&str, &str)> = [("title", &req.title), ("description", &req.description)]
+        .into_iter()
+        .filter_map(|(field, value)| Some((field, value.as_deref()?)))
+        .collect();
+    let review = moderate(&user, ContentKind::Og, og_id, ContentSource::User, &fields).await?;
 
     let mut updates = doc! {};
+    let mut removals = doc! {};
+    // New copy is judged afresh, lifting an earlier flag or rejection.
+    if review.status().is_some() {
+        updates.insert("moderation", "pending");
+    } else if !fields.is_empty() {
+        removals.insert("moderation", "");
+    }
     if let Some(title) = req.title {
         updates.insert("title", title);
     }
@@ -124,8 +199,22 @@ pub async fn update_og(
         updates.insert("siteName", site_name);
     }
     if let Some(image_url) = req.image_url {
+        // Like backgrounds, images must be ones this user pinned, or already
+        // on one of their OGs.
+        if !owns_asset(user.id, &image_url).await?
+            && (!is_gateway_url(&image_url)
+                || og_collection
+                    .count_documents(doc! { "user": user.id, "imageUrl": &image_url })
+                    .await?
+                    == 0)
+        {
+            return Err(ServerFnError::new("Invalid image URL"));
+        }
         updates.insert("imageUrl", image_url);
     }
+    if let Some(image_alt) = req.image_alt {
+        updates.insert("imageAlt", clean_alt_text(&image_alt));
+    }
     if let Some(author) = req.author {
         updates.insert("author", author);
     }
@@ -141,9 +230,24 @@ pub async fn update_og(
 
     updates.insert("updatedAt", Utc::now());
 
-    og_collection
-        .update_one(doc! { "_id": og_id }, doc! { "$set": updates })
+    let result = og_collection
+        .update_one(
+            doc! { "_id": og_id, "user": user.id },
+            if removals.is_empty() {
+                doc! { "$set": updates }
+            } else {
+                doc! { "$set": updates, "$unset": removals }
+            },
+        )
         .await?;
+    if result.matched_count == 0 {
+        return Err(ServerFnError::new("OG not found"));
+    }
+    review.record().await;
+    if let Some(og) = og_collection.find_one(doc! { "_id": og_id }).await? {
+        index_og(&og);
+    }
+    rescore(user.id);
 
     Ok(SuccessResponse {
         status: "success".into(),
@@ -152,12 +256,8 @@ pub async fn update_og(
 }
 
 #[server]
-pub async fn get_ogs_for_user(
-    req: GetOGsForUserRequest,
-) -> Result<SuccessResponse<Vec<OG>>, ServerFnError> {
-    let user = auth(req.token)
-        .await
-        .map_err(|_| ServerFnError::new("Not Authenticated"))?;
+pub async fn get_ogs_for_user() -> Result<SuccessResponse<Vec<OG>>, ServerFnError> {
+    let user = authorize(Permission::ManageOwnContent).await?;
 
     let client = get_client().await;
     let db =
@@ -180,9 +280,7 @@ pub async fn get_ogs_for_user(
 pub async fn get_og_for_user(
     req: GetOGForUserRequest,
 ) -> Result<SuccessResponse<OG>, ServerFnError> {
-    let user = auth(req.token)
-        .await
-        .map_err(|_| ServerFnError::new("Not Authenticated"))?;
+    let user = authorize(Permission::ManageOwnContent).await?;
     let client = get_client().await;
     let db =
         client.database(&std::env::var("MONGODB_DB_NAME").expect("MONGODB_DB_NAME must be set."));
@@ -201,38 +299,247 @@ pub async fn get_og_for_user(
     })
 }
 
+/// A page of the user's OGs matching `req.filters`, in `req.sort` order.
+#[server]
+pub async fn list_ogs(
+    req: ListOGsRequest,
+) -> Result<SuccessResponse<OGPageResponse>, ServerFnError> {
+    let user = authorize(Permission::ManageOwnContent).await?;
+    let client = get_client().await;
+    let db =
+        client.database(&std::env::var("MONGODB_DB_NAME").expect("MONGODB_DB_NAME must be set."));
+    let og_collection = db.collection::<OG>("ogs");
+
+    let mut filter = filter_doc(user.id, &req.filters)?;
+    if let Some(cursor) = &req.cursor {
+        filter = doc! { "$and": [filter, req.sort.after(cursor)?] };
+    }
+    let limit = req.limit.clamp(1, MAX_OGS_PER_PAGE);
+    let mut ogs: Vec<OG> = og_collection
+        .find(filter)
+        .sort(req.sort.sort_doc())
+        .limit(limit as i64 + 1)
+        .await?
+        .try_collect()
+        .await?;
+    let next_cursor = if ogs.len() as u64 > limit {
+        ogs.truncate(limit as usize);
+        ogs.last().map(|og| req.sort.cursor(og))
+    } else {
+        None
+    };
+
+    let items = ogs
+        .into_iter()
+        .map(|og| OGListItem {
+            score: og.seo_score,
+            og,
+        })
+        .collect();
+
+    Ok(SuccessResponse {
+        status: "success".into(),
+        data: OGPageResponse { items, next_cursor },
+    })
+}
+
+/// The SEO score of an OG, judged against the user's other OGs.
+#[server]
+pub async fn get_og_score(
+    req: GetOGForUserRequest,
+) -> Result<SuccessResponse<SeoScore>, ServerFnError> {
+    let user = authorize(Permission::ManageOwnContent).await?;
+    let client = get_client().await;
+    let db =
+        client.database(&std::env::var("MONGODB_DB_NAME").expect("MONGODB_DB_NAME must be set."));
+    let og_collection = db.collection::<OG>("ogs");
+
+    let og_id = ObjectId::parse_str(&req.og_id).map_err(|_| ServerFnError::new("Invalid og ID"))?;
+    let ogs: Vec<OG> = og_collection
+        .find(doc! { "user": user.id })
+        .await?
+        .try_collect()
+        .await?;
+    let og = ogs
+        .iter()
+        .find(|og| og.id == og_id)
+        .ok_or(ServerFnError::new("OG not found"))?;
+
+    Ok(SuccessResponse {
+        status: "success".into(),
+        data: score_og(og, &ogs),
+    })
+}
+
+/// Every OG in the variant group of the requested one, itself included,
+/// sorted by locale.
+#[server]
+pub async fn get_og_variants(
+    req: GetOGForUserRequest,
+) -> Result<SuccessResponse<Vec<OG>>, ServerFnError> {
+    let user = authorize(Permission::ManageOwnContent).await?;
+    let client = get_client().await;
+    let db =
+        client.database(&std::env::var("MONGODB_DB_NAME").expect("MONGODB_DB_NAME must be set."));
+    let og_collection = db.collection::<OG>("ogs");
+
+    let og_id = ObjectId::parse_str(&req.og_id).map_err(|_| ServerFnError::new("Invalid og ID"))?;
+    let og = og_collection
+        .find_one(doc! { "_id": og_id, "user": user.id })
+        .await?
+        .ok_or(ServerFnError::new("OG not found"))?;
+
+    Ok(SuccessResponse {
+        status: "success".into(),
+        data: og_variants(&og_collection, user.id, og.group_id()).await?,
+    })
+}
+
+/// The requested OG's meta tags, or `None` while moderation holds it back.
+/// Only this server function hands tags out, so held back copy cannot be
+/// published from the client.
+#[server]
+pub async fn get_og_meta_tags(
+    req: GetOGForUserRequest,
+) -> Result<SuccessResponse<Option<String>>, ServerFnError> {
+    let user = authorize(Permission::ManageOwnContent).await?;
+    let client = get_client().await;
+    let db =
+        client.database(&std::env::var("MONGODB_DB_NAME").expect("MONGODB_DB_NAME must be set."));
+    let og_collection = db.collection::<OG>("ogs");
+
+    let og_id = ObjectId::parse_str(&req.og_id).map_err(|_| ServerFnError::new("Invalid og ID"))?;
+    let og = og_collection
+        .find_one(doc! { "_id": og_id, "user": user.id })
+        .await?
+        .ok_or(ServerFnError::new("OG not found"))?;
+    let variants = og_variants(&og_collection, user.id, og.group_id()).await?;
+
+    Ok(SuccessResponse {
+        status: "success".into(),
+        data: meta_tags(&og, &variants),
+    })
+}
+
+/// Refreshes the stored scores of `user`'s OGs in the background, after one
+/// of them was written. Any OG's uniqueness can change with a write, so all
+/// of them are rescored, but writes made while a rescore waits share it
+/// instead of each starting their own.
+#[cfg(feature = "server")]
+pub(crate) fn rescore(user: ObjectId) {
+    if !claim_rescore(user) {
+        return;
+    }
+    tokio::spawn(async move {
+        tokio::time::sleep(tokio::time::Duration::from_secs(RESCORE_DELAY_SECS)).await;
+        // Released before reading, so later writes get a run of their own.
+        PENDING_RESCORES
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .remove(&user);
+        let client = get_client().await;
+        let db = client
+            .database(&std::env::var("MONGODB_DB_NAME").expect("MONGODB_DB_NAME must be set."));
+        if let Err(err) = refresh_scores(&db.collection("ogs"), user).await {
+            tracing::warn!("failed to refresh SEO scores: {err}");
+        }
+    });
+}
+
+/// Whether a rescore for `user` should be started, i.e. none is waiting.
+#[cfg(feature = "server")]
+fn claim_rescore(user: ObjectId) -> bool {
+    PENDING_RESCORES
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .insert(user)
+}
+
+/// Scores the OGs of every user with an OG that was never scored, once at
+/// startup. Returns how many scores were stored.
+#[cfg(feature = "server")]
+pub async fn backfill_scores() -> Result<usize, ServerFnError> {
+    let client = get_client().await;
+    let db =
+        client.database(&std::env::var("MONGODB_DB_NAME").expect("MONGODB_DB_NAME must be set."));
+    let og_collection = db.collection::<OG>("ogs");
+    let users = og_collection
+        .distinct("user", doc! { "seoScore": { "$exists": false } })
+        .await?;
+    // Unscored OGs read as zero, so the field is set first for the listings
+    // that sort on it; the refresh then only writes real changes.
+    og_collection
+        .update_many(
+            doc! { "seoScore": { "$exists": false } },
+            doc! { "$set": { "seoScore": 0 } },
+        )
+        .await?;
+    let mut count = 0;
+    for user in users.iter().filter_map(bson::Bson::as_object_id) {
+        count += refresh_scores(&og_collection, user).await?;
+    }
+    Ok(count)
+}
+
+#[cfg(feature = "server")]
+pub(crate) async fn og_variants(
+    og_collection: &mongodb::Collection<OG>,
+    user: ObjectId,
+    group: ObjectId,
+) -> Result<Vec<OG>, ServerFnError> {
+    Ok(og_collection
+        .find(doc! {
+            "user": user,
+            "$or": [{ "_id": group }, { "variantOf": group }],
+        })
+        .sort(doc! { "locale": 1 })
+        .await?
+        .try_collect()
+        .await?)
+}
+
 #[server]
 pub async fn upload_og(req: UploadOGRequest) -> Result<SuccessResponse<String>, ServerFnError> {
+    let user = authorize(Permission::ManageOwnContent).await?;
+
     let client = get_client().await;
     let db =
         client.database(&std::env::var("MONGODB_DB_NAME").expect("MONGODB_DB_NAME must be set."));
     let og_collection = db.collection::<OG>("ogs");
 
-    let temp_file_path = "/tmp/uploaded_image.png";
     let decoded_data = STANDARD
         .decode(&req.image_url)
         .map_err(|_| ServerFnError::new("Invalid Base64 image data"))?;
-    write(temp_file_path, decoded_data).map_err(|_| {
-        ServerFnError::new("Failed to write Base64-decoded image to temporary file")
-    })?;
-
-    let pinata = get_pinata_client().await.lock().await;
-
-    let pin_result = pinata
-        .pin_file(PinByFile::new(temp_file_path))
+    let ipfs_url = pin_bytes(&decoded_data, "png")
         .await
-        .map_err(|err| ServerFnError::new(format!("Pinata Error: {:?}", err)))?;
-
-    let ipfs_url = format!("https://gateway.pinata.cloud/ipfs/{}", pin_result.ipfs_hash);
-
-    let mut new_og = OG::default();
-    new_og.image_url = ipfs_url.clone();
+        .map_err(ServerFnError::new)?;
+    record_asset(user.id, &ipfs_url).await?;
 
-    new_og.created_at = Utc::now();
+    let new_og = OG {
+        user: user.id,
+        image_url: ipfs_url.clone(),
+        created_at: Utc::now(),
+        ..OG::default()
+    };
 
     og_collection.insert_one(new_og.clone()).await?;
+    rescore(user.id);
     Ok(SuccessResponse {
         status: "success".into(),
         data: ipfs_url,
     })
 }
+
+#[cfg(all(test, feature = "server"))]
+mod tests {
+    use super::claim_rescore;
+    use bson::oid::ObjectId;
+
+    #[test]
+    fn writes_share_a_waiting_rescore() {
+        let (user, other) = (ObjectId::new(), ObjectId::new());
+        assert!(claim_rescore(user));
+        assert!(!claim_rescore(user));
+        assert!(claim_rescore(other));
+    }
+}
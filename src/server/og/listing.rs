@@ -0,0 +1,179 @@
+use bson::oid::ObjectId;
+use bson::{doc, Bson, Document};
+use chrono::prelude::*;
+use dioxus::prelude::ServerFnError;
+use futures_util::TryStreamExt;
+
+use crate::server::og::model::OG;
+use crate::server::og::request::{OGFilters, OGSort};
+use crate::server::og::score::score_og;
+
+impl OGSort {
+    /// The field sorted on and whether it is descending. Ties are broken by
+    /// `_id` in the same direction.
+    fn key(self) -> (&'static str, bool) {
+        match self {
+            OGSort::Newest => ("createdAt", true),
+            OGSort::Oldest => ("createdAt", false),
+            OGSort::RecentlyUpdated => ("updatedAt", true),
+            OGSort::LeastRecentlyUpdated => ("updatedAt", false),
+            OGSort::TitleAsc => ("title", false),
+            OGSort::TitleDesc => ("title", true),
+            OGSort::ScoreDesc => ("seoScore", true),
+            OGSort::ScoreAsc => ("seoScore", false),
+        }
+    }
+
+    pub fn sort_doc(self) -> Document {
+        let (field, descending) = self.key();
+        let direction = if descending { -1 } else { 1 };
+        doc! { field: direction, "_id": direction }
+    }
+
+    /// Cursor resuming after `og`: its id and sort value, joined by `:`.
+    pub fn cursor(self, og: &OG) -> String {
+        let value = match self.key().0 {
+            "createdAt" => og.created_at.timestamp_millis().to_string(),
+            "updatedAt" => og.updated_at.timestamp_millis().to_string(),
+            "seoScore" => og.seo_score.to_string(),
+            _ => og.title.clone(),
+        };
+        format!("{}:{value}", og.id.to_hex())
+    }
+
+    /// Matches the OGs sorted after the one `cursor` was made from.
+    pub fn after(self, cursor: &str) -> Result<Document, ServerFnError> {
+        let invalid = || ServerFnError::new("Invalid page cursor");
+        let (id, value) = cursor.split_once(':').ok_or_else(invalid)?;
+        let id = ObjectId::parse_str(id).map_err(|_| invalid())?;
+        let (field, descending) = self.key();
+        let value = match field {
+            "title" => Bson::String(value.to_string()),
+            "seoScore" => Bson::Int64(value.parse().map_err(|_| invalid())?),
+            _ => Bson::DateTime(bson::DateTime::from_millis(
+                value.parse().map_err(|_| invalid())?,
+            )),
+        };
+        let op = if descending { "$lt" } else { "$gt" };
+        Ok(doc! { "$or": [
+            { field: { op: value.clone() } },
+            { field: value, "_id": { op: id } },
+        ]})
+    }
+}
+
+/// The MongoDB filter for `user`'s OGs matching `filters`.
+pub fn filter_doc(user: ObjectId, filters: &OGFilters) -> Result<Document, ServerFnError> {
+    let mut filter = doc! { "user": user };
+    let exact = |value: &str| doc! { "$regex": format!("^{}$", regex::escape(value.trim())), "$options": "i" };
+
+    if !filters.locale.trim().is_empty() {
+        filter.insert("locale", exact(&filters.locale));
+    }
+    if !filters.site_name.trim().is_empty() {
+        filter.insert("siteName", exact(&filters.site_name));
+    }
+    if !filters.twitter_card.trim().is_empty() {
+        filter.insert("twitterCard", filters.twitter_card.trim());
+    }
+
+    let mut created = doc! {};
+    if let Some(from) = day(&filters.created_from)? {
+        created.insert("$gte", from);
+    }
+    if let Some(to) = day(&filters.created_to)? {
+        created.insert("$lt", to + chrono::Duration::days(1));
+    }
+    if !created.is_empty() {
+        filter.insert("createdAt", created);
+    }
+
+    // Keywords are stored comma separated, so each tag must be a whole entry.
+    let tags: Vec<Document> = filters
+        .tags
+        .iter()
+        .map(|tag| tag.trim())
+        .filter(|tag| !tag.is_empty())
+        .map(|tag| {
+            doc! { "keywords": {
+                "$regex": format!(r"(^|,)\s*{}\s*(,|$)", regex::escape(tag)),
+                "$options": "i",
+            }}
+        })
+        .collect();
+    if !tags.is_empty() {
+        filter.insert("$and", tags);
+    }
+
+    Ok(filter)
+}
+
+/// Midnight UTC of a `YYYY-MM-DD` day.
+fn day(value: &str) -> Result<Option<DateTime<Utc>>, ServerFnError> {
+    if value.trim().is_empty() {
+        return Ok(None);
+    }
+    let date = NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d")
+        .map_err(|_| ServerFnError::new(format!("Invalid date \"{value}\"")))?;
+    Ok(Some(
+        date.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc(),
+    ))
+}
+
+/// Rescores every OG of `user` against the others and stores the totals that
+/// changed. Returns how many changed.
+pub async fn refresh_scores(
+    collection: &mongodb::Collection<OG>,
+    user: ObjectId,
+) -> Result<usize, ServerFnError> {
+    let ogs: Vec<OG> = collection
+        .find(doc! { "user": user })
+        .await?
+        .try_collect()
+        .await?;
+    let mut changed = 0;
+    for og in &ogs {
+        let total = score_og(og, &ogs).total;
+        if total != og.seo_score {
+            collection
+                .update_one(
+                    doc! { "_id": og.id },
+                    doc! { "$set": { "seoScore": total } },
+                )
+                .await?;
+            changed += 1;
+        }
+    }
+    Ok(changed)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::server::og::model::OG;
+    use crate::server::og::request::OGSort;
+    use bson::doc;
+    use bson::oid::ObjectId;
+
+    #[test]
+    fn score_sorts_page_on_the_stored_score() {
+        let og = OG {
+            id: ObjectId::new(),
+            seo_score: 72,
+            ..OG::default()
+        };
+        assert_eq!(
+            OGSort::ScoreDesc.sort_doc(),
+            doc! { "seoScore": -1, "_id": -1 }
+        );
+        let cursor = OGSort::ScoreDesc.cursor(&og);
+        assert_eq!(cursor, format!("{}:72", og.id.to_hex()));
+        assert_eq!(
+            OGSort::ScoreDesc.after(&cursor).unwrap(),
+            doc! { "$or": [
+                { "seoScore": { "$lt": 72_i64 } },
+                { "seoScore": 72_i64, "_id": { "$lt": og.id } },
+            ]}
+        );
+        assert!(OGSort::ScoreAsc.after("bad").is_err());
+    }
+}
@@ -4,6 +4,9 @@ use bson::{oid::ObjectId, serde_helpers::chrono_datetime_as_bson_datetime};
 use chrono::prelude::*;
 use serde::{Deserialize, Serialize};
 
+use crate::markdown::escape;
+use crate::server::moderation::model::ReviewStatus;
+
 #[derive(Debug, Deserialize, Serialize, Default, Clone)]
 pub struct OG {
     #[serde(rename = "_id")]
@@ -15,16 +18,205 @@ pub struct OG {
     pub site_name: String,
     #[serde(rename = "imageUrl")]
     pub image_url: String,
+    /// Text alternative for the card image, published as `og:image:alt` and
+    /// `twitter:image:alt`.
+    #[serde(default, rename = "imageAlt")]
+    pub image_alt: String,
     #[serde(rename = "brandUrl")]
     pub brand_url: String,
+    /// Tailwind colors of the card's background gradient, e.g. `purple-300`.
+    #[serde(default, rename = "fromColor")]
+    pub from_color: String,
+    #[serde(default, rename = "toColor")]
+    pub to_color: String,
+    /// Generated background image of the card, empty for a plain gradient.
+    #[serde(default, rename = "backgroundUrl")]
+    pub background_url: String,
     pub author: String,
     pub locale: String,
     #[serde(rename = "twitterCard")]
     pub twitter_card: String,
     #[serde(rename = "twitterSite")]
     pub twitter_site: String,
+    /// Comma separated target keywords.
+    #[serde(default)]
+    pub keywords: String,
+    /// The OG this one is a localized variant of. Variants share the layout
+    /// and images of the original and only differ in their copy and locale.
+    #[serde(default, rename = "variantOf", skip_serializing_if = "Option::is_none")]
+    pub variant_of: Option<ObjectId>,
+    /// Set once moderation flags the copy; only unset or approved OGs are
+    /// published.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub moderation: Option<ReviewStatus>,
+    /// SEO score out of 100, stored so listings can sort on it. Refreshed for
+    /// all of a user's OGs whenever one changes, as uniqueness depends on the
+    /// others.
+    #[serde(default, rename = "seoScore")]
+    pub seo_score: u32,
     #[serde(with = "chrono_datetime_as_bson_datetime", rename = "createdAt")]
     pub created_at: DateTime<Utc>,
     #[serde(with = "chrono_datetime_as_bson_datetime", rename = "updatedAt")]
     pub updated_at: DateTime<Utc>,
 }
+
+#[cfg(feature = "server")]
+impl OG {
+    /// The original of this OG's variant group; the OG itself when it is not a
+    /// variant.
+    pub fn group_id(&self) -> ObjectId {
+        self.variant_of.unwrap_or(self.id)
+    }
+}
+
+impl OG {
+    /// Whether the OG may appear on public pages: moderation never flagged it
+    /// or a reviewer approved it.
+    pub fn is_publishable(&self) -> bool {
+        matches!(self.moderation, None | Some(ReviewStatus::Approved))
+    }
+}
+
+/// The OG's meta tags, with an `og:locale:alternate` for every other
+/// publishable locale of its variant group. Every value is escaped for its
+/// attribute. `None` while moderation holds the OG back, so nothing held back
+/// is ever handed out for publishing.
+pub fn meta_tags(og: &OG, variants: &[OG]) -> Option<String> {
+    if !og.is_publishable() {
+        return None;
+    }
+    let meta = |attribute: &str, name: &str, value: &str| {
+        format!(
+            "<meta {attribute}=\"{name}\" content=\"{}\" />",
+            escape(value)
+        )
+    };
+    let mut tags = vec![
+        format!("<title>{}</title>", escape(&og.title)),
+        meta("name", "description", &og.description),
+        meta("property", "og:title", &og.title),
+        meta("property", "og:description", &og.description),
+        meta("property", "og:site_name", &og.site_name),
+        meta("property", "og:image", &og.image_url),
+        meta("property", "og:image:alt", &og.image_alt),
+        meta("property", "og:author", &og.author),
+        meta("property", "og:locale", &og.locale),
+    ];
+    for variant in variants {
+        if variant.id != og.id
+            && variant.is_publishable()
+            && !variant.locale.is_empty()
+            && variant.locale != og.locale
+        {
+            tags.push(meta("property", "og:locale:alternate", &variant.locale));
+        }
+    }
+    tags.push(meta("name", "twitter:card", &og.twitter_card));
+    tags.push(meta("name", "twitter:site", &og.twitter_site));
+    tags.push(meta("name", "twitter:image:alt", &og.image_alt));
+    Some(tags.join("\n"))
+}
+
+/// Twitter's limit for image descriptions.
+pub const IMAGE_ALT_MAX_CHARS: usize = 420;
+
+/// Alt text for the generated card built from its text and layout alone, used
+/// whenever nothing better was written.
+pub fn card_alt_text(title: &str, description: &str, author: &str, site_name: &str) -> String {
+    let mut alt = format!("Link preview card with the headline “{}”", title.trim());
+    if !description.trim().is_empty() {
+        alt.push_str(&format!(" above the text “{}”", description.trim()));
+    }
+    let byline: Vec<&str> = [author.trim(), site_name.trim()]
+        .into_iter()
+        .filter(|part| !part.is_empty())
+        .collect();
+    if !byline.is_empty() {
+        alt.push_str(&format!(", credited to {}", byline.join(" on ")));
+    }
+    alt.push_str(", with the brand logo in the top right corner.");
+    clean_alt_text(&alt)
+}
+
+/// Trims `alt`, drops wrapping quotes and cuts it to [`IMAGE_ALT_MAX_CHARS`].
+pub fn clean_alt_text(alt: &str) -> String {
+    let alt = alt.trim();
+    let alt = alt
+        .strip_prefix('"')
+        .and_then(|alt| alt.strip_suffix('"'))
+        .unwrap_or(alt)
+        .trim();
+    if alt.chars().count() <= IMAGE_ALT_MAX_CHARS {
+        return alt.to_string();
+    }
+    let cut: String = alt.chars().take(IMAGE_ALT_MAX_CHARS - 1).collect();
+    format!("{}…", cut.trim_end())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{card_alt_text, clean_alt_text, meta_tags, IMAGE_ALT_MAX_CHARS, OG};
+    use crate::server::moderation::model::ReviewStatus;
+    use bson::oid::ObjectId;
+
+    fn og(locale: &str, moderation: Option<ReviewStatus>) -> OG {
+        OG {
+            id: ObjectId::new(),
+            title: "Beans & \"more\"".into(),
+            locale: locale.into(),
+            moderation,
+            ..OG::default()
+        }
+    }
+
+    #[test]
+    fn card_alt_text_has_no_straight_quotes() {
+        let alt = card_alt_text("Fresh beans", "Roasted daily", "Ana", "Bean There");
+        assert_eq!(
+            alt,
+            "Link preview card with the headline “Fresh beans” above the text “Roasted daily”, \
+             credited to Ana on Bean There, with the brand logo in the top right corner."
+        );
+        assert!(!alt.contains('"'));
+    }
+
+    #[test]
+    fn card_alt_text_skips_empty_parts() {
+        assert_eq!(
+            card_alt_text("Hi", " ", "", ""),
+            "Link preview card with the headline “Hi”, with the brand logo in the top right corner."
+        );
+    }
+
+    #[test]
+    fn clean_alt_text_unwraps_and_cuts() {
+        assert_eq!(clean_alt_text("  \"A card\"  "), "A card");
+        let long = clean_alt_text(&"word ".repeat(200));
+        assert_eq!(long.chars().count(), IMAGE_ALT_MAX_CHARS);
+        assert!(long.ends_with('…'));
+    }
+
+    #[test]
+    fn meta_tags_are_held_back_until_publishable() {
+        assert!(meta_tags(&og("en_US", Some(ReviewStatus::Pending)), &[]).is_none());
+        assert!(meta_tags(&og("en_US", Some(ReviewStatus::Rejected)), &[]).is_none());
+        assert!(meta_tags(&og("en_US", Some(ReviewStatus::Approved)), &[]).is_some());
+    }
+
+    #[test]
+    fn meta_tags_escape_values_and_skip_held_back_variants() {
+        let original = og("en_US", None);
+        let variants = [
+            original.clone(),
+            og("fr_FR", None),
+            og("de_DE", Some(ReviewStatus::Pending)),
+        ];
+        let tags = meta_tags(&original, &variants).unwrap();
+        assert!(
+            tags.contains(r#"<meta property="og:title" content="Beans &amp; &quot;more&quot;" />"#)
+        );
+        assert!(tags.contains(r#"content="fr_FR""#));
+        assert!(!tags.contains("de_DE"));
+        assert_eq!(tags.matches("og:locale:alternate").count(), 1);
+    }
+}
@@ -2,16 +2,26 @@ use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct StoreOGRequest {
-    pub token: String,
     pub title: String,
     pub description: String,
     pub site_name: String,
     pub image_url: String,
+    /// Generated from the card when left empty.
+    #[serde(default)]
+    pub image_alt: String,
     pub brand_url: String,
+    #[serde(default)]
+    pub from_color: String,
+    #[serde(default)]
+    pub to_color: String,
+    #[serde(default)]
+    pub background_url: String,
     pub author: String,
     pub locale: String,
     pub twitter_card: String,
     pub twitter_site: String,
+    #[serde(default)]
+    pub keywords: String,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -21,6 +31,8 @@ pub struct UpdateOGContentRequest {
     pub description: Option<String>,
     pub site_name: Option<String>,
     pub image_url: Option<String>,
+    #[serde(default)]
+    pub image_alt: Option<String>,
     pub brand_url: Option<String>,
     pub author: Option<String>,
     pub locale: Option<String>,
@@ -28,19 +40,54 @@ pub struct UpdateOGContentRequest {
     pub twitter_site: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct GetOGsForUserRequest {
-    pub token: String,
-}
-
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct GetOGForUserRequest {
-    pub token: String,
     pub og_id: String,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum OGSort {
+    #[default]
+    Newest,
+    Oldest,
+    RecentlyUpdated,
+    LeastRecentlyUpdated,
+    TitleAsc,
+    TitleDesc,
+    ScoreDesc,
+    ScoreAsc,
+}
+
+/// Filters for [`crate::server::og::controller::list_ogs`]; empty fields do
+/// not filter.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+pub struct OGFilters {
+    #[serde(default)]
+    pub locale: String,
+    #[serde(default)]
+    pub site_name: String,
+    #[serde(default)]
+    pub twitter_card: String,
+    /// First creation day to include, as `YYYY-MM-DD`.
+    #[serde(default)]
+    pub created_from: String,
+    /// Last creation day to include, as `YYYY-MM-DD`.
+    #[serde(default)]
+    pub created_to: String,
+    /// Keywords the OG must all have.
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct AIRequest {
-    pub token: String,
-    pub text: String,
+pub struct ListOGsRequest {
+    /// `next_cursor` of the previous page; `None` for the first page.
+    #[serde(default)]
+    pub cursor: Option<String>,
+    pub limit: u64,
+    #[serde(default)]
+    pub sort: OGSort,
+    #[serde(default)]
+    pub filters: OGFilters,
 }
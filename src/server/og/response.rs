@@ -1,3 +1,4 @@
+use crate::server::og::model::OG;
 use bson::oid::ObjectId;
 use chrono::prelude::*;
 use serde::{Deserialize, Serialize};
@@ -10,10 +11,14 @@ pub struct OGResponse {
     pub description: String,
     pub site_name: String,
     pub image_url: String,
+    pub image_alt: String,
     pub author: String,
     pub locale: String,
     pub twitter_card: String,
     pub twitter_site: String,
+    /// Ready to publish; `None` while moderation holds the OG back.
+    #[serde(default)]
+    pub meta_tags: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -23,6 +28,20 @@ pub struct GenerateOGResponse {
     pub og: OGResponse,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OGListItem {
+    pub og: OG,
+    /// SEO score, judged against all of the user's OGs.
+    pub score: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OGPageResponse {
+    pub items: Vec<OGListItem>,
+    /// Passed back to fetch the following page; `None` on the last one.
+    pub next_cursor: Option<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct AnalyticsData {
     pub engagement: EngagementStats,
@@ -34,11 +53,15 @@ pub struct AnalyticsData {
 pub struct EngagementStats {
     pub total_ogs: u64,
     pub avg_ogs_per_og: f64,
+    /// Successful AI calls per OG the user has.
+    pub ai_requests_per_og: f64,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct AIUsageStats {
     pub total_ai_ogs: u64,
+    /// Successful AI calls of any kind.
+    pub total_requests: u64,
     pub avg_gen_time: f64,
     pub success_rate: f64,
 }
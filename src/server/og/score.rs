@@ -0,0 +1,574 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+use crate::server::og::model::OG;
+
+const TITLE_IDEAL: (usize, usize) = (30, 60);
+const TITLE_OK: (usize, usize) = (15, 70);
+const DESCRIPTION_IDEAL: (usize, usize) = (110, 160);
+const DESCRIPTION_OK: (usize, usize) = (50, 200);
+/// Card text is Tailwind's gray-900.
+const TEXT_COLOR: (u8, u8, u8) = (0x11, 0x18, 0x27);
+const CALLS_TO_ACTION: [&str; 22] = [
+    "learn",
+    "discover",
+    "get",
+    "try",
+    "start",
+    "join",
+    "sign up",
+    "subscribe",
+    "shop",
+    "buy",
+    "book",
+    "download",
+    "explore",
+    "find out",
+    "see how",
+    "read",
+    "watch",
+    "build",
+    "create",
+    "register",
+    "contact",
+    "order",
+];
+
+/// An OG's SEO score out of 100 and the factors it is made of.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+pub struct SeoScore {
+    pub total: u32,
+    pub factors: Vec<ScoreFactor>,
+}
+
+/// One explainable part of a [`SeoScore`]. `score` runs from 0 to 1 and counts
+/// for `weight` points of the total.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ScoreFactor {
+    pub name: String,
+    pub weight: u32,
+    pub score: f32,
+    pub detail: String,
+}
+
+impl ScoreFactor {
+    fn new(name: &str, weight: u32, score: f32, detail: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            weight,
+            score: score.clamp(0.0, 1.0),
+            detail: detail.into(),
+        }
+    }
+
+    pub fn points(&self) -> f32 {
+        self.score * self.weight as f32
+    }
+}
+
+/// Scores `og`. `others` are the user's other OGs, used to judge uniqueness;
+/// `og` itself and its localized variants are skipped.
+pub fn score_og(og: &OG, others: &[OG]) -> SeoScore {
+    let factors = vec![
+        keyword_factor(og),
+        readability_factor(og),
+        length_factor("Title length", &og.title, TITLE_IDEAL, TITLE_OK),
+        length_factor(
+            "Description length",
+            &og.description,
+            DESCRIPTION_IDEAL,
+            DESCRIPTION_OK,
+        ),
+        uniqueness_factor(og, others),
+        call_to_action_factor(og),
+        contrast_factor(og),
+    ];
+    let total = factors.iter().map(ScoreFactor::points).sum::<f32>().round() as u32;
+
+    SeoScore { total, factors }
+}
+
+fn keyword_factor(og: &OG) -> ScoreFactor {
+    let keywords: Vec<String> = og
+        .keywords
+        .split(',')
+        .map(|keyword| keyword.trim().to_lowercase())
+        .filter(|keyword| !keyword.is_empty())
+        .collect();
+    if keywords.is_empty() {
+        return ScoreFactor::new(
+            "Keywords",
+            20,
+            0.5,
+            "No target keywords set, so none could be checked",
+        );
+    }
+
+    let text = format!("{} {}", og.title, og.description).to_lowercase();
+    let missing: Vec<&str> = keywords
+        .iter()
+        .filter(|keyword| !text.contains(keyword.as_str()))
+        .map(String::as_str)
+        .collect();
+    let found = keywords.len() - missing.len();
+    let mut score = found as f32 / keywords.len() as f32;
+    // The main keyword belongs in the title.
+    if !og.title.to_lowercase().contains(&keywords[0]) {
+        score *= 0.8;
+    }
+
+    let detail = if missing.is_empty() {
+        format!("All {} keywords appear", keywords.len())
+    } else {
+        format!(
+            "{found} of {} keywords appear; missing: {}",
+            keywords.len(),
+            missing.join(", ")
+        )
+    };
+    ScoreFactor::new("Keywords", 20, score, detail)
+}
+
+fn readability_factor(og: &OG) -> ScoreFactor {
+    let text = format!("{}. {}", og.title.trim(), og.description.trim());
+    let words: Vec<&str> = text
+        .split_whitespace()
+        .filter(|word| word.chars().any(char::is_alphanumeric))
+        .collect();
+    if words.is_empty() {
+        return ScoreFactor::new("Readability", 15, 0.0, "No text to read");
+    }
+    let sentences = text
+        .split(['.', '!', '?'])
+        .filter(|sentence| sentence.chars().any(char::is_alphanumeric))
+        .count()
+        .max(1);
+    let words_per_sentence = words.len() as f32 / sentences as f32;
+
+    if !(og.locale.is_empty() || og.locale.starts_with("en")) {
+        // Syllable counting only works for English; judge sentence length.
+        let score = if words_per_sentence <= 20.0 {
+            1.0
+        } else {
+            20.0 / words_per_sentence
+        };
+        return ScoreFactor::new(
+            "Readability",
+            15,
+            score,
+            format!("{words_per_sentence:.0} words per sentence"),
+        );
+    }
+
+    let syllables: usize = words.iter().map(|word| syllables(word)).sum();
+    let ease =
+        206.835 - 1.015 * words_per_sentence - 84.6 * (syllables as f32 / words.len() as f32);
+    let (score, label) = match ease {
+        ease if ease >= 60.0 => (1.0, "easy to read"),
+        ease if ease >= 45.0 => (0.75, "fairly readable"),
+        ease if ease >= 30.0 => (0.5, "hard to read"),
+        _ => (0.2, "very hard to read"),
+    };
+    ScoreFactor::new(
+        "Readability",
+        15,
+        score,
+        format!("Flesch reading ease {ease:.0}, {label}"),
+    )
+}
+
+/// Vowel groups, minus a silent final "e".
+fn syllables(word: &str) -> usize {
+    let word: Vec<char> = word
+        .to_lowercase()
+        .chars()
+        .filter(char::is_ascii_alphabetic)
+        .collect();
+    let vowel = |c: &char| "aeiouy".contains(*c);
+    let mut count = 0;
+    let mut previous = false;
+    for c in &word {
+        let current = vowel(c);
+        if current && !previous {
+            count += 1;
+        }
+        previous = current;
+    }
+    if word.len() > 2 && word.ends_with(&['e']) && !word.ends_with(&['l', 'e']) {
+        count -= usize::from(count > 1);
+    }
+    count.max(1)
+}
+
+fn length_factor(name: &str, text: &str, ideal: (usize, usize), ok: (usize, usize)) -> ScoreFactor {
+    let length = text.trim().chars().count();
+    let (score, verdict) = if (ideal.0..=ideal.1).contains(&length) {
+        (1.0, "within")
+    } else if (ok.0..=ok.1).contains(&length) {
+        (0.5, "outside")
+    } else {
+        (0.0, "far outside")
+    };
+    ScoreFactor::new(
+        name,
+        15,
+        score,
+        format!(
+            "{length} characters, {verdict} the ideal {}–{}",
+            ideal.0, ideal.1
+        ),
+    )
+}
+
+fn uniqueness_factor(og: &OG, others: &[OG]) -> ScoreFactor {
+    let group = og.variant_of.unwrap_or(og.id);
+    let own = words(&format!("{} {}", og.title, og.description));
+    let closest = others
+        .iter()
+        .filter(|other| other.variant_of.unwrap_or(other.id) != group)
+        .filter(|other| !other.title.trim().is_empty())
+        .map(|other| {
+            let theirs = words(&format!("{} {}", other.title, other.description));
+            (similarity(&own, &theirs), other)
+        })
+        .max_by(|a, b| a.0.total_cmp(&b.0));
+
+    match closest {
+        None => ScoreFactor::new("Uniqueness", 15, 1.0, "No other OGs to compare with"),
+        Some((similarity, other)) => ScoreFactor::new(
+            "Uniqueness",
+            15,
+            1.0 - similarity,
+            format!(
+                "Closest is \"{}\", {:.0}% similar",
+                other.title,
+                similarity * 100.0
+            ),
+        ),
+    }
+}
+
+fn words(text: &str) -> HashSet<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| word.chars().count() > 2)
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Jaccard similarity of two word sets.
+fn similarity(a: &HashSet<String>, b: &HashSet<String>) -> f32 {
+    let union = a.union(b).count();
+    if union == 0 {
+        return 0.0;
+    }
+    a.intersection(b).count() as f32 / union as f32
+}
+
+fn call_to_action_factor(og: &OG) -> ScoreFactor {
+    let text = format!(" {} {} ", og.title, og.description).to_lowercase();
+    let pattern = Regex::new(&format!(r"\b({})\b", CALLS_TO_ACTION.join("|"))).unwrap();
+    match pattern.find(&text) {
+        Some(found) => ScoreFactor::new(
+            "Call to action",
+            10,
+            1.0,
+            format!("Invites the reader to \"{}\"", found.as_str()),
+        ),
+        None => ScoreFactor::new(
+            "Call to action",
+            10,
+            0.0,
+            "No call to action such as \"learn\", \"try\" or \"get started\"",
+        ),
+    }
+}
+
+fn contrast_factor(og: &OG) -> ScoreFactor {
+    if !og.background_url.is_empty() {
+        return ScoreFactor::new(
+            "Image text contrast",
+            10,
+            0.5,
+            "Text sits on a generated image; check the preview",
+        );
+    }
+    let stops: Option<Vec<(u8, u8, u8)>> = [&og.from_color, &og.to_color]
+        .into_iter()
+        .map(|color| {
+            tailwind_rgb(if color.is_empty() {
+                "purple-300"
+            } else {
+                color
+            })
+        })
+        .collect();
+    let Some(stops) = stops else {
+        return ScoreFactor::new(
+            "Image text contrast",
+            10,
+            0.5,
+            "Custom background colors could not be measured",
+        );
+    };
+
+    let ratio = stops
+        .into_iter()
+        .map(|stop| contrast_ratio(TEXT_COLOR, stop))
+        .fold(f32::MAX, f32::min);
+    let score = match ratio {
+        ratio if ratio >= 7.0 => 1.0,
+        ratio if ratio >= 4.5 => 0.8,
+        ratio if ratio >= 3.0 => 0.5,
+        _ => 0.0,
+    };
+    ScoreFactor::new(
+        "Image text contrast",
+        10,
+        score,
+        format!("Lowest text contrast {ratio:.1}:1; 4.5:1 is the accessible minimum"),
+    )
+}
+
+/// WCAG contrast ratio of two colors.
+fn contrast_ratio(a: (u8, u8, u8), b: (u8, u8, u8)) -> f32 {
+    let (a, b) = (luminance(a), luminance(b));
+    (a.max(b) + 0.05) / (a.min(b) + 0.05)
+}
+
+fn luminance((r, g, b): (u8, u8, u8)) -> f32 {
+    let channel = |value: u8| {
+        let value = value as f32 / 255.0;
+        if value <= 0.03928 {
+            value / 12.92
+        } else {
+            ((value + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    0.2126 * channel(r) + 0.7152 * channel(g) + 0.0722 * channel(b)
+}
+
+/// RGB of a Tailwind 2 color class such as `pink-300`.
+fn tailwind_rgb(color: &str) -> Option<(u8, u8, u8)> {
+    let hex = match color.trim() {
+        "white" => 0xFFFFFF,
+        "black" => 0x000000,
+        color => {
+            let (family, shade) = color.rsplit_once('-')?;
+            let palette: [u32; 10] = match family {
+                "gray" => [
+                    0xF9FAFB, 0xF3F4F6, 0xE5E7EB, 0xD1D5DB, 0x9CA3AF, 0x6B7280, 0x4B5563, 0x374151,
+                    0x1F2937, 0x111827,
+                ],
+                "red" => [
+                    0xFEF2F2, 0xFEE2E2, 0xFECACA, 0xFCA5A5, 0xF87171, 0xEF4444, 0xDC2626, 0xB91C1C,
+                    0x991B1B, 0x7F1D1D,
+                ],
+                "yellow" => [
+                    0xFFFBEB, 0xFEF3C7, 0xFDE68A, 0xFCD34D, 0xFBBF24, 0xF59E0B, 0xD97706, 0xB45309,
+                    0x92400E, 0x78350F,
+                ],
+                "green" => [
+                    0xECFDF5, 0xD1FAE5, 0xA7F3D0, 0x6EE7B7, 0x34D399, 0x10B981, 0x059669, 0x047857,
+                    0x065F46, 0x064E3B,
+                ],
+                "blue" => [
+                    0xEFF6FF, 0xDBEAFE, 0xBFDBFE, 0x93C5FD, 0x60A5FA, 0x3B82F6, 0x2563EB, 0x1D4ED8,
+                    0x1E40AF, 0x1E3A8A,
+                ],
+                "indigo" => [
+                    0xEEF2FF, 0xE0E7FF, 0xC7D2FE, 0xA5B4FC, 0x818CF8, 0x6366F1, 0x4F46E5, 0x4338CA,
+                    0x3730A3, 0x312E81,
+                ],
+                "purple" => [
+                    0xF5F3FF, 0xEDE9FE, 0xDDD6FE, 0xC4B5FD, 0xA78BFA, 0x8B5CF6, 0x7C3AED, 0x6D28D9,
+                    0x5B21B6, 0x4C1D95,
+                ],
+                "pink" => [
+                    0xFDF2F8, 0xFCE7F3, 0xFBCFE8, 0xF9A8D4, 0xF472B6, 0xEC4899, 0xDB2777, 0xBE185D,
+                    0x9D174D, 0x831843,
+                ],
+                _ => return None,
+            };
+            let index = match shade.parse::<u32>().ok()? {
+                50 => 0,
+                shade if shade % 100 == 0 && (100..=900).contains(&shade) => shade / 100,
+                _ => return None,
+            };
+            palette[index as usize]
+        }
+    };
+    Some(((hex >> 16) as u8, (hex >> 8) as u8, hex as u8))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bson::oid::ObjectId;
+
+    fn og(title: &str, description: &str) -> OG {
+        OG {
+            id: ObjectId::new(),
+            title: title.into(),
+            description: description.into(),
+            locale: "en_US".into(),
+            ..OG::default()
+        }
+    }
+
+    fn text_of(length: usize) -> String {
+        "a".repeat(length)
+    }
+
+    #[test]
+    fn weights_add_up_to_the_total() {
+        let score = score_og(&og("Learn to roast coffee", "Fresh beans."), &[]);
+        let weights: u32 = score.factors.iter().map(|factor| factor.weight).sum();
+        assert_eq!(weights, 100);
+        let points: f32 = score.factors.iter().map(ScoreFactor::points).sum();
+        assert_eq!(score.total, points.round() as u32);
+    }
+
+    #[test]
+    fn keywords_are_checked_against_title_and_description() {
+        let mut card = og("Coffee roasting at home", "Beans, grinders and kettles.");
+        assert_eq!(keyword_factor(&card).score, 0.5);
+
+        card.keywords = "coffee, beans".into();
+        assert_eq!(keyword_factor(&card).score, 1.0);
+
+        card.keywords = "beans, coffee".into();
+        assert_eq!(keyword_factor(&card).score, 0.8);
+
+        card.keywords = "coffee, tea".into();
+        let factor = keyword_factor(&card);
+        assert_eq!(factor.score, 0.5);
+        assert!(factor.detail.ends_with("missing: tea"));
+    }
+
+    #[test]
+    fn length_boundaries() {
+        let score = |length| length_factor("Title", &text_of(length), TITLE_IDEAL, TITLE_OK).score;
+        assert_eq!(score(14), 0.0);
+        assert_eq!(score(15), 0.5);
+        assert_eq!(score(29), 0.5);
+        assert_eq!(score(30), 1.0);
+        assert_eq!(score(60), 1.0);
+        assert_eq!(score(61), 0.5);
+        assert_eq!(score(70), 0.5);
+        assert_eq!(score(71), 0.0);
+        let padded = format!("  {}  ", text_of(30));
+        assert_eq!(
+            length_factor("Title", &padded, TITLE_IDEAL, TITLE_OK).score,
+            1.0
+        );
+    }
+
+    #[test]
+    fn readability_of_english_copy() {
+        assert_eq!(readability_factor(&og("", "")).score, 0.0);
+        assert_eq!(
+            readability_factor(&og("The cat sat.", "It was fun.")).score,
+            1.0
+        );
+        let hard = og(
+            "",
+            "Internationalization considerations necessitate comprehensive organizational \
+             reconfiguration throughout multinational institutions",
+        );
+        assert_eq!(readability_factor(&hard).score, 0.2);
+    }
+
+    #[test]
+    fn readability_elsewhere_judges_sentence_length() {
+        let mut card = og("", &vec!["Wort"; 40].join(" "));
+        card.locale = "de_DE".into();
+        assert_eq!(readability_factor(&card).score, 0.5);
+        card.description = vec!["Wort"; 20].join(" ");
+        assert_eq!(readability_factor(&card).score, 1.0);
+    }
+
+    #[test]
+    fn counts_syllables() {
+        assert_eq!(syllables("cat"), 1);
+        assert_eq!(syllables("the"), 1);
+        assert_eq!(syllables("make"), 1);
+        assert_eq!(syllables("table"), 2);
+        assert_eq!(syllables("readability"), 5);
+        assert_eq!(syllables("42"), 1);
+    }
+
+    #[test]
+    fn uniqueness_against_other_ogs() {
+        let card = og("Fresh roasted coffee", "Small batch beans");
+        assert_eq!(uniqueness_factor(&card, &[]).score, 1.0);
+
+        let copy = og("Fresh roasted coffee", "Small batch beans");
+        assert_eq!(uniqueness_factor(&card, &[copy]).score, 0.0);
+
+        let mut variant = og("Fresh roasted coffee", "Small batch beans");
+        variant.variant_of = Some(card.id);
+        let untitled = og("", "Small batch beans");
+        assert_eq!(uniqueness_factor(&card, &[variant, untitled]).score, 1.0);
+    }
+
+    #[test]
+    fn similarity_of_word_sets() {
+        assert_eq!(similarity(&words(""), &words("")), 0.0);
+        assert_eq!(words("An ox and a cow").len(), 2);
+        let score = similarity(&words("fresh coffee"), &words("coffee beans"));
+        assert!((score - 1.0 / 3.0).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn calls_to_action_match_whole_words() {
+        assert_eq!(call_to_action_factor(&og("Learn more", "")).score, 1.0);
+        assert_eq!(
+            call_to_action_factor(&og("Coffee club", "Sign up today")).score,
+            1.0
+        );
+        assert_eq!(
+            call_to_action_factor(&og("Relearning coffee", "")).score,
+            0.0
+        );
+    }
+
+    #[test]
+    fn contrast_of_the_card_background() {
+        let mut card = og("", "");
+        assert_eq!(contrast_factor(&card).score, 1.0);
+
+        card.from_color = "white".into();
+        card.to_color = "gray-400".into();
+        assert_eq!(contrast_factor(&card).score, 0.8);
+
+        card.to_color = "gray-500".into();
+        assert_eq!(contrast_factor(&card).score, 0.5);
+
+        card.to_color = "gray-600".into();
+        assert_eq!(contrast_factor(&card).score, 0.0);
+
+        card.to_color = "teal-300".into();
+        assert_eq!(contrast_factor(&card).score, 0.5);
+
+        card.to_color = "gray-900".into();
+        card.background_url = "https://gateway.pinata.cloud/ipfs/Qm1".into();
+        assert_eq!(contrast_factor(&card).score, 0.5);
+    }
+
+    #[test]
+    fn contrast_ratio_bounds() {
+        assert!((contrast_ratio((0, 0, 0), (255, 255, 255)) - 21.0).abs() < 0.01);
+        assert_eq!(contrast_ratio(TEXT_COLOR, TEXT_COLOR), 1.0);
+    }
+
+    #[test]
+    fn tailwind_colors() {
+        assert_eq!(tailwind_rgb("pink-300"), Some((0xF9, 0xA8, 0xD4)));
+        assert_eq!(tailwind_rgb("gray-50"), Some((0xF9, 0xFA, 0xFB)));
+        assert_eq!(tailwind_rgb(" white "), Some((0xFF, 0xFF, 0xFF)));
+        assert_eq!(tailwind_rgb("gray-950"), None);
+        assert_eq!(tailwind_rgb("gray-150"), None);
+        assert_eq!(tailwind_rgb("teal-300"), None);
+    }
+}
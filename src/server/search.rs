@@ -0,0 +1,6 @@
+pub(crate) mod controller;
+pub(crate) mod model;
+pub(crate) mod request;
+pub(crate) mod response;
+#[cfg(feature = "server")]
+pub(crate) mod text;
@@ -0,0 +1,543 @@
+#![allow(unused)]
+#![allow(dead_code)]
+
+use crate::server::auth::model::Permission;
+use crate::server::common::response::SuccessResponse;
+use crate::server::conversation::model::{Conversation, Message};
+use crate::server::og::model::OG;
+use crate::server::search::model::OGEmbedding;
+use crate::server::search::request::{SemanticSearchRequest, SimilarOGsRequest, TextSearchRequest};
+use crate::server::search::response::{
+    MessageTextHit, OGTextHit, SearchHit, Snippet, TextSearchResponse,
+};
+use bson::doc;
+use bson::oid::ObjectId;
+use chrono::prelude::*;
+use dioxus::prelude::*;
+
+#[cfg(feature = "server")]
+use {
+    crate::db::get_client,
+    crate::embed::{cosine_similarity, get_embedder},
+    crate::llm::estimate_tokens,
+    crate::server::auth::guard::authorize,
+    crate::server::auth::model::User,
+    crate::server::search::model::{embedding_text, og_text, text_hash},
+    crate::server::search::text::{highlight, term_matcher},
+    crate::server::usage::controller::{check_quota, store_usage},
+    crate::server::usage::model::{AIFeature, AIUsage},
+    bson::Document,
+    dioxus_logger::tracing,
+    futures_util::TryStreamExt,
+    mongodb::options::IndexOptions,
+    mongodb::Collection,
+    mongodb::IndexModel,
+    std::collections::HashMap,
+    std::time::Instant,
+    tokio::sync::OnceCell,
+};
+
+const DEFAULT_SEARCH_LIMIT: usize = 20;
+const MAX_SEARCH_LIMIT: usize = 100;
+const QUERY_MAX_CHARS: usize = 500;
+const MAX_DUPLICATES: usize = 5;
+/// Texts sent to the embedder per call while indexing.
+const EMBED_BATCH: usize = 64;
+/// Candidates `$vectorSearch` considers per result it returns.
+const VECTOR_CANDIDATES: usize = 20;
+const DEFAULT_TEXT_LIMIT: u64 = 20;
+const MAX_TEXT_LIMIT: u64 = 100;
+
+#[cfg(feature = "server")]
+static TEXT_INDEXES: OnceCell<()> = OnceCell::const_new();
+#[cfg(feature = "server")]
+static EMBEDDING_INDEXES: OnceCell<()> = OnceCell::const_new();
+
+/// The user's OGs ranked by how close their meaning is to `req.query`.
+#[server]
+pub async fn search_ogs(
+    req: SemanticSearchRequest,
+) -> Result<SuccessResponse<Vec<SearchHit>>, ServerFnError> {
+    let user = authorize(Permission::ManageOwnContent).await?;
+    let query = req.query.trim();
+    if query.is_empty() || query.chars().count() > QUERY_MAX_CHARS {
+        return Err(ServerFnError::new(format!(
+            "Search for 1 to {QUERY_MAX_CHARS} characters"
+        )));
+    }
+    let limit = req
+        .limit
+        .unwrap_or(DEFAULT_SEARCH_LIMIT)
+        .clamp(1, MAX_SEARCH_LIMIT);
+
+    let mut hits = rank(&user, query.to_string(), limit).await?;
+    hits.retain(|hit| hit.similarity > 0.0);
+
+    Ok(SuccessResponse {
+        status: "success".into(),
+        data: hits,
+    })
+}
+
+/// Saved OGs whose text is nearly identical to the one being written.
+#[server]
+pub async fn find_similar_ogs(
+    req: SimilarOGsRequest,
+) -> Result<SuccessResponse<Vec<SearchHit>>, ServerFnError> {
+    let user = authorize(Permission::ManageOwnContent).await?;
+    let text = embedding_text(&req.title, &req.description, &req.site_name, &req.keywords);
+    if text.is_empty() {
+        return Ok(SuccessResponse {
+            status: "success".into(),
+            data: Vec::new(),
+        });
+    }
+
+    let threshold = get_embedder().await.config.duplicate_threshold;
+    let mut hits = rank(&user, text, MAX_DUPLICATES).await?;
+    hits.retain(|hit| hit.similarity >= threshold);
+
+    Ok(SuccessResponse {
+        status: "success".into(),
+        data: hits,
+    })
+}
+
+/// Full-text search over the user's OGs and chat messages, most relevant
+/// first, with the matching terms highlighted.
+#[server]
+pub async fn text_search(
+    req: TextSearchRequest,
+) -> Result<SuccessResponse<TextSearchResponse>, ServerFnError> {
+    let user = authorize(Permission::ManageOwnContent).await?;
+    let query = req.query.trim();
+    if query.is_empty() {
+        return Ok(SuccessResponse {
+            status: "success".into(),
+            data: TextSearchResponse::default(),
+        });
+    }
+    if query.chars().count() > QUERY_MAX_CHARS {
+        return Err(ServerFnError::new(format!(
+            "Search for at most {QUERY_MAX_CHARS} characters"
+        )));
+    }
+    let limit = req
+        .limit
+        .unwrap_or(DEFAULT_TEXT_LIMIT)
+        .clamp(1, MAX_TEXT_LIMIT) as i64;
+
+    let client = get_client().await;
+    let db =
+        client.database(&std::env::var("MONGODB_DB_NAME").expect("MONGODB_DB_NAME must be set."));
+    ensure_text_indexes(&db).await?;
+    let matcher = term_matcher(query);
+    let snippet = |field: &str, text: &str, trim: bool| {
+        let parts = highlight(text, matcher.as_ref()?, trim)?;
+        Some(Snippet {
+            field: field.to_string(),
+            parts,
+        })
+    };
+
+    let ogs = text_matches::<OG>(
+        &db.collection("ogs"),
+        doc! { "user": user.id, "$text": { "$search": query } },
+        limit,
+    )
+    .await?
+    .into_iter()
+    .map(|(og, relevance)| OGTextHit {
+        snippets: [
+            snippet("title", &og.title, false),
+            snippet("description", &og.description, true),
+            snippet("site_name", &og.site_name, false),
+            snippet("author", &og.author, false),
+        ]
+        .into_iter()
+        .flatten()
+        .collect(),
+        og,
+        relevance,
+    })
+    .collect();
+
+    let conversations: Vec<Conversation> = db
+        .collection::<Conversation>("conversations")
+        .find(doc! { "user": user.id })
+        .await?
+        .try_collect()
+        .await?;
+    let ids: Vec<ObjectId> = conversations.iter().map(|c| c.id).collect();
+    let messages = text_matches::<Message>(
+        &db.collection("messages"),
+        doc! { "conversation": { "$in": ids }, "$text": { "$search": query } },
+        limit,
+    )
+    .await?
+    .into_iter()
+    .filter_map(|(message, relevance)| {
+        let conversation = conversations
+            .iter()
+            .find(|c| c.id == message.conversation)?
+            .clone();
+        let snippet = snippet("content", &message.content, true)
+            .map(|snippet| snippet.parts)
+            .unwrap_or_default();
+        Some(MessageTextHit {
+            message,
+            conversation,
+            relevance,
+            snippet,
+        })
+    })
+    .collect();
+
+    Ok(SuccessResponse {
+        status: "success".into(),
+        data: TextSearchResponse { ogs, messages },
+    })
+}
+
+/// Documents matching a `$text` filter with their text score, best first.
+#[cfg(feature = "server")]
+async fn text_matches<T: serde::de::DeserializeOwned>(
+    collection: &Collection<Document>,
+    filter: Document,
+    limit: i64,
+) -> Result<Vec<(T, f64)>, ServerFnError> {
+    let mut cursor = collection
+        .find(filter)
+        .projection(doc! { "relevance": { "$meta": "textScore" } })
+        .sort(doc! { "relevance": { "$meta": "textScore" } })
+        .limit(limit)
+        .await?;
+    let mut matches = Vec::new();
+    while cursor.advance().await? {
+        let document = cursor.deserialize_current()?;
+        let relevance = document.get_f64("relevance").unwrap_or_default();
+        matches.push((bson::from_document(document)?, relevance));
+    }
+    Ok(matches)
+}
+
+/// Creates the text indexes searched by [`text_search`] once per process.
+/// Titles weigh most, then descriptions, site names and authors.
+#[cfg(feature = "server")]
+async fn ensure_text_indexes(db: &mongodb::Database) -> Result<(), ServerFnError> {
+    TEXT_INDEXES
+        .get_or_try_init(|| async {
+            let og_index = IndexModel::builder()
+                .keys(doc! {
+                    "title": "text",
+                    "description": "text",
+                    "siteName": "text",
+                    "author": "text",
+                })
+                .options(
+                    IndexOptions::builder()
+                        .name("og_text".to_string())
+                        .weights(doc! { "title": 10, "description": 5, "siteName": 2, "author": 1 })
+                        .build(),
+                )
+                .build();
+            let message_index = IndexModel::builder()
+                .keys(doc! { "content": "text" })
+                .options(
+                    IndexOptions::builder()
+                        .name("message_text".to_string())
+                        .build(),
+                )
+                .build();
+            db.collection::<Document>("ogs")
+                .create_index(og_index)
+                .await?;
+            db.collection::<Document>("messages")
+                .create_index(message_index)
+                .await?;
+            Ok::<_, mongodb::error::Error>(())
+        })
+        .await
+        .map_err(|err| {
+            tracing::error!("failed to create text indexes: {err}");
+            ServerFnError::new("Search is unavailable right now")
+        })?;
+    Ok(())
+}
+
+/// The OGs of `user` most similar to `text`, best first. Only stored vectors
+/// are read: OGs are embedded when they are written, see [`index_og`].
+#[cfg(feature = "server")]
+async fn rank(user: &User, text: String, limit: usize) -> Result<Vec<SearchHit>, ServerFnError> {
+    let embedder = get_embedder().await;
+    if embedder.is_remote() {
+        check_quota(user).await?;
+    }
+
+    let client = get_client().await;
+    let db =
+        client.database(&std::env::var("MONGODB_DB_NAME").expect("MONGODB_DB_NAME must be set."));
+    let embeddings = db.collection::<OGEmbedding>("og_embeddings");
+    ensure_embedding_indexes(&embeddings).await?;
+    let query = embed(user.id, vec![text]).await?.remove(0);
+
+    let filter = doc! { "user": user.id, "model": embedder.model() };
+    let candidates: Vec<OGEmbedding> = match &embedder.config.vector_index {
+        Some(index) => {
+            let pipeline = vec![doc! { "$vectorSearch": {
+                "index": index,
+                "path": "vector",
+                "queryVector": query.iter().map(|x| *x as f64).collect::<Vec<_>>(),
+                "numCandidates": (limit * VECTOR_CANDIDATES) as i64,
+                "limit": limit as i64,
+                "filter": filter,
+            } }];
+            let documents: Vec<Document> =
+                embeddings.aggregate(pipeline).await?.try_collect().await?;
+            documents
+                .into_iter()
+                .map(bson::from_document)
+                .collect::<Result<_, _>>()?
+        }
+        None => embeddings.find(filter).await?.try_collect().await?,
+    };
+    let nearest = nearest(&query, candidates, limit);
+
+    let ids: Vec<ObjectId> = nearest.iter().map(|(og, _)| *og).collect();
+    let mut ogs: HashMap<ObjectId, OG> = db
+        .collection::<OG>("ogs")
+        .find(doc! { "_id": { "$in": ids }, "user": user.id })
+        .await?
+        .try_collect::<Vec<_>>()
+        .await?
+        .into_iter()
+        .map(|og| (og.id, og))
+        .collect();
+
+    Ok(nearest
+        .into_iter()
+        .filter_map(|(og, similarity)| {
+            Some(SearchHit {
+                og: ogs.remove(&og)?,
+                similarity,
+            })
+        })
+        .collect())
+}
+
+/// The OG ids of the `limit` embeddings closest to `query`, with their
+/// similarity, best first.
+#[cfg(feature = "server")]
+fn nearest(query: &[f32], embeddings: Vec<OGEmbedding>, limit: usize) -> Vec<(ObjectId, f32)> {
+    let mut scored: Vec<(ObjectId, f32)> = embeddings
+        .into_iter()
+        .map(|embedding| (embedding.og, cosine_similarity(query, &embedding.vector)))
+        .collect();
+    scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+    scored.truncate(limit);
+    scored
+}
+
+/// Creates the indexes `rank` reads stored vectors through, once per process.
+#[cfg(feature = "server")]
+async fn ensure_embedding_indexes(
+    collection: &Collection<OGEmbedding>,
+) -> Result<(), ServerFnError> {
+    EMBEDDING_INDEXES
+        .get_or_try_init(|| async {
+            collection
+                .create_indexes([
+                    IndexModel::builder()
+                        .keys(doc! { "user": 1, "model": 1 })
+                        .build(),
+                    IndexModel::builder().keys(doc! { "og": 1 }).build(),
+                ])
+                .await
+                .map(|_| ())
+        })
+        .await
+        .map_err(|err| {
+            tracing::error!("failed to create embedding indexes: {err}");
+            ServerFnError::new("Search is unavailable right now")
+        })?;
+    Ok(())
+}
+
+/// Embeds `og` in the background after it is written, unless its vector is
+/// already current. A failure only leaves it for the startup backfill.
+#[cfg(feature = "server")]
+pub fn index_og(og: &OG) {
+    let og = og.clone();
+    tokio::spawn(async move {
+        let client = get_client().await;
+        let db = client
+            .database(&std::env::var("MONGODB_DB_NAME").expect("MONGODB_DB_NAME must be set."));
+        let collection = db.collection::<OGEmbedding>("og_embeddings");
+        let result = async {
+            let text = og_text(&og);
+            if text.is_empty() {
+                collection.delete_many(doc! { "og": og.id }).await?;
+                return Ok(());
+            }
+            let model = get_embedder().await.model();
+            let hash = text_hash(&text);
+            let stored = collection.find_one(doc! { "og": og.id }).await?;
+            if stored
+                .as_ref()
+                .is_some_and(|e| e.model == model && e.text_hash == hash)
+            {
+                return Ok(());
+            }
+            store_embeddings(
+                og.user,
+                &collection,
+                vec![(og.id, stored.map(|e| e.id), hash, text)],
+            )
+            .await
+        }
+        .await;
+        if let Err(err) = result {
+            tracing::warn!("failed to index OG {}: {err}", og.id);
+        }
+    });
+}
+
+/// Embeds every OG whose vector is missing, outdated or from another model,
+/// and drops vectors of OGs that are gone. Returns how many were embedded.
+#[cfg(feature = "server")]
+pub async fn backfill_embeddings() -> Result<usize, ServerFnError> {
+    let client = get_client().await;
+    let db =
+        client.database(&std::env::var("MONGODB_DB_NAME").expect("MONGODB_DB_NAME must be set."));
+    let collection = db.collection::<OGEmbedding>("og_embeddings");
+    let model = get_embedder().await.model().to_string();
+
+    let mut stored: HashMap<ObjectId, OGEmbedding> = collection
+        .find(doc! {})
+        .await?
+        .try_collect::<Vec<_>>()
+        .await?
+        .into_iter()
+        .map(|embedding| (embedding.og, embedding))
+        .collect();
+    let mut stale: HashMap<ObjectId, Vec<_>> = HashMap::new();
+    let mut ogs = db.collection::<OG>("ogs").find(doc! {}).await?;
+    while let Some(og) = ogs.try_next().await? {
+        let text = og_text(&og);
+        let previous = stored.remove(&og.id);
+        if text.is_empty() {
+            continue;
+        }
+        let hash = text_hash(&text);
+        match previous {
+            Some(embedding) if embedding.model == model && embedding.text_hash == hash => {}
+            previous => {
+                stale
+                    .entry(og.user)
+                    .or_default()
+                    .push((og.id, previous.map(|e| e.id), hash, text))
+            }
+        }
+    }
+
+    let orphans: Vec<ObjectId> = stored.into_values().map(|embedding| embedding.id).collect();
+    if !orphans.is_empty() {
+        collection
+            .delete_many(doc! { "_id": { "$in": orphans } })
+            .await?;
+    }
+
+    let mut count = 0;
+    for (user, entries) in stale {
+        count += entries.len();
+        store_embeddings(user, &collection, entries).await?;
+    }
+    Ok(count)
+}
+
+/// Embeds and upserts `(og, stored id, text hash, text)` entries of `user`.
+#[cfg(feature = "server")]
+async fn store_embeddings(
+    user: ObjectId,
+    collection: &Collection<OGEmbedding>,
+    entries: Vec<(ObjectId, Option<ObjectId>, String, String)>,
+) -> Result<(), ServerFnError> {
+    let model = get_embedder().await.model().to_string();
+    for batch in entries.chunks(EMBED_BATCH) {
+        let texts = batch.iter().map(|(.., text)| text.clone()).collect();
+        let vectors = embed(user, texts).await?;
+        for ((og, id, hash, _), vector) in batch.iter().zip(vectors) {
+            let embedding = OGEmbedding {
+                id: id.unwrap_or_else(ObjectId::new),
+                og: *og,
+                user,
+                model: model.clone(),
+                text_hash: hash.clone(),
+                vector,
+                updated_at: Utc::now(),
+            };
+            collection
+                .replace_one(doc! { "_id": embedding.id }, &embedding)
+                .upsert(true)
+                .await?;
+        }
+    }
+    Ok(())
+}
+
+/// Embeds `texts`, recording the call when it goes to a remote provider.
+#[cfg(feature = "server")]
+async fn embed(user: ObjectId, texts: Vec<String>) -> Result<Vec<Vec<f32>>, ServerFnError> {
+    let embedder = get_embedder().await;
+    let started = Instant::now();
+    let result = embedder.embed(&texts).await;
+    if embedder.is_remote() {
+        store_usage(AIUsage {
+            id: ObjectId::new(),
+            user,
+            feature: AIFeature::Embedding,
+            provider: embedder.config.provider.clone(),
+            model: embedder.model().to_string(),
+            prompt_tokens: texts.iter().map(|text| estimate_tokens(text)).sum(),
+            completion_tokens: 0,
+            latency_ms: started.elapsed().as_millis() as u64,
+            success: result.is_ok(),
+            error: result.as_ref().err().map(|err| err.to_string()),
+            created_at: Utc::now(),
+        })
+        .await;
+    }
+    Ok(result?)
+}
+
+#[cfg(all(test, feature = "server"))]
+mod tests {
+    use super::nearest;
+    use crate::server::search::model::OGEmbedding;
+    use bson::oid::ObjectId;
+    use chrono::Utc;
+
+    fn embedding(vector: Vec<f32>) -> OGEmbedding {
+        OGEmbedding {
+            id: ObjectId::new(),
+            og: ObjectId::new(),
+            user: ObjectId::new(),
+            model: "test".into(),
+            text_hash: String::new(),
+            vector,
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn nearest_keeps_the_closest_first() {
+        let far = embedding(vec![0.0, 1.0]);
+        let close = embedding(vec![1.0, 0.1]);
+        let exact = embedding(vec![2.0, 0.0]);
+        let ids = [exact.og, close.og];
+
+        let ranked = nearest(&[1.0, 0.0], vec![far, close, exact], 2);
+        assert_eq!(ranked.iter().map(|(og, _)| *og).collect::<Vec<_>>(), ids);
+        assert!((ranked[0].1 - 1.0).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,48 @@
+#![allow(non_snake_case)]
+
+use bson::{oid::ObjectId, serde_helpers::chrono_datetime_as_bson_datetime};
+use chrono::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "server")]
+use crate::server::og::model::OG;
+
+/// The embedding of an OG's text, kept in the `og_embeddings` collection.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct OGEmbedding {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    pub og: ObjectId,
+    pub user: ObjectId,
+    pub model: String,
+    /// Hash of the embedded text; a mismatch means the OG changed since.
+    #[serde(rename = "textHash")]
+    pub text_hash: String,
+    pub vector: Vec<f32>,
+    #[serde(with = "chrono_datetime_as_bson_datetime", rename = "updatedAt")]
+    pub updated_at: DateTime<Utc>,
+}
+
+/// The text of an OG that is embedded and searched.
+#[cfg(feature = "server")]
+pub fn og_text(og: &OG) -> String {
+    embedding_text(&og.title, &og.description, &og.site_name, &og.keywords)
+}
+
+#[cfg(feature = "server")]
+pub fn embedding_text(title: &str, description: &str, site_name: &str, keywords: &str) -> String {
+    [title, description, site_name, keywords]
+        .iter()
+        .map(|part| part.trim())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(feature = "server")]
+pub fn text_hash(text: &str) -> String {
+    let hash = text.bytes().fold(0xcbf29ce484222325_u64, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    });
+    format!("{hash:016x}")
+}
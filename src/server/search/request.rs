@@ -0,0 +1,27 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SemanticSearchRequest {
+    pub query: String,
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+/// The text of an OG being written, checked against the user's saved ones.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SimilarOGsRequest {
+    pub title: String,
+    pub description: String,
+    #[serde(default)]
+    pub site_name: String,
+    #[serde(default)]
+    pub keywords: String,
+}
+
+/// A MongoDB `$text` query: words, `"exact phrases"` and `-excluded` words.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TextSearchRequest {
+    pub query: String,
+    #[serde(default)]
+    pub limit: Option<u64>,
+}
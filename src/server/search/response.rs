@@ -0,0 +1,45 @@
+use crate::server::conversation::model::{Conversation, Message};
+use crate::server::og::model::OG;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SearchHit {
+    pub og: OG,
+    /// Cosine similarity between the query and the OG, at most 1.
+    pub similarity: f32,
+}
+
+/// A piece of matched text; `highlight` marks a query term.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct SnippetPart {
+    pub text: String,
+    pub highlight: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Snippet {
+    pub field: String,
+    pub parts: Vec<SnippetPart>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OGTextHit {
+    pub og: OG,
+    /// MongoDB's text score; higher is more relevant.
+    pub relevance: f64,
+    pub snippets: Vec<Snippet>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MessageTextHit {
+    pub message: Message,
+    pub conversation: Conversation,
+    pub relevance: f64,
+    pub snippet: Vec<SnippetPart>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct TextSearchResponse {
+    pub ogs: Vec<OGTextHit>,
+    pub messages: Vec<MessageTextHit>,
+}
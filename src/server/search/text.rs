@@ -0,0 +1,223 @@
+use regex::{Regex, RegexBuilder};
+
+use crate::server::search::response::SnippetPart;
+
+/// Characters kept on each side of the first match in long fields.
+const SNIPPET_CONTEXT: usize = 60;
+
+/// Matches the words and phrases of a `$text` query, ignoring excluded
+/// `-words`. MongoDB stems terms, so a word also matches longer words it
+/// starts.
+pub fn term_matcher(query: &str) -> Option<Regex> {
+    // Every other segment between quotes is a phrase; an unterminated
+    // quote leaves its words as plain words.
+    let segments: Vec<&str> = query.split('"').collect();
+    let mut terms = Vec::new();
+    let mut words = Vec::new();
+    for (i, segment) in segments.iter().enumerate() {
+        if i % 2 == 1 && i + 1 < segments.len() {
+            let phrase = segment.split_whitespace().collect::<Vec<_>>().join(" ");
+            if !phrase.is_empty() {
+                terms.push(regex::escape(&phrase));
+            }
+        } else {
+            words.push(*segment);
+        }
+    }
+    terms.extend(
+        words
+            .iter()
+            .flat_map(|segment| segment.split_whitespace())
+            .filter(|word| !word.starts_with('-'))
+            .map(|word| word.trim_matches(|c: char| !c.is_alphanumeric()))
+            .filter(|word| !word.is_empty())
+            .map(|word| format!(r"{}\w*", regex::escape(word))),
+    );
+    if terms.is_empty() {
+        return None;
+    }
+    // Longest first, so a phrase wins over the words inside it.
+    terms.sort_by_key(|term| std::cmp::Reverse(term.len()));
+    RegexBuilder::new(&format!(r"\b(?:{})", terms.join("|")))
+        .case_insensitive(true)
+        .build()
+        .ok()
+}
+
+/// `text` split around the matches of `matcher`, cut down to the
+/// surroundings of the first match when `trim` is set. `None` when nothing
+/// matches.
+pub fn highlight(text: &str, matcher: &Regex, trim: bool) -> Option<Vec<SnippetPart>> {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    let first = matcher.find(&text)?;
+
+    let (mut start, mut end) = (0, text.len());
+    if trim {
+        start = text[..first.start()]
+            .char_indices()
+            .rev()
+            .nth(SNIPPET_CONTEXT - 1)
+            .map(|(i, _)| i)
+            .unwrap_or(0);
+        end = text[first.end()..]
+            .char_indices()
+            .nth(SNIPPET_CONTEXT)
+            .map(|(i, _)| first.end() + i)
+            .unwrap_or(text.len());
+    }
+
+    let mut parts = Vec::new();
+    let window = &text[start..end];
+    let mut last = 0;
+    for found in matcher.find_iter(window) {
+        push_plain(&mut parts, &window[last..found.start()]);
+        parts.push(SnippetPart {
+            text: found.as_str().to_string(),
+            highlight: true,
+        });
+        last = found.end();
+    }
+    push_plain(&mut parts, &window[last..]);
+
+    if start > 0 {
+        parts.insert(
+            0,
+            SnippetPart {
+                text: "…".into(),
+                highlight: false,
+            },
+        );
+    }
+    if end < text.len() {
+        parts.push(SnippetPart {
+            text: "…".into(),
+            highlight: false,
+        });
+    }
+    Some(parts)
+}
+
+fn push_plain(parts: &mut Vec<SnippetPart>, text: &str) {
+    if !text.is_empty() {
+        parts.push(SnippetPart {
+            text: text.to_string(),
+            highlight: false,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{highlight, term_matcher, SNIPPET_CONTEXT};
+    use crate::server::search::response::SnippetPart;
+
+    fn matches(query: &str, text: &str) -> Vec<String> {
+        term_matcher(query)
+            .map(|matcher| {
+                matcher
+                    .find_iter(text)
+                    .map(|found| found.as_str().to_string())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn rendered(parts: &[SnippetPart]) -> String {
+        parts
+            .iter()
+            .map(|part| {
+                if part.highlight {
+                    format!("[{}]", part.text)
+                } else {
+                    part.text.clone()
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn matches_words_by_prefix_and_phrases_whole() {
+        assert_eq!(
+            matches("launch", "Launching the launch pad"),
+            ["Launching", "launch"]
+        );
+        assert_eq!(
+            matches(
+                r#""launch pad" rocket"#,
+                "The launch pad holds a rocket, not a launch."
+            ),
+            ["launch pad", "rocket"]
+        );
+        assert_eq!(matches(r#""launch   pad""#, "a launch pad"), ["launch pad"]);
+        assert_eq!(matches("pad", "notepad"), Vec::<String>::new());
+    }
+
+    #[test]
+    fn keeps_the_words_of_an_unterminated_quote() {
+        assert_eq!(
+            matches(r#"rocket "launch pad"#, "rocket on the pad for launch"),
+            ["rocket", "pad", "launch"]
+        );
+        assert_eq!(matches(r#"""#, "anything"), Vec::<String>::new());
+    }
+
+    #[test]
+    fn ignores_excluded_words_and_stray_punctuation() {
+        assert_eq!(matches("rocket -launch", "rocket launch"), ["rocket"]);
+        assert!(term_matcher("-launch").is_none());
+        assert!(term_matcher("  ").is_none());
+        assert_eq!(matches(r"rocket\", r"rocket\ launch"), ["rocket"]);
+        assert_eq!(matches(r"\ (a+", "(aa"), ["aa"]);
+    }
+
+    #[test]
+    fn matches_multibyte_text() {
+        assert_eq!(matches("café", "Le Café crème"), ["Café"]);
+        assert_eq!(matches("東京", "東京タワー"), ["東京タワー"]);
+        assert_eq!(
+            matches(r#""crème brûlée""#, "une Crème Brûlée"),
+            ["Crème Brûlée"]
+        );
+    }
+
+    #[test]
+    fn highlights_every_match_in_short_text() {
+        let matcher = term_matcher("og").unwrap();
+        let parts = highlight("OG  cards\nfor   ogs", &matcher, true).unwrap();
+        assert_eq!(rendered(&parts), "[OG] cards for [ogs]");
+        assert!(highlight("nothing here", &matcher, true).is_none());
+    }
+
+    #[test]
+    fn trims_long_text_around_the_first_match_on_char_boundaries() {
+        let matcher = term_matcher("needle").unwrap();
+        let before = format!("{} ", "é".repeat(SNIPPET_CONTEXT + 5));
+        let after = format!(" {}", "ü".repeat(SNIPPET_CONTEXT + 5));
+        let text = format!("{before}needle{after}");
+
+        let parts = highlight(&text, &matcher, true).unwrap();
+        assert_eq!(parts.first().unwrap().text, "…");
+        assert_eq!(parts.last().unwrap().text, "…");
+        assert_eq!(
+            rendered(&parts[1..parts.len() - 1]),
+            format!(
+                "{} [needle] {}",
+                "é".repeat(SNIPPET_CONTEXT - 1),
+                "ü".repeat(SNIPPET_CONTEXT - 1)
+            )
+        );
+
+        let whole = highlight(&text, &matcher, false).unwrap();
+        assert_eq!(rendered(&whole), format!("{before}[needle]{after}"));
+    }
+
+    #[test]
+    fn does_not_mark_trimming_at_the_edges() {
+        let matcher = term_matcher("needle").unwrap();
+        let context = "x".repeat(SNIPPET_CONTEXT - 1);
+        let parts = highlight(&format!("{context} needle {context}"), &matcher, true).unwrap();
+        assert_eq!(rendered(&parts), format!("{context} [needle] {context}"));
+        let parts = highlight("needle first", &matcher, true).unwrap();
+        assert_eq!(rendered(&parts), "[needle] first");
+    }
+}
@@ -0,0 +1,209 @@
+#![allow(unused)]
+#![allow(dead_code)]
+
+use crate::server::auth::model::{Permission, User};
+use crate::server::common::response::SuccessResponse;
+use crate::server::template::model::{unknown_variables, PromptTemplate, TemplateVersion};
+use crate::server::template::request::{
+    DeleteTemplateRequest, RestoreTemplateRequest, SaveTemplateRequest,
+};
+use bson::doc;
+use bson::oid::ObjectId;
+use chrono::prelude::*;
+use dioxus::prelude::*;
+use futures_util::TryStreamExt;
+
+#[cfg(feature = "server")]
+use {crate::db::get_client, crate::server::auth::guard::authorize, mongodb::Collection};
+
+const NAME_MAX_CHARS: usize = 80;
+const BODY_MAX_CHARS: usize = 4_000;
+
+/// The caller's own templates followed by the global ones.
+#[server]
+pub async fn list_templates() -> Result<SuccessResponse<Vec<PromptTemplate>>, ServerFnError> {
+    let user = authorize(Permission::ManageOwnContent).await?;
+
+    let templates: Vec<PromptTemplate> = template_collection()
+        .await
+        .find(doc! { "$or": [{ "user": user.id }, { "user": { "$exists": false } }] })
+        .sort(doc! { "user": -1, "name": 1 })
+        .await?
+        .try_collect()
+        .await?;
+
+    Ok(SuccessResponse {
+        status: "success".into(),
+        data: templates,
+    })
+}
+
+/// Creates a template, or saves a new version of an existing one.
+#[server]
+pub async fn save_template(
+    req: SaveTemplateRequest,
+) -> Result<SuccessResponse<PromptTemplate>, ServerFnError> {
+    let user = authorize(Permission::ManageOwnContent).await?;
+
+    let name = req.name.trim();
+    let body = req.body.trim();
+    if name.is_empty() || name.chars().count() > NAME_MAX_CHARS {
+        return Err(ServerFnError::new(format!(
+            "Name must be between 1 and {NAME_MAX_CHARS} characters"
+        )));
+    }
+    if body.is_empty() || body.chars().count() > BODY_MAX_CHARS {
+        return Err(ServerFnError::new(format!(
+            "Prompt must be between 1 and {BODY_MAX_CHARS} characters"
+        )));
+    }
+    if let Some(variable) = unknown_variables(body).first() {
+        return Err(ServerFnError::new(format!(
+            "Unknown variable {{{variable}}}"
+        )));
+    }
+
+    let now = Utc::now();
+    let version = |number: u32| TemplateVersion {
+        version: number,
+        body: body.to_string(),
+        author: user.id,
+        created_at: now,
+    };
+
+    let template = match req.template_id {
+        None => {
+            if req.global && !user.role.can(Permission::ManageTemplates) {
+                return Err(ServerFnError::new("Not Authorized"));
+            }
+            let template = PromptTemplate {
+                id: ObjectId::new(),
+                user: (!req.global).then_some(user.id),
+                name: name.to_string(),
+                field: req.field,
+                body: body.to_string(),
+                version: 1,
+                versions: vec![version(1)],
+                created_at: now,
+                updated_at: now,
+            };
+            template_collection().await.insert_one(&template).await?;
+            template
+        }
+        Some(template_id) => {
+            let mut template = editable_template(&user, template_id).await?;
+            template.name = name.to_string();
+            template.field = req.field;
+            if template.body != body {
+                template.version += 1;
+                template.body = body.to_string();
+                template.versions.push(version(template.version));
+            }
+            template.updated_at = now;
+            template_collection()
+                .await
+                .replace_one(doc! { "_id": template.id }, &template)
+                .await?;
+            template
+        }
+    };
+
+    Ok(SuccessResponse {
+        status: "success".into(),
+        data: template,
+    })
+}
+
+/// Makes an earlier version current again by saving it as a new version, so
+/// the history is never rewritten.
+#[server]
+pub async fn restore_template_version(
+    req: RestoreTemplateRequest,
+) -> Result<SuccessResponse<PromptTemplate>, ServerFnError> {
+    let user = authorize(Permission::ManageOwnContent).await?;
+    let mut template = editable_template(&user, req.template_id).await?;
+
+    let body = template
+        .versions
+        .iter()
+        .find(|version| version.version == req.version)
+        .map(|version| version.body.clone())
+        .ok_or(ServerFnError::new("Version not found"))?;
+    if body != template.body {
+        let now = Utc::now();
+        template.version += 1;
+        template.body = body.clone();
+        template.versions.push(TemplateVersion {
+            version: template.version,
+            body,
+            author: user.id,
+            created_at: now,
+        });
+        template.updated_at = now;
+        template_collection()
+            .await
+            .replace_one(doc! { "_id": template.id }, &template)
+            .await?;
+    }
+
+    Ok(SuccessResponse {
+        status: "success".into(),
+        data: template,
+    })
+}
+
+#[server]
+pub async fn delete_template(
+    req: DeleteTemplateRequest,
+) -> Result<SuccessResponse<String>, ServerFnError> {
+    let user = authorize(Permission::ManageOwnContent).await?;
+    let template = editable_template(&user, req.template_id).await?;
+
+    template_collection()
+        .await
+        .delete_one(doc! { "_id": template.id })
+        .await?;
+
+    Ok(SuccessResponse {
+        status: "success".into(),
+        data: "Template deleted".into(),
+    })
+}
+
+/// The template the caller may use for generation: their own or a global one.
+#[cfg(feature = "server")]
+pub(crate) async fn usable_template(
+    user: &User,
+    template_id: ObjectId,
+) -> Result<PromptTemplate, ServerFnError> {
+    template_collection()
+        .await
+        .find_one(doc! {
+            "_id": template_id,
+            "$or": [{ "user": user.id }, { "user": { "$exists": false } }],
+        })
+        .await?
+        .ok_or(ServerFnError::new("Template not found"))
+}
+
+/// Owners edit their own templates; global ones need
+/// [`Permission::ManageTemplates`].
+#[cfg(feature = "server")]
+async fn editable_template(
+    user: &User,
+    template_id: ObjectId,
+) -> Result<PromptTemplate, ServerFnError> {
+    let template = usable_template(user, template_id).await?;
+    if template.is_global() && !user.role.can(Permission::ManageTemplates) {
+        return Err(ServerFnError::new("Not Authorized"));
+    }
+    Ok(template)
+}
+
+#[cfg(feature = "server")]
+async fn template_collection() -> Collection<PromptTemplate> {
+    let client = get_client().await;
+    client
+        .database(&std::env::var("MONGODB_DB_NAME").expect("MONGODB_DB_NAME must be set."))
+        .collection::<PromptTemplate>("prompt_templates")
+}
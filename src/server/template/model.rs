@@ -0,0 +1,67 @@
+#![allow(non_snake_case)]
+
+use bson::{oid::ObjectId, serde_helpers::chrono_datetime_as_bson_datetime};
+use chrono::prelude::*;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::server::ai::request::SuggestionField;
+
+/// Placeholders a template may use, filled in from the generate form.
+pub const TEMPLATE_VARIABLES: [&str; 9] = [
+    "count",
+    "title",
+    "description",
+    "site_name",
+    "site_url",
+    "author",
+    "locale",
+    "keywords",
+    "tone",
+];
+
+/// A system prompt for title or description suggestions. Templates without
+/// an owner are global and offered to everyone.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct PromptTemplate {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user: Option<ObjectId>,
+    pub name: String,
+    pub field: SuggestionField,
+    pub body: String,
+    pub version: u32,
+    /// Every saved body, oldest first; the last one is `body`.
+    pub versions: Vec<TemplateVersion>,
+    #[serde(with = "chrono_datetime_as_bson_datetime", rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+    #[serde(with = "chrono_datetime_as_bson_datetime", rename = "updatedAt")]
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct TemplateVersion {
+    pub version: u32,
+    pub body: String,
+    /// Who saved this version.
+    pub author: ObjectId,
+    #[serde(with = "chrono_datetime_as_bson_datetime", rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+}
+
+impl PromptTemplate {
+    pub fn is_global(&self) -> bool {
+        self.user.is_none()
+    }
+}
+
+/// Placeholders in `body` that are not in [`TEMPLATE_VARIABLES`].
+pub fn unknown_variables(body: &str) -> Vec<String> {
+    let placeholder = Regex::new(r"\{([a-zA-Z_]+)\}").unwrap();
+    placeholder
+        .captures_iter(body)
+        .map(|caps| caps[1].to_string())
+        .filter(|name| !TEMPLATE_VARIABLES.contains(&name.as_str()))
+        .collect()
+}
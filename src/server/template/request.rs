@@ -0,0 +1,25 @@
+use crate::server::ai::request::SuggestionField;
+use bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SaveTemplateRequest {
+    /// `None` creates a new template.
+    pub template_id: Option<ObjectId>,
+    pub name: String,
+    pub field: SuggestionField,
+    pub body: String,
+    /// Share the template with every user. Only honoured on creation.
+    pub global: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DeleteTemplateRequest {
+    pub template_id: ObjectId,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RestoreTemplateRequest {
+    pub template_id: ObjectId,
+    pub version: u32,
+}
@@ -0,0 +1,324 @@
+#![allow(unused)]
+#![allow(dead_code)]
+
+use crate::server::auth::model::{Permission, User};
+use crate::server::common::response::SuccessResponse;
+use crate::server::og::model::OG;
+use crate::server::og::response::{AIUsageStats, AnalyticsData, EngagementStats};
+use crate::server::usage::model::{AIFeature, AIUsage, Quota};
+use crate::server::usage::request::UsageReportRequest;
+use crate::server::usage::response::{
+    UsageBreakdown, UsageReportResponse, UsageResponse, UsageTotals,
+};
+use bson::oid::ObjectId;
+use bson::{doc, Bson, Document};
+use chrono::prelude::*;
+use chrono::Duration;
+use dioxus::prelude::*;
+use futures_util::TryStreamExt;
+use serde::Deserialize;
+
+#[cfg(feature = "server")]
+use {
+    crate::db::get_client,
+    crate::llm::{estimate_tokens, get_llm, ChatMessage},
+    crate::server::auth::guard::authorize,
+    dioxus_logger::tracing,
+    mongodb::Collection,
+    std::time::Instant,
+};
+
+const USAGE_WINDOW_DAYS: i64 = 30;
+const MAX_REPORT_DAYS: u32 = 365;
+const TOP_USERS: i64 = 10;
+
+#[server]
+pub async fn get_ai_usage() -> Result<SuccessResponse<UsageResponse>, ServerFnError> {
+    let user = authorize(Permission::ManageOwnContent).await?;
+
+    let client = get_client().await;
+    let db =
+        client.database(&std::env::var("MONGODB_DB_NAME").expect("MONGODB_DB_NAME must be set."));
+    let og_collection = db.collection::<OG>("ogs");
+
+    let since = Utc::now() - Duration::days(USAGE_WINDOW_DAYS);
+    let window = breakdown(
+        doc! { "user": user.id, "createdAt": { "$gte": since } },
+        None,
+        1,
+    )
+    .await?
+    .pop();
+    let total_ogs = og_collection
+        .count_documents(doc! { "user": user.id })
+        .await?;
+    let ai_usage = window.as_ref().map(stats).unwrap_or_default();
+
+    Ok(SuccessResponse {
+        status: "success".into(),
+        data: UsageResponse {
+            today: today_usage(user.id).await?,
+            quota: Quota::for_role(user.role),
+            analytics: AnalyticsData {
+                engagement: EngagementStats {
+                    total_ogs,
+                    ai_requests_per_og: if total_ogs == 0 {
+                        0.0
+                    } else {
+                        ai_usage.total_requests as f64 / total_ogs as f64
+                    },
+                    ..Default::default()
+                },
+                ai_usage,
+                predictions: Default::default(),
+            },
+        },
+    })
+}
+
+#[server]
+pub async fn ai_usage_report(
+    req: UsageReportRequest,
+) -> Result<SuccessResponse<UsageReportResponse>, ServerFnError> {
+    authorize(Permission::ViewStats).await?;
+
+    let client = get_client().await;
+    let db =
+        client.database(&std::env::var("MONGODB_DB_NAME").expect("MONGODB_DB_NAME must be set."));
+    let user_collection = db.collection::<User>("users");
+
+    let since = Utc::now() - Duration::days(req.days.clamp(1, MAX_REPORT_DAYS) as i64);
+    let filter = doc! { "createdAt": { "$gte": since } };
+    let total = breakdown(filter.clone(), None, 1).await?.pop();
+
+    let mut by_user = breakdown(filter.clone(), Some("$user"), TOP_USERS).await?;
+    let ids: Vec<ObjectId> = by_user
+        .iter()
+        .filter_map(|usage| ObjectId::parse_str(&usage.key).ok())
+        .collect();
+    let users: Vec<User> = user_collection
+        .find(doc! { "_id": { "$in": ids } })
+        .await?
+        .try_collect()
+        .await?;
+    for usage in &mut by_user {
+        if let Some(user) = users.iter().find(|user| user.id.to_hex() == usage.key) {
+            usage.key = user.email.clone();
+        }
+    }
+
+    Ok(SuccessResponse {
+        status: "success".into(),
+        data: UsageReportResponse {
+            stats: total.as_ref().map(stats).unwrap_or_default(),
+            tokens: total.map(|total| total.tokens).unwrap_or_default(),
+            by_feature: breakdown(filter.clone(), Some("$feature"), 0).await?,
+            by_model: breakdown(filter, Some("$model"), 0).await?,
+            by_user,
+        },
+    })
+}
+
+/// Refuses the call when `user` has used up today's quota.
+#[cfg(feature = "server")]
+pub(crate) async fn check_quota(user: &User) -> Result<(), ServerFnError> {
+    let quota = Quota::for_role(user.role);
+    if quota == Quota::default() {
+        return Ok(());
+    }
+
+    let today = today_usage(user.id).await?;
+    if quota.requests > 0 && today.requests >= quota.requests {
+        return Err(ServerFnError::new(format!(
+            "Daily AI limit of {} requests reached; it resets at midnight UTC",
+            quota.requests
+        )));
+    }
+    if quota.tokens > 0 && today.tokens >= quota.tokens {
+        return Err(ServerFnError::new(format!(
+            "Daily AI limit of {} tokens reached; it resets at midnight UTC",
+            quota.tokens
+        )));
+    }
+    Ok(())
+}
+
+/// [`crate::llm::Llm::complete`] for `user`, recording the call. Every call
+/// checks the quota first, so retries cannot run past it. Provider errors are
+/// logged and recorded, and the caller only gets a generic error.
+#[cfg(feature = "server")]
+pub(crate) async fn metered_complete(
+    user: &User,
+    feature: AIFeature,
+    model: Option<&str>,
+    messages: Vec<ChatMessage>,
+    temperature: f32,
+    max_tokens: u32,
+) -> Result<String, ServerFnError> {
+    check_quota(user).await?;
+    let prompt_tokens = prompt_tokens(&messages);
+    let started = Instant::now();
+    let result = get_llm()
+        .await
+        .complete(model, messages, temperature, max_tokens)
+        .await;
+    let outcome = match &result {
+        Ok(reply) => Ok(reply.as_str()),
+        Err(err) => Err(err.to_string()),
+    };
+    record_usage(user, feature, model, prompt_tokens, outcome, started).await;
+    result.map_err(|err| {
+        tracing::warn!("AI provider call failed: {err}");
+        ServerFnError::new("The AI provider could not complete the request")
+    })
+}
+
+#[cfg(feature = "server")]
+pub(crate) fn prompt_tokens(messages: &[ChatMessage]) -> u64 {
+    messages
+        .iter()
+        .map(|message| estimate_tokens(&message.content))
+        .sum()
+}
+
+/// Stores one AI call. `outcome` is the reply, or the error the call failed
+/// with. Metering never fails the call itself.
+#[cfg(feature = "server")]
+pub(crate) async fn record_usage(
+    user: &User,
+    feature: AIFeature,
+    model: Option<&str>,
+    prompt_tokens: u64,
+    outcome: Result<&str, String>,
+    started: Instant,
+) {
+    let llm = get_llm().await;
+    let usage = AIUsage {
+        id: ObjectId::new(),
+        user: user.id,
+        feature,
+        provider: llm.config.provider.clone(),
+        model: llm
+            .resolve_model(model)
+            .unwrap_or_else(|_| model.unwrap_or_default().to_string()),
+        prompt_tokens,
+        completion_tokens: outcome.as_deref().map(estimate_tokens).unwrap_or(0),
+        latency_ms: started.elapsed().as_millis() as u64,
+        success: outcome.is_ok(),
+        error: outcome.err(),
+        created_at: Utc::now(),
+    };
+
+    store_usage(usage).await;
+}
+
+/// Stores a call made outside the chat provider, such as image generation.
+#[cfg(feature = "server")]
+pub(crate) async fn store_usage(usage: AIUsage) {
+    if let Err(err) = usage_collection().await.insert_one(usage).await {
+        tracing::error!("failed to record AI usage: {err}");
+    }
+}
+
+#[cfg(feature = "server")]
+async fn usage_collection() -> Collection<AIUsage> {
+    let client = get_client().await;
+    client
+        .database(&std::env::var("MONGODB_DB_NAME").expect("MONGODB_DB_NAME must be set."))
+        .collection::<AIUsage>("ai_usage")
+}
+
+/// Successful calls and their tokens since midnight UTC.
+#[cfg(feature = "server")]
+async fn today_usage(user: ObjectId) -> Result<UsageTotals, ServerFnError> {
+    let midnight = Utc::now()
+        .date_naive()
+        .and_hms_opt(0, 0, 0)
+        .unwrap_or_default()
+        .and_utc();
+    let today = breakdown(
+        doc! { "user": user, "success": true, "createdAt": { "$gte": midnight } },
+        None,
+        1,
+    )
+    .await?
+    .pop();
+
+    Ok(today
+        .map(|today| UsageTotals {
+            requests: today.requests,
+            tokens: today.tokens,
+        })
+        .unwrap_or_default())
+}
+
+#[derive(Deserialize)]
+struct UsageGroup {
+    #[serde(rename = "_id")]
+    key: Bson,
+    requests: u64,
+    successes: u64,
+    tokens: u64,
+    latency: Option<f64>,
+}
+
+/// Usage matching `filter`, grouped by the `group` field (everything in one
+/// group when `None`) and sorted by request count. `limit` 0 keeps all groups.
+#[cfg(feature = "server")]
+async fn breakdown(
+    filter: Document,
+    group: Option<&str>,
+    limit: i64,
+) -> Result<Vec<UsageBreakdown>, ServerFnError> {
+    let mut pipeline = vec![
+        doc! { "$match": filter },
+        doc! { "$group": {
+            "_id": group.map(Bson::from).unwrap_or(Bson::Null),
+            "requests": { "$sum": 1 },
+            "successes": { "$sum": { "$cond": ["$success", 1, 0] } },
+            "tokens": { "$sum": { "$add": ["$promptTokens", "$completionTokens"] } },
+            "latency": { "$avg": "$latencyMs" },
+        } },
+        doc! { "$sort": { "requests": -1 } },
+    ];
+    if limit > 0 {
+        pipeline.push(doc! { "$limit": limit });
+    }
+
+    let groups: Vec<Document> = usage_collection()
+        .await
+        .aggregate(pipeline)
+        .await?
+        .try_collect()
+        .await?;
+    groups
+        .into_iter()
+        .map(|group| {
+            let group: UsageGroup = bson::from_document(group)?;
+            Ok(UsageBreakdown {
+                key: match group.key {
+                    Bson::String(key) => key,
+                    Bson::ObjectId(id) => id.to_hex(),
+                    _ => String::new(),
+                },
+                requests: group.requests,
+                tokens: group.tokens,
+                avg_latency_ms: group.latency.unwrap_or_default(),
+                success_rate: if group.requests == 0 {
+                    0.0
+                } else {
+                    group.successes as f64 / group.requests as f64
+                },
+            })
+        })
+        .collect()
+}
+
+fn stats(usage: &UsageBreakdown) -> AIUsageStats {
+    AIUsageStats {
+        total_requests: (usage.requests as f64 * usage.success_rate).round() as u64,
+        avg_gen_time: usage.avg_latency_ms / 1000.0,
+        success_rate: usage.success_rate,
+        ..Default::default()
+    }
+}
@@ -0,0 +1,78 @@
+#![allow(non_snake_case)]
+
+use bson::{oid::ObjectId, serde_helpers::chrono_datetime_as_bson_datetime};
+use chrono::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "server")]
+use crate::server::auth::model::Role;
+
+/// What an AI call was made for.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AIFeature {
+    Suggestions,
+    Chat,
+    ConversationTitle,
+    AltText,
+    Translation,
+    BackgroundImage,
+    Embedding,
+    Moderation,
+}
+
+/// One call to the AI provider.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct AIUsage {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    pub user: ObjectId,
+    pub feature: AIFeature,
+    pub provider: String,
+    pub model: String,
+    #[serde(rename = "promptTokens")]
+    pub prompt_tokens: u64,
+    #[serde(rename = "completionTokens")]
+    pub completion_tokens: u64,
+    #[serde(rename = "latencyMs")]
+    pub latency_ms: u64,
+    pub success: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(with = "chrono_datetime_as_bson_datetime", rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+}
+
+/// Daily allowance of successful AI calls and estimated tokens. Zero means
+/// unlimited.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Default)]
+pub struct Quota {
+    pub requests: u64,
+    pub tokens: u64,
+}
+
+#[cfg(feature = "server")]
+impl Quota {
+    /// The quota for `role`, read from `AI_QUOTA_<ROLE>_REQUESTS` and
+    /// `AI_QUOTA_<ROLE>_TOKENS`.
+    pub fn for_role(role: Role) -> Self {
+        let (requests, tokens) = match role {
+            Role::User => (100, 100_000),
+            Role::Support => (500, 500_000),
+            Role::Admin => (0, 0),
+        };
+        let limit = |kind: &str, default: u64| {
+            std::env::var(format!(
+                "AI_QUOTA_{}_{kind}",
+                role.to_string().to_uppercase()
+            ))
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(default)
+        };
+        Self {
+            requests: limit("REQUESTS", requests),
+            tokens: limit("TOKENS", tokens),
+        }
+    }
+}
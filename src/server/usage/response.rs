@@ -0,0 +1,36 @@
+use crate::server::og::response::{AIUsageStats, AnalyticsData};
+use crate::server::usage::model::Quota;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct UsageTotals {
+    pub requests: u64,
+    pub tokens: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct UsageResponse {
+    /// Successful calls since midnight UTC, counted against `quota`.
+    pub today: UsageTotals,
+    pub quota: Quota,
+    pub analytics: AnalyticsData,
+}
+
+/// Usage grouped by feature, model or user.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct UsageBreakdown {
+    pub key: String,
+    pub requests: u64,
+    pub tokens: u64,
+    pub avg_latency_ms: f64,
+    pub success_rate: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct UsageReportResponse {
+    pub stats: AIUsageStats,
+    pub tokens: u64,
+    pub by_feature: Vec<UsageBreakdown>,
+    pub by_model: Vec<UsageBreakdown>,
+    pub by_user: Vec<UsageBreakdown>,
+}
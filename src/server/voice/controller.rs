@@ -0,0 +1,143 @@
+#![allow(unused)]
+#![allow(dead_code)]
+
+use crate::server::auth::model::{Permission, User};
+use crate::server::common::response::SuccessResponse;
+use crate::server::voice::model::BrandVoice;
+use crate::server::voice::request::{DeleteVoiceRequest, SaveVoiceRequest};
+use bson::doc;
+use bson::oid::ObjectId;
+use chrono::prelude::*;
+use dioxus::prelude::*;
+use futures_util::TryStreamExt;
+
+#[cfg(feature = "server")]
+use {crate::db::get_client, crate::server::auth::guard::authorize, mongodb::Collection};
+
+const NAME_MAX_CHARS: usize = 80;
+const MAX_TERMS: usize = 100;
+const EXAMPLE_MAX_CHARS: usize = 2_000;
+
+#[server]
+pub async fn list_voices() -> Result<SuccessResponse<Vec<BrandVoice>>, ServerFnError> {
+    let user = authorize(Permission::ManageOwnContent).await?;
+
+    let voices: Vec<BrandVoice> = voice_collection()
+        .await
+        .find(doc! { "user": user.id })
+        .sort(doc! { "name": 1 })
+        .await?
+        .try_collect()
+        .await?;
+
+    Ok(SuccessResponse {
+        status: "success".into(),
+        data: voices,
+    })
+}
+
+#[server]
+pub async fn save_voice(
+    req: SaveVoiceRequest,
+) -> Result<SuccessResponse<BrandVoice>, ServerFnError> {
+    let user = authorize(Permission::ManageOwnContent).await?;
+
+    let name = req.name.trim();
+    if name.is_empty() || name.chars().count() > NAME_MAX_CHARS {
+        return Err(ServerFnError::new(format!(
+            "Name must be between 1 and {NAME_MAX_CHARS} characters"
+        )));
+    }
+    if req.banned_words.len() > MAX_TERMS || req.preferred_terms.len() > MAX_TERMS {
+        return Err(ServerFnError::new(format!(
+            "Use at most {MAX_TERMS} banned words and preferred terms"
+        )));
+    }
+    if req.example_copy.chars().count() > EXAMPLE_MAX_CHARS {
+        return Err(ServerFnError::new(format!(
+            "Example copy must be at most {EXAMPLE_MAX_CHARS} characters"
+        )));
+    }
+    let clean = |terms: &[String]| -> Vec<String> {
+        terms
+            .iter()
+            .map(|term| term.trim().to_string())
+            .filter(|term| !term.is_empty())
+            .collect()
+    };
+
+    let now = Utc::now();
+    let mut voice = match req.voice_id {
+        Some(voice_id) => owned_voice(&user, voice_id).await?,
+        None => BrandVoice {
+            id: ObjectId::new(),
+            user: user.id,
+            name: String::new(),
+            tone: String::new(),
+            banned_words: Vec::new(),
+            preferred_terms: Vec::new(),
+            example_copy: String::new(),
+            max_title_chars: 0,
+            max_description_chars: 0,
+            created_at: now,
+            updated_at: now,
+        },
+    };
+    voice.name = name.to_string();
+    voice.tone = req.tone.trim().to_string();
+    voice.banned_words = clean(&req.banned_words);
+    voice.preferred_terms = clean(&req.preferred_terms);
+    voice.example_copy = req.example_copy.trim().to_string();
+    voice.max_title_chars = req.max_title_chars;
+    voice.max_description_chars = req.max_description_chars;
+    voice.updated_at = now;
+
+    voice_collection()
+        .await
+        .replace_one(doc! { "_id": voice.id }, &voice)
+        .upsert(true)
+        .await?;
+
+    Ok(SuccessResponse {
+        status: "success".into(),
+        data: voice,
+    })
+}
+
+#[server]
+pub async fn delete_voice(
+    req: DeleteVoiceRequest,
+) -> Result<SuccessResponse<String>, ServerFnError> {
+    let user = authorize(Permission::ManageOwnContent).await?;
+    let voice = owned_voice(&user, req.voice_id).await?;
+
+    voice_collection()
+        .await
+        .delete_one(doc! { "_id": voice.id })
+        .await?;
+
+    Ok(SuccessResponse {
+        status: "success".into(),
+        data: "Brand voice deleted".into(),
+    })
+}
+
+#[cfg(feature = "server")]
+pub(crate) async fn owned_voice(
+    user: &User,
+    voice_id: ObjectId,
+) -> Result<BrandVoice, ServerFnError> {
+    voice_collection()
+        .await
+        .find_one(doc! { "_id": voice_id, "user": user.id })
+        .await?
+        .ok_or(ServerFnError::new("Brand voice not found"))
+}
+
+#[cfg(feature = "server")]
+async fn voice_collection() -> Collection<BrandVoice> {
+    let client = get_client().await;
+    client
+        .database(&std::env::var("MONGODB_DB_NAME").expect("MONGODB_DB_NAME must be set."))
+        .collection::<BrandVoice>("brand_voices")
+}
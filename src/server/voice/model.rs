@@ -0,0 +1,152 @@
+#![allow(non_snake_case)]
+
+use bson::{oid::ObjectId, serde_helpers::chrono_datetime_as_bson_datetime};
+use chrono::prelude::*;
+#[cfg(feature = "server")]
+use regex::{escape, Regex};
+use serde::{Deserialize, Serialize};
+
+/// Extra attempts when generated copy breaks a voice's hard rules.
+#[cfg(feature = "server")]
+pub const VOICE_RETRIES: usize = 2;
+
+/// How a user's brand sounds. Tone, preferred terms and examples steer the
+/// model; banned words and maximum lengths are checked after generation.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct BrandVoice {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    pub user: ObjectId,
+    pub name: String,
+    pub tone: String,
+    #[serde(rename = "bannedWords")]
+    pub banned_words: Vec<String>,
+    #[serde(rename = "preferredTerms")]
+    pub preferred_terms: Vec<String>,
+    #[serde(rename = "exampleCopy")]
+    pub example_copy: String,
+    /// Zero means no limit.
+    #[serde(rename = "maxTitleChars")]
+    pub max_title_chars: u32,
+    /// Zero means no limit.
+    #[serde(rename = "maxDescriptionChars")]
+    pub max_description_chars: u32,
+    #[serde(with = "chrono_datetime_as_bson_datetime", rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+    #[serde(with = "chrono_datetime_as_bson_datetime", rename = "updatedAt")]
+    pub updated_at: DateTime<Utc>,
+}
+
+#[cfg(feature = "server")]
+impl BrandVoice {
+    /// The length limit for an OG field, if the voice sets one.
+    pub fn max_chars(&self, field: &str) -> Option<usize> {
+        let max = match field {
+            "title" => self.max_title_chars,
+            "description" => self.max_description_chars,
+            _ => 0,
+        };
+        (max > 0).then_some(max as usize)
+    }
+
+    /// Banned words appearing in `text` as whole words, ignoring case. A
+    /// word must not touch letters or digits on either side, which also
+    /// works for terms such as "c++" that start or end with punctuation.
+    pub fn banned_in(&self, text: &str) -> Vec<String> {
+        self.banned_words
+            .iter()
+            .filter(|word| {
+                Regex::new(&format!(r"(?i)(?:^|\W){}(?:\W|$)", escape(word)))
+                    .map(|pattern| pattern.is_match(text))
+                    .unwrap_or(false)
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Why `text`, written for the OG `field`, breaks this voice's hard rules.
+    pub fn violations(&self, field: &str, text: &str) -> Vec<String> {
+        let mut violations = Vec::new();
+        let length = text.chars().count();
+        if let Some(max) = self.max_chars(field).filter(|max| length > *max) {
+            violations.push(format!("{length} characters, over the {max} limit"));
+        }
+        for word in self.banned_in(text) {
+            violations.push(format!("uses the banned word \"{word}\""));
+        }
+        violations
+    }
+}
+
+/// Splits a comma or newline separated list, dropping blanks.
+pub fn split_terms(raw: &str) -> Vec<String> {
+    raw.split([',', '\n'])
+        .map(|term| term.trim().to_string())
+        .filter(|term| !term.is_empty())
+        .collect()
+}
+
+#[cfg(all(test, feature = "server"))]
+mod tests {
+    use super::{split_terms, BrandVoice};
+    use bson::oid::ObjectId;
+    use chrono::Utc;
+
+    fn voice(banned: &[&str]) -> BrandVoice {
+        BrandVoice {
+            id: ObjectId::new(),
+            user: ObjectId::new(),
+            name: "Crisp".into(),
+            tone: String::new(),
+            banned_words: banned.iter().map(|word| word.to_string()).collect(),
+            preferred_terms: Vec::new(),
+            example_copy: String::new(),
+            max_title_chars: 20,
+            max_description_chars: 0,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn bans_whole_words_only() {
+        let voice = voice(&["cheap", "synergy"]);
+        assert_eq!(voice.banned_in("Cheap, fast and good"), vec!["cheap"]);
+        assert!(voice.banned_in("Cheapest prices").is_empty());
+        assert_eq!(voice.banned_in("pure SYNERGY"), vec!["synergy"]);
+    }
+
+    #[test]
+    fn bans_terms_with_punctuation_edges() {
+        let voice = voice(&["c++", ".net", "#1"]);
+        assert_eq!(voice.banned_in("Learn C++ today"), vec!["c++"]);
+        assert_eq!(voice.banned_in("c++"), vec!["c++"]);
+        assert_eq!(voice.banned_in("Built on .NET."), vec![".net"]);
+        assert_eq!(voice.banned_in("The #1 tool"), vec!["#1"]);
+        assert!(voice.banned_in("c++x and abc++").is_empty());
+    }
+
+    #[test]
+    fn reports_length_and_banned_words() {
+        let voice = voice(&["cheap"]);
+        assert!(voice.violations("title", "Short title").is_empty());
+        assert_eq!(
+            voice.violations("title", "A cheap title that runs long"),
+            vec![
+                "28 characters, over the 20 limit".to_string(),
+                "uses the banned word \"cheap\"".to_string(),
+            ]
+        );
+        assert!(voice
+            .violations("description", &"long ".repeat(100))
+            .is_empty());
+    }
+
+    #[test]
+    fn splits_term_lists() {
+        assert_eq!(
+            split_terms("a, b\n\n c ,"),
+            vec!["a".to_string(), "b".to_string(), "c".to_string()]
+        );
+    }
+}
@@ -0,0 +1,20 @@
+use bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SaveVoiceRequest {
+    /// `None` creates a new profile.
+    pub voice_id: Option<ObjectId>,
+    pub name: String,
+    pub tone: String,
+    pub banned_words: Vec<String>,
+    pub preferred_terms: Vec<String>,
+    pub example_copy: String,
+    pub max_title_chars: u32,
+    pub max_description_chars: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DeleteVoiceRequest {
+    pub voice_id: ObjectId,
+}
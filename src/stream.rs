@@ -0,0 +1,289 @@
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use dioxus_logger::tracing;
+use futures_util::future::join;
+use futures_util::stream;
+use serde::Serialize;
+use std::convert::Infallible;
+use std::future::Future;
+use std::time::Instant;
+use tokio::sync::mpsc;
+
+use crate::llm::{get_llm, LlmError};
+use crate::server::auth::guard::authenticate_headers;
+use crate::server::auth::model::Permission;
+use crate::server::conversation::controller::{prepare_chat, save_reply, CHAT_TEMPERATURE};
+use crate::server::conversation::request::SendQueryRequest;
+use crate::server::moderation::controller::classify_for;
+use crate::server::moderation::model::{ModerationAction, Verdict};
+use crate::server::usage::controller::{check_quota, prompt_tokens, record_usage};
+use crate::server::usage::model::AIFeature;
+
+/// Tokens buffered between the provider and the client. Both channels are
+/// bounded, so a slow client holds back the upstream read instead of piling
+/// the reply up in memory.
+const STREAM_BUFFER: usize = 32;
+/// Bytes of reply held back before a chunk is moderated and shown. Larger
+/// chunks mean fewer moderation calls but a choppier stream.
+const MODERATION_CHUNK: usize = 160;
+
+/// Streams the assistant's reply to a chat query as server-sent events: a
+/// `query` event with the stored user message, `token` events carrying text
+/// deltas, then one `done` event with the stored reply or an `error` event.
+/// Every payload is JSON. Tokens are held back and moderated a few sentences
+/// at a time, and generation stops at the first blocked chunk. Dropping the
+/// connection stops generation; whatever was produced so far is saved.
+/// Streamed text cannot be retried, so a brand voice is enforced after the
+/// fact: breaking edit fields are dropped and banned words in the text are
+/// called out in a note on the saved reply.
+pub async fn stream_query(headers: HeaderMap, Json(req): Json<SendQueryRequest>) -> Response {
+    let user = match authenticate_headers(&headers).await {
+        Ok(user) if user.role.can(Permission::ManageOwnContent) => user,
+        Ok(_) => return (StatusCode::FORBIDDEN, "Not Authorized").into_response(),
+        Err(_) => return (StatusCode::UNAUTHORIZED, "Not Authenticated").into_response(),
+    };
+    if let Err(err) = check_quota(&user).await {
+        return (StatusCode::TOO_MANY_REQUESTS, err.to_string()).into_response();
+    }
+    let chat = match prepare_chat(&user, &req).await {
+        Ok(chat) => chat,
+        Err(err) => return (StatusCode::BAD_REQUEST, err.to_string()).into_response(),
+    };
+
+    let (conversation, parent, og, voice) = (chat.conversation, chat.parent, chat.og, chat.voice);
+    let (prompt, max_tokens) = (chat.prompt, chat.max_tokens);
+
+    let (events, rx) = mpsc::channel::<Event>(STREAM_BUFFER);
+    if let Some(query) = &chat.query {
+        let _ = events.try_send(event("query", query));
+    }
+    tokio::spawn(async move {
+        let started = Instant::now();
+        let prompt_tokens = prompt_tokens(&prompt);
+        let model = req.model.clone();
+        let (tokens, mut token_rx) = mpsc::channel::<String>(STREAM_BUFFER);
+        let generate = async move {
+            get_llm()
+                .await
+                .stream(
+                    Some(&req.model),
+                    prompt,
+                    CHAT_TEMPERATURE,
+                    max_tokens,
+                    &tokens,
+                )
+                .await
+        };
+        let forward = async {
+            let (user, events) = (&user, &events);
+            let classify = move |chunk: String| async move {
+                classify_for(user, &[&chunk])
+                    .await
+                    .pop()
+                    .unwrap_or_default()
+            };
+            let content = forward_moderated(&mut token_rx, classify, move |chunk| async move {
+                events.send(event("token", &chunk)).await.is_ok()
+            })
+            .await;
+            // Closing the receiver makes the provider stop with `Cancelled`.
+            drop(token_rx);
+            content
+        };
+        let (result, content) = join(generate, forward).await;
+
+        match result {
+            Err(LlmError::Cancelled) => tracing::info!("chat stream cancelled by client"),
+            Err(ref err) => tracing::warn!("chat stream failed: {err}"),
+            Ok(()) => {}
+        }
+        let outcome = match &result {
+            Err(err) if content.trim().is_empty() => Err(err.to_string()),
+            _ => Ok(content.as_str()),
+        };
+        record_usage(
+            &user,
+            AIFeature::Chat,
+            Some(&model),
+            prompt_tokens,
+            outcome,
+            started,
+        )
+        .await;
+        if content.trim().is_empty() {
+            let message = match result {
+                Err(err) => err.to_string(),
+                Ok(()) => "The AI returned an empty reply".into(),
+            };
+            let _ = events.send(event("error", &message)).await;
+            return;
+        }
+
+        match save_reply(&user, conversation, parent, &og, voice.as_ref(), &content).await {
+            Ok(message) => {
+                let _ = events.send(event("done", &message)).await;
+            }
+            Err(err) => {
+                tracing::error!("failed to save streamed reply: {err}");
+                let _ = events.send(event("error", &err.to_string())).await;
+            }
+        }
+    });
+
+    let body = stream::unfold(rx, |mut rx| async move {
+        rx.recv()
+            .await
+            .map(|event| (Ok::<_, Infallible>(event), rx))
+    });
+    Sse::new(body)
+        .keep_alive(KeepAlive::default())
+        .into_response()
+}
+
+/// Passes the reply on to `send` a chunk at a time, each checked by
+/// moderation first, and returns all the text received. Stops at the first
+/// blocked chunk, or once `send` reports the client gone; the blocked text is
+/// still returned, so saving the reply replaces it with a notice.
+async fn forward_moderated<C, F>(
+    tokens: &mut mpsc::Receiver<String>,
+    mut classify: impl FnMut(String) -> C,
+    mut send: impl FnMut(String) -> F,
+) -> String
+where
+    C: Future<Output = Verdict>,
+    F: Future<Output = bool>,
+{
+    let mut content = String::new();
+    let mut shown = 0;
+    loop {
+        let token = tokens.recv().await;
+        let finished = token.is_none();
+        if let Some(token) = token {
+            content.push_str(&token);
+        }
+        let pending = &content[shown..];
+        let end = if finished {
+            Some(pending.len())
+        } else {
+            chunk_end(pending)
+        };
+        if let Some(end) = end.filter(|end| *end > 0) {
+            let chunk = &pending[..end];
+            if classify(chunk.to_string()).await.action == ModerationAction::Block {
+                tracing::info!("chat stream stopped by moderation");
+                break;
+            }
+            if !send(chunk.to_string()).await {
+                break;
+            }
+            shown += end;
+        }
+        if finished {
+            break;
+        }
+    }
+    content
+}
+
+/// How much of the text held back can be checked and shown: up to its last
+/// sentence or line end once there is [`MODERATION_CHUNK`] of it, or all of
+/// it once it runs long without one.
+fn chunk_end(pending: &str) -> Option<usize> {
+    if pending.len() < MODERATION_CHUNK {
+        return None;
+    }
+    pending
+        .rmatch_indices(['\n', '.', '!', '?'])
+        .next()
+        .map(|(index, mark)| index + mark.len())
+        .or((pending.len() >= MODERATION_CHUNK * 4).then_some(pending.len()))
+}
+
+fn event(kind: &str, data: &impl Serialize) -> Event {
+    Event::default()
+        .event(kind)
+        .json_data(data)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{chunk_end, forward_moderated, MODERATION_CHUNK};
+    use crate::moderation::{ModerationConfig, Moderator};
+    use std::time::Duration;
+    use tokio::sync::mpsc;
+
+    fn moderator() -> Moderator {
+        Moderator::new(ModerationConfig {
+            provider: "none".into(),
+            base_url: String::new(),
+            api_key: None,
+            model: String::new(),
+            timeout: Duration::from_secs(1),
+            blocked_terms: vec!["forbidden plan".into()],
+            flagged_terms: Vec::new(),
+        })
+    }
+
+    async fn stream(tokens: &[&str]) -> (String, Vec<String>) {
+        let (tx, mut rx) = mpsc::channel(tokens.len() + 1);
+        for token in tokens {
+            tx.send(token.to_string()).await.unwrap();
+        }
+        drop(tx);
+        let (sent_tx, mut sent_rx) = mpsc::unbounded_channel();
+        let moderator = moderator();
+        let classify = |chunk: String| {
+            let moderator = &moderator;
+            async move {
+                let classification = moderator.classify_many(&[&chunk]).await;
+                classification
+                    .verdicts
+                    .into_iter()
+                    .next()
+                    .unwrap_or_default()
+            }
+        };
+        let content = forward_moderated(&mut rx, classify, |chunk| {
+            let sent = sent_tx.send(chunk).is_ok();
+            async move { sent }
+        })
+        .await;
+        drop(sent_tx);
+        let mut sent = Vec::new();
+        while let Some(chunk) = sent_rx.recv().await {
+            sent.push(chunk);
+        }
+        (content, sent)
+    }
+
+    #[test]
+    fn holds_text_back_until_a_sentence_ends() {
+        assert_eq!(chunk_end("Short. "), None);
+        let sentence = format!("{}. Then", "a".repeat(MODERATION_CHUNK));
+        assert_eq!(chunk_end(&sentence), Some(MODERATION_CHUNK + 1));
+        let unbroken = "a".repeat(MODERATION_CHUNK * 4);
+        assert_eq!(chunk_end(&unbroken), Some(unbroken.len()));
+        assert_eq!(chunk_end(&unbroken[1..]), None);
+    }
+
+    #[tokio::test]
+    async fn forwards_clean_replies_whole() {
+        let words = vec!["Try a shorter title. "; 20];
+        let (content, sent) = stream(&words).await;
+        assert_eq!(content, words.concat());
+        assert_eq!(sent.concat(), content);
+        assert!(sent.len() > 1);
+    }
+
+    #[tokio::test]
+    async fn stops_before_showing_blocked_text() {
+        let clean = "Try a shorter title. ".repeat(10);
+        let (content, sent) = stream(&[&clean, "Here is the forbidden plan. ", &clean]).await;
+        assert!(content.contains("forbidden plan"));
+        assert!(sent.iter().all(|chunk| !chunk.contains("forbidden")));
+        assert_eq!(sent.concat(), clean.trim_end());
+    }
+}